@@ -23,8 +23,8 @@ fn main() {
     let pass = PassBuilder::new(PassConfig {
         organization_name: "Dodo Airlines".into(),
         description: "DAL Boarding Pass".into(),
-        pass_type_identifier: "com.example.pass".into(),
-        team_identifier: "AA00AA0A0A".into(),
+        pass_type_identifier: "pass.com.example".parse().unwrap(),
+        team_identifier: "AA00AA0A0A".parse().unwrap(),
         serial_number: "ABCDEFG1234567890".into(),
     })
     .appearance(visual_appearance::VisualAppearance {
@@ -138,9 +138,9 @@ fn main() {
             longitude: 132.1451673,
         }
         .into(),
-        original_boarding_date: time_to_boarding.into(),
-        original_departure_date: time_to_departure.into(),
-        original_arrival_date: time_to_arrive.into(),
+        original_boarding_date: time_to_boarding.fixed_offset().into(),
+        original_departure_date: time_to_departure.fixed_offset().into(),
+        original_arrival_date: time_to_arrive.fixed_offset().into(),
         seats: vec![semantic_tags::SemanticTagSeat {
             seat_identifier: String::from("20A").into(),
             seat_number: String::from("A").into(),
@@ -215,5 +215,6 @@ fn setup_sign_config(cert: &str, key: &str) -> SignConfig {
     let mut sign_cert_key_data = Vec::new();
     std::io::Read::read_to_end(&mut file_sign_key_cert, &mut sign_cert_key_data).unwrap();
 
-    SignConfig::new(sign::WWDR::G4, &sign_cert_data, &sign_cert_key_data).unwrap()
+    let pem_str = std::str::from_utf8(&sign_cert_key_data).unwrap();
+    SignConfig::new(&sign::WWDR::G4, &sign_cert_data, pem_str).unwrap()
 }
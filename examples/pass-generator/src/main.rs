@@ -8,8 +8,8 @@ fn main() {
     let pass = PassBuilder::new(PassConfig {
         organization_name: "Test organization".into(),
         description: "Super gentlememe pass".into(),
-        pass_type_identifier: "com.example.pass".into(),
-        team_identifier: "AA00AA0A0A".into(),
+        pass_type_identifier: "pass.com.example".parse().unwrap(),
+        team_identifier: "AA00AA0A0A".parse().unwrap(),
         serial_number: "ABCDEFG1234567890".into(),
     })
     .grouping_identifier(String::from("com.example.pass.app"))
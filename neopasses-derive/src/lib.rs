@@ -0,0 +1,309 @@
+//! Derive macro for [neopasses](https://docs.rs/neopasses), see `neopasses::fields::PassFields`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Fields, GenericArgument, LitBool, LitStr, PathArguments, Type,
+    parse_macro_input,
+};
+
+/// Implements `neopasses::fields::PassFields` for a struct with named fields.
+///
+/// Field attributes `#[pass(...)]`:
+///
+/// * `section = "header" | "primary" | "secondary" | "auxiliary" | "back"`, can be set for the whole struct
+/// * `key = "..."`, name of the struct field by default
+/// * `label = "..."`, `change_message = "..."`
+/// * `date_style = "..."`, `time_style = "..."` - `none`, `short`, `medium`, `long` or `full`
+/// * `number_style = "..."` - `decimal`, `percent`, `scientific` or `spell_out`
+/// * `currency = "USD"` - value is a currency amount
+/// * `text_alignment = "..."` - `left`, `center`, `right` or `natural`
+/// * `ignores_time_zone`, `is_relative`
+/// * `skip` - not a pass field
+///
+/// Fields of type `Option<T>` are added only when they have a value.
+/// Field types must convert into `FieldValue`: strings, `f32`, `f64`, `i32`, `u32`, `i64`, `u64`
+/// and dates, `currency` fields must convert into `f64`.
+#[proc_macro_derive(PassFields, attributes(pass))]
+pub fn derive_pass_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "PassFields can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "PassFields requires a struct with named fields",
+        ));
+    };
+
+    let defaults = FieldAttrs::parse(&input.attrs, None)?;
+    let mut inserts = Vec::new();
+    for field in &named.named {
+        let attrs = FieldAttrs::parse(&field.attrs, Some(&defaults))?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        inserts.push(attrs.expand(ident, &field.ty)?);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::neopasses::fields::PassFields for #name #ty_generics #where_clause {
+            fn append_to(
+                &self,
+                fields: &mut ::neopasses::fields::Type,
+            ) -> ::core::result::Result<(), ::neopasses::fields::FieldsError> {
+                // Fields are changed only when every insert succeeds
+                let mut appended = ::core::clone::Clone::clone(fields);
+                {
+                    let fields = &mut appended;
+                    #(#inserts)*
+                }
+                *fields = appended;
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Parsed `#[pass(...)]` attributes
+#[derive(Default)]
+struct FieldAttrs {
+    section: Option<TokenStream2>,
+    key: Option<LitStr>,
+    label: Option<LitStr>,
+    change_message: Option<LitStr>,
+    date_style: Option<TokenStream2>,
+    time_style: Option<TokenStream2>,
+    number_style: Option<TokenStream2>,
+    currency: Option<LitStr>,
+    text_alignment: Option<TokenStream2>,
+    ignores_time_zone: Option<bool>,
+    is_relative: Option<bool>,
+    skip: bool,
+}
+
+impl FieldAttrs {
+    /// Attributes of struct (`defaults` is `None`) or of its field
+    fn parse(attrs: &[Attribute], defaults: Option<&FieldAttrs>) -> syn::Result<Self> {
+        let mut parsed = FieldAttrs {
+            section: defaults.and_then(|defaults| defaults.section.clone()),
+            ..Default::default()
+        };
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("pass")) {
+            attr.parse_nested_meta(|meta| {
+                let name = meta
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                if defaults.is_none() && name != "section" {
+                    return Err(meta.error("only `section` can be set for the struct"));
+                }
+                match name.as_str() {
+                    "section" => {
+                        let value: LitStr = meta.value()?.parse()?;
+                        parsed.section = Some(variant(&value, "Section", SECTIONS)?);
+                    }
+                    "key" => parsed.key = Some(meta.value()?.parse()?),
+                    "label" => parsed.label = Some(meta.value()?.parse()?),
+                    "change_message" => parsed.change_message = Some(meta.value()?.parse()?),
+                    "date_style" => {
+                        let value: LitStr = meta.value()?.parse()?;
+                        parsed.date_style = Some(variant(&value, "DateStyle", DATE_STYLES)?);
+                    }
+                    "time_style" => {
+                        let value: LitStr = meta.value()?.parse()?;
+                        parsed.time_style = Some(variant(&value, "DateStyle", DATE_STYLES)?);
+                    }
+                    "number_style" => {
+                        let value: LitStr = meta.value()?.parse()?;
+                        parsed.number_style = Some(variant(&value, "NumberStyle", NUMBER_STYLES)?);
+                    }
+                    "currency" => parsed.currency = Some(meta.value()?.parse()?),
+                    "text_alignment" => {
+                        let value: LitStr = meta.value()?.parse()?;
+                        parsed.text_alignment =
+                            Some(variant(&value, "TextAlignment", TEXT_ALIGNMENTS)?);
+                    }
+                    "ignores_time_zone" => parsed.ignores_time_zone = Some(flag(&meta)?),
+                    "is_relative" => parsed.is_relative = Some(flag(&meta)?),
+                    "skip" => parsed.skip = true,
+                    _ => return Err(meta.error("unknown pass attribute")),
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+
+    /// Code inserting the struct field into `fields`
+    fn expand(&self, ident: &syn::Ident, ty: &Type) -> syn::Result<TokenStream2> {
+        let Some(section) = &self.section else {
+            return Err(syn::Error::new(
+                ident.span(),
+                "missing `#[pass(section = \"...\")]` for the field or the struct",
+            ));
+        };
+        if self.currency.is_some() && (self.number_style.is_some() || self.date_style.is_some()) {
+            return Err(syn::Error::new(
+                ident.span(),
+                "`currency` can't be combined with `number_style` or `date_style`",
+            ));
+        }
+
+        let key = match &self.key {
+            Some(key) => key.clone(),
+            None => LitStr::new(&ident.to_string(), ident.span()),
+        };
+        let string = |value: &Option<LitStr>| match value {
+            Some(value) => {
+                quote!(::core::option::Option::Some(::std::string::String::from(#value)))
+            }
+            None => quote!(::core::option::Option::None),
+        };
+        let option = |value: &Option<TokenStream2>| match value {
+            Some(value) => quote!(::core::option::Option::Some(#value)),
+            None => quote!(::core::option::Option::None),
+        };
+        let boolean = |value: Option<bool>| match value {
+            Some(value) => quote!(::core::option::Option::Some(#value)),
+            None => quote!(::core::option::Option::None),
+        };
+        let label = string(&self.label);
+        let change_message = string(&self.change_message);
+        let currency_code = string(&self.currency);
+        let date_style = option(&self.date_style);
+        let time_style = option(&self.time_style);
+        let number_style = option(&self.number_style);
+        let text_alignment = option(&self.text_alignment);
+        let ignores_time_zone = boolean(self.ignores_time_zone);
+        let is_relative = boolean(self.is_relative);
+
+        let field_value = match self.currency {
+            Some(_) => quote! {
+                ::neopasses::fields::FieldValue::Currency(
+                    ::core::convert::Into::<f64>::into(::core::clone::Clone::clone(value))
+                )
+            },
+            None => quote! {
+                ::neopasses::fields::FieldValue::from(::core::clone::Clone::clone(value))
+            },
+        };
+        let insert = quote! {
+            fields.insert(
+                #section,
+                ::neopasses::fields::Content::new(
+                    #key,
+                    #field_value,
+                    ::neopasses::fields::ContentOptions {
+                        label: #label,
+                        change_message: #change_message,
+                        currency_code: #currency_code,
+                        date_style: #date_style,
+                        time_style: #time_style,
+                        number_style: #number_style,
+                        text_alignment: #text_alignment,
+                        ignores_time_zone: #ignores_time_zone,
+                        is_relative: #is_relative,
+                        ..::core::default::Default::default()
+                    },
+                ),
+            )?;
+        };
+
+        Ok(if is_option(ty) {
+            quote! {
+                if let ::core::option::Option::Some(value) = &self.#ident {
+                    #insert
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#ident;
+                #insert
+            }
+        })
+    }
+}
+
+const SECTIONS: &[(&str, &str)] = &[
+    ("header", "Header"),
+    ("primary", "Primary"),
+    ("secondary", "Secondary"),
+    ("auxiliary", "Auxiliary"),
+    ("back", "Back"),
+];
+
+const DATE_STYLES: &[(&str, &str)] = &[
+    ("none", "None"),
+    ("short", "Short"),
+    ("medium", "Medium"),
+    ("long", "Long"),
+    ("full", "Full"),
+];
+
+const NUMBER_STYLES: &[(&str, &str)] = &[
+    ("decimal", "Decimal"),
+    ("percent", "Percent"),
+    ("scientific", "Scientific"),
+    ("spell_out", "SpellOut"),
+];
+
+const TEXT_ALIGNMENTS: &[(&str, &str)] = &[
+    ("left", "Left"),
+    ("center", "Center"),
+    ("right", "Right"),
+    ("natural", "Natural"),
+];
+
+/// Path to variant of `neopasses::fields` enum named by `value`
+fn variant(value: &LitStr, ty: &str, variants: &[(&str, &str)]) -> syn::Result<TokenStream2> {
+    let name = value.value();
+    let Some((_, variant)) = variants.iter().find(|(lower, _)| *lower == name) else {
+        let expected: Vec<&str> = variants.iter().map(|(lower, _)| *lower).collect();
+        return Err(syn::Error::new(
+            value.span(),
+            format!("expected one of: {}", expected.join(", ")),
+        ));
+    };
+    let ty = format_ident!("{}", ty);
+    let variant = format_ident!("{}", variant);
+    Ok(quote!(::neopasses::fields::#ty::#variant))
+}
+
+/// Boolean attribute, `name` or `name = true`
+fn flag(meta: &syn::meta::ParseNestedMeta) -> syn::Result<bool> {
+    if meta.input.peek(syn::Token![=]) {
+        let value: LitBool = meta.value()?.parse()?;
+        Ok(value.value)
+    } else {
+        Ok(true)
+    }
+}
+
+/// Checks that type is `Option<T>`
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Option"
+                && matches!(&segment.arguments, PathArguments::AngleBracketed(args)
+                    if args.args.len() == 1 && matches!(args.args[0], GenericArgument::Type(_)))
+        })
+}
@@ -0,0 +1,63 @@
+use std::{fs, process::ExitCode};
+
+use neopasses::Pass;
+use neopasses::fields::DeviceClass;
+
+const USAGE: &str = "Usage: neopasses-cli <command>
+
+Commands:
+  schema [output]    Print JSON Schema of pass.json, or write it to output file
+  layout <pass.json> Print fields likely to be truncated or wrapped, fails if there are any";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["schema"] => {
+            println!("{}", schema());
+            ExitCode::SUCCESS
+        }
+        ["schema", output] => match fs::write(output, schema()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("couldn't write {}: {}", output, err);
+                ExitCode::FAILURE
+            }
+        },
+        ["layout", path] => layout(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Pretty printed JSON Schema of pass.json
+fn schema() -> String {
+    serde_json::to_string_pretty(&Pass::json_schema()).expect("schema is valid JSON")
+}
+
+/// Prints layout issues of pass.json on all device classes
+fn layout(path: &str) -> ExitCode {
+    let pass = match fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|data| Pass::from_json(&data).map_err(|err| err.to_string()))
+    {
+        Ok(pass) => pass,
+        Err(err) => {
+            eprintln!("couldn't read {}: {}", path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let issues = pass.fields.estimate_layout(&DeviceClass::ALL);
+    for issue in &issues {
+        println!("{}", issue);
+    }
+    if issues.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,7 +1,13 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 use x509_cert::der;
 use zip::result::ZipError;
 
+use crate::pass::barcode::MessageEncoding;
+use crate::pass::fields::{ContentError, Section};
+
 #[derive(Error, Debug)]
 pub enum PassError {
     #[error("missing pass.json in package file")]
@@ -22,6 +28,247 @@ pub enum PassError {
     CertificateParse(rsa::pkcs8::Error),
     #[error("Certificate has expired")]
     CertificateExpired,
+    #[error("failed to decode image: {0}")]
+    ImageDecode(png::DecodingError),
+    #[error("image has no opaque pixels")]
+    ImageEmpty,
+    #[error("missing background, strip or logo image in package")]
+    MissingImage,
+    #[error("failed to render preview: {0}")]
+    Render(String),
+}
+
+/// Constraint violations found while building a pass
+///
+/// All violations are collected, so they can be reported at once.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("invalid pass: {}", .violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+pub struct BuildError {
+    pub violations: Vec<Violation>,
+}
+
+/// Single constraint violation of a pass
+///
+/// The transit type of boarding passes is part of [Type::BoardingPass](crate::fields::Type::BoardingPass),
+/// and colors are stored as RGB components, so neither can be invalid in a built pass.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum Violation {
+    #[error("too many locations: {count} (maximum - {max})")]
+    TooManyLocations { count: usize, max: usize },
+    #[error("too many beacons: {count} (maximum - {max})")]
+    TooManyBeacons { count: usize, max: usize },
+    #[error("field key `{0}` is used more than once")]
+    DuplicateFieldKey(String),
+    #[error("field `{key}`: text alignment is not allowed in {section:?} fields")]
+    TextAlignmentNotAllowed { key: String, section: Section },
+    #[error("relevant date #{index} needs `date`, or `startDate` not after `endDate`")]
+    InvalidRelevantDate { index: usize },
+    #[error("barcode #{index}: {error}")]
+    InvalidBarcode { index: usize, error: BarcodeError },
+    #[error(transparent)]
+    InvalidField(ContentError),
+}
+
+impl From<ContentError> for Violation {
+    fn from(err: ContentError) -> Self {
+        Violation::InvalidField(err)
+    }
+}
+
+/// Invalid value of a strongly-typed pass property
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValueError {
+    #[error("team identifier must be 10 alphanumeric characters, got `{0}`")]
+    TeamId(String),
+    #[error("pass type identifier must be a reverse-DNS string starting with `pass.`, got `{0}`")]
+    PassTypeId(String),
+    #[error("App Store identifier must be a non-negative integer, got `{0}`")]
+    AppStoreId(String),
+    #[error("proximity UUID must be in form XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX, got `{0}`")]
+    ProximityUuid(String),
+    #[error("latitude must be in range -90..=90 degrees, got {0}")]
+    Latitude(f64),
+    #[error("longitude must be in range -180..=180 degrees, got {0}")]
+    Longitude(f64),
+    #[error("coordinates must be in form `latitude,longitude`, got `{0}`")]
+    Coordinates(String),
+    #[error("Invalid color format: `{0}`")]
+    ColorFormat(String),
+    #[error("Invalid color arguments: `{0}`")]
+    ColorArguments(String),
+    #[error("unknown lint rule `{0}`")]
+    LintRule(String),
+    #[error("unknown message encoding `{0}`")]
+    MessageEncoding(String),
+}
+
+/// Error rendering barcode image
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BarcodeError {
+    #[error("message can't be encoded in `{0}`")]
+    Unencodable(MessageEncoding),
+    #[error("unknown message encoding `{0}`")]
+    UnknownEncoding(String),
+    #[error("message is {bytes} bytes, maximum is {max}")]
+    TooLong { bytes: usize, max: usize },
+    #[error("module size must be at least 1 pixel")]
+    ModuleSize,
+    #[error("barcode image is too large")]
+    ImageTooLarge,
+    #[error("failed to encode barcode: {0}")]
+    Encode(String),
+}
+
+/// Error signing or verifying signed barcode payload
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SignedPayloadError {
+    #[error("malformed signed payload")]
+    Malformed,
+    #[error("unsupported signature algorithm `{0}`")]
+    Algorithm(String),
+    #[error("payload is signed with another algorithm than the key")]
+    KeyMismatch,
+    #[error("invalid signature")]
+    Signature,
+    #[error("payload is not valid before {}", .0.to_rfc3339())]
+    NotYetValid(DateTime<Utc>),
+    #[error("payload expired at {}", .0.to_rfc3339())]
+    Expired(DateTime<Utc>),
+    #[error("payload is for pass type `{0}`")]
+    PassType(String),
+}
+
+/// Error encoding or decoding IATA Bar Coded Boarding Pass
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BcbpError {
+    #[error("BCBP must be ASCII text starting with format code `M`")]
+    FormatCode,
+    #[error("BCBP ends in {0}")]
+    Truncated(&'static str),
+    #[error("invalid {item}: `{value}`")]
+    InvalidItem { item: &'static str, value: String },
+    #[error("{item} is longer than {max} characters: `{value}`")]
+    TooLong {
+        item: &'static str,
+        max: usize,
+        value: String,
+    },
+    #[error("BCBP has 1 to 9 legs, got {0}")]
+    LegCount(usize),
+    #[error("BCBP has no leg with index {0}")]
+    NoSuchLeg(usize),
+    #[error("semantic tag `{0}` is required")]
+    MissingSemantic(&'static str),
+}
+
+/// Error reading or writing pass in YAML or TOML
+#[derive(Error, Debug)]
+pub enum FormatError {
+    /// Syntax errors point to the exact position. Pass keys are flattened,
+    /// so invalid values in them point to the start of the enclosing mapping.
+    #[error("{message} at line {line}, column {column}")]
+    Located {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("couldn't read `{}`: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("`{}` includes itself", .0.display())]
+    IncludeCycle(PathBuf),
+    #[error("`$include` is only resolved when reading files")]
+    IncludeNotAllowed,
+    #[error("`{}` is outside the directory of the pass file", .0.display())]
+    IncludeOutside(PathBuf),
+    #[error("in `{}`: {source}", .path.display())]
+    InFile {
+        path: PathBuf,
+        source: Box<FormatError>,
+    },
+    #[error("failed to serialize: {0}")]
+    Serialize(String),
+}
+
+impl FormatError {
+    /// Adds file to error, errors of reading already have it
+    pub(crate) fn in_file(self, path: &Path) -> Self {
+        match self {
+            FormatError::Read { .. } | FormatError::IncludeCycle(_) => self,
+            source => FormatError::InFile {
+                path: path.to_path_buf(),
+                source: Box::new(source),
+            },
+        }
+    }
+}
+
+impl From<serde_yaml_ng::Error> for FormatError {
+    fn from(err: serde_yaml_ng::Error) -> Self {
+        match err.location() {
+            Some(location) => FormatError::Located {
+                // Message of YAML error ends with position
+                message: err
+                    .to_string()
+                    .split(" at line ")
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                line: location.line(),
+                column: location.column(),
+            },
+            None => FormatError::Invalid(err.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(err: serde_json::Error) -> Self {
+        if err.line() == 0 {
+            return FormatError::Invalid(err.to_string());
+        }
+        FormatError::Located {
+            message: err
+                .to_string()
+                .split(" at line ")
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            line: err.line(),
+            column: err.column(),
+        }
+    }
+}
+
+/// Error applying JSON Patch or JSON Merge Patch to a pass
+#[derive(Error, Debug)]
+pub enum PatchError {
+    #[error("invalid patch document: {0}")]
+    Format(serde_json::Error),
+    #[error("failed to serialize pass: {0}")]
+    Serialize(serde_json::Error),
+    #[error("failed to apply patch: {0}")]
+    Patch(json_patch::PatchError),
+    #[error("patched pass.json is not a pass: {0}")]
+    Invalid(serde_json::Error),
+    #[error("patched {0}")]
+    Violations(BuildError),
+}
+
+impl From<json_patch::PatchError> for PatchError {
+    fn from(err: json_patch::PatchError) -> Self {
+        PatchError::Patch(err)
+    }
+}
+
+impl From<BuildError> for PatchError {
+    fn from(err: BuildError) -> Self {
+        PatchError::Violations(err)
+    }
 }
 
 impl From<rsa::pkcs8::Error> for PassError {
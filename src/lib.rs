@@ -16,8 +16,8 @@
 //! let pass = PassBuilder::new(PassConfig {
 //!     organization_name: "Test organization".into(),
 //!     description: "Test description for pass".into(),
-//!     pass_type_identifier: "com.example.pass".into(),
-//!     team_identifier: "AA00AA0A0A".into(),
+//!     pass_type_identifier: "pass.com.example".parse().unwrap(),
+//!     team_identifier: "AA00AA0A0A".parse().unwrap(),
 //!     serial_number: "ABCDEFG1234567890".into(),
 //! })
 //! .grouping_identifier(String::from("com.example.pass.app"))
@@ -70,13 +70,20 @@
 //! ```
 //!
 //! For more examples, see [example directory](https://github.com/mvodya/passes-rs/tree/main/examples) on GitHub.
+
+// Lets `#[derive(PassFields)]` refer to `::neopasses` inside this crate
+extern crate self as neopasses;
+
 // Primary modules
 mod error;
 mod package;
 mod pass;
 
 // Re-exports
-pub use self::error::PassError;
+pub use self::error::{
+    BarcodeError, BcbpError, BuildError, FormatError, PassError, PatchError, SignedPayloadError, ValueError,
+    Violation,
+};
 pub use self::package::*;
 pub use self::pass::*;
 // Python bindings module
@@ -5,14 +5,19 @@ use std::{
 
 use crate::pass::Pass;
 use crate::error::PassError;
+use crate::pass::diff::PassDiff;
+use crate::pass::migration::JsonOptions;
 use sha2::Digest;
 use x509_cert::der::Encode;
 
 use self::{manifest::Manifest, resource::Resource, sign::SignConfig};
 
 pub mod manifest;
+pub mod palette;
+pub mod render;
 pub mod resource;
 pub mod sign;
+pub mod validation;
 
 /// Pass Package, contains information about pass.json, images, manifest.json and signature.
 pub struct Package {
@@ -24,6 +29,9 @@ pub struct Package {
 
     // Certificates for signing package
     pub sign_config: Option<SignConfig>,
+
+    /// Options for writing pass.json
+    pub json_options: JsonOptions,
 }
 
 impl Package {
@@ -33,6 +41,7 @@ impl Package {
             pass,
             resources: vec![],
             sign_config: None,
+            json_options: JsonOptions::default(),
         }
     }
 
@@ -78,6 +87,7 @@ impl Package {
                 pass,
                 resources,
                 sign_config: None,
+                json_options: JsonOptions::default(),
             })
         } else {
             Err("pass.json is missed in package file")
@@ -98,7 +108,7 @@ impl Package {
 
     /// Write compressed package.
     ///
-    /// Use for creating .pkpass file
+    /// Use for creating .pkpass file. The package is not checked, see [validate](Package::validate).
     pub fn write<W: Write + Seek>(&mut self, writer: W) -> Result<(), &'static str> {
         let mut manifest = Manifest::new();
 
@@ -111,7 +121,7 @@ impl Package {
             .expect("Error while creating pass.json in zip");
         let pass_json = self
             .pass
-            .make_json()
+            .make_json_with(&self.json_options)
             .expect("Error while building pass.json");
         zip.write_all(pass_json.as_bytes())
             .expect("Error while writing pass.json in zip");
@@ -245,13 +255,99 @@ impl Package {
         self.resources.push(resource);
         Ok(())
     }
+
+    /// Manifest of resources (image files), without pass.json
+    pub fn resource_manifest(&self) -> Manifest {
+        let mut manifest = Manifest::new();
+        for resource in &self.resources {
+            manifest.add_item(&resource.filename(), resource.as_bytes());
+        }
+        manifest
+    }
+
+    /// Compares this package with `other` (newer version of the package).
+    ///
+    /// Resources are compared by SHA-1, see [resource_manifest](Package::resource_manifest).
+    pub fn diff(&self, other: &Package) -> PackageDiff {
+        let old = self.resource_manifest();
+        let new = other.resource_manifest();
+        let mut resources = Vec::new();
+        for path in old.paths() {
+            match new.checksum(path) {
+                None => resources.push(ResourceDiff::Removed(path.to_string())),
+                Some(checksum) if old.checksum(path) != Some(checksum) => {
+                    resources.push(ResourceDiff::Modified(path.to_string()))
+                }
+                Some(_) => {}
+            }
+        }
+        for path in new.paths() {
+            if old.checksum(path).is_none() {
+                resources.push(ResourceDiff::Added(path.to_string()));
+            }
+        }
+
+        PackageDiff {
+            pass: self.pass.diff(&other.pass),
+            resources,
+        }
+    }
+
+    /// Sets [appearance](Pass::appearance) with colors extracted from pass images.
+    ///
+    /// Background color is the dominant color of background, strip or logo image (see [palette::source_image]),
+    /// foreground and label colors have at least `min_contrast` with it (see [palette::appearance_for]).
+    pub fn auto_appearance(&mut self, min_contrast: f64) -> Result<(), PassError> {
+        let image = palette::source_image(&self.resources).ok_or(PassError::MissingImage)?;
+        let background = palette::dominant_color(image.as_bytes())?;
+        self.pass.appearance = Some(palette::appearance_for(background, min_contrast));
+        Ok(())
+    }
+}
+
+/// Differences between two packages, see [Package::diff]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PackageDiff {
+    /// Differences of pass.json
+    pub pass: PassDiff,
+
+    /// Resources (image files) added, removed or modified
+    pub resources: Vec<ResourceDiff>,
+}
+
+impl PackageDiff {
+    /// Checks that packages are the same.
+    pub fn is_empty(&self) -> bool {
+        self.pass.is_empty() && self.resources.is_empty()
+    }
+
+    /// Checks that the change is displayed on the pass, all images are displayed.
+    pub fn is_user_visible(&self) -> bool {
+        self.pass.is_user_visible() || !self.resources.is_empty()
+    }
+
+    /// Checks that any field with changed value has a change message.
+    pub fn has_change_message(&self) -> bool {
+        self.pass.has_change_message()
+    }
+}
+
+/// Change of resource file, by file name
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceDiff {
+    /// File exists only in the new package
+    Added(String),
+    /// File exists only in the old package
+    Removed(String),
+    /// File has different SHA-1
+    Modified(String),
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::Read;
 
-    use crate::pass::{PassBuilder, PassConfig};
+    use crate::pass::{PassBuilder, PassConfig, make_config};
 
     use super::*;
 
@@ -260,8 +356,8 @@ mod tests {
         let pass = PassBuilder::new(PassConfig {
             organization_name: "Apple inc.".into(),
             description: "Example pass".into(),
-            pass_type_identifier: "com.example.pass".into(),
-            team_identifier: "AA00AA0A0A".into(),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
             serial_number: "ABCDEFG1234567890".into(),
         })
         .logo_text("Test pass".into())
@@ -275,8 +371,8 @@ mod tests {
         let pass = PassBuilder::new(PassConfig {
             organization_name: "Apple inc.".into(),
             description: "Example pass".into(),
-            pass_type_identifier: "com.example.pass".into(),
-            team_identifier: "AA00AA0A0A".into(),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
             serial_number: "ABCDEFG1234567890".into(),
         })
         .logo_text("Test pass".into())
@@ -315,8 +411,8 @@ mod tests {
         let pass = PassBuilder::new(PassConfig {
             organization_name: "Apple inc.".into(),
             description: "Example pass".into(),
-            pass_type_identifier: "com.example.pass".into(),
-            team_identifier: "AA00AA0A0A".into(),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
             serial_number: "ABCDEFG1234567890".into(),
         })
         .logo_text("Test pass".into())
@@ -354,4 +450,52 @@ mod tests {
         assert_eq!("icon.png", package.resources.get(0).unwrap().filename());
         assert_eq!("logo@3x.png", package.resources.get(1).unwrap().filename());
     }
+
+    #[test]
+    fn diff_package() {
+        let pass = PassBuilder::new(make_config()).build();
+
+        let mut old = Package::new(pass.clone());
+        old.add_resource(
+            resource::Type::Icon(resource::Version::Standard),
+            &[1u8; 16][..],
+        )
+        .unwrap();
+        old.add_resource(
+            resource::Type::Logo(resource::Version::Standard),
+            &[2u8; 16][..],
+        )
+        .unwrap();
+
+        let mut new = Package::new(pass);
+        new.add_resource(
+            resource::Type::Icon(resource::Version::Standard),
+            &[1u8; 16][..],
+        )
+        .unwrap();
+        new.add_resource(
+            resource::Type::Logo(resource::Version::Standard),
+            &[3u8; 16][..],
+        )
+        .unwrap();
+        new.add_resource(
+            resource::Type::Strip(resource::Version::Size2X),
+            &[4u8; 16][..],
+        )
+        .unwrap();
+
+        assert!(old.diff(&old).is_empty());
+
+        let diff = old.diff(&new);
+        assert!(diff.pass.is_empty());
+        assert_eq!(
+            vec![
+                ResourceDiff::Modified(String::from("logo.png")),
+                ResourceDiff::Added(String::from("strip@2x.png")),
+            ],
+            diff.resources
+        );
+        assert!(diff.is_user_visible());
+        assert!(!diff.has_change_message());
+    }
 }
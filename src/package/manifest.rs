@@ -53,6 +53,19 @@ impl Manifest {
     pub fn clear(&mut self) {
         self.items.clear();
     }
+
+    /// SHA-1 of file, as lowercase hex
+    pub fn checksum(&self, path: &str) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|item| item.path == path)
+            .map(|item| item.checksum.as_str())
+    }
+
+    /// Paths of all files
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().map(|item| item.path.as_str())
+    }
 }
 
 /// Manifest item
@@ -0,0 +1,242 @@
+use std::io::Cursor;
+
+use crate::error::PassError;
+use crate::pass::visual_appearance::{Color, VisualAppearance};
+
+use super::resource::{self, Resource};
+
+/// Pixels with lower alpha are not counted, so transparent areas of logos don't affect the palette
+const MIN_ALPHA: u8 = 128;
+
+/// Decodes PNG image and finds its dominant color.
+///
+/// Colors are grouped into buckets (4 bits per channel), the average color of the largest bucket is returned.
+/// Transparent pixels are skipped.
+pub fn dominant_color(png: &[u8]) -> Result<Color, PassError> {
+    let mut decoder = png::Decoder::new(Cursor::new(png));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(PassError::ImageDecode)?;
+    let mut buf = vec![0; reader.output_buffer_size().unwrap_or_default()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(PassError::ImageDecode)?;
+    let data = &buf[..info.buffer_size()];
+
+    // Pixels as (r, g, b, a)
+    let pixels: Box<dyn Iterator<Item = (u8, u8, u8, u8)>> = match info.color_type {
+        png::ColorType::Grayscale => Box::new(data.iter().map(|&l| (l, l, l, 255))),
+        png::ColorType::GrayscaleAlpha => {
+            Box::new(data.chunks_exact(2).map(|p| (p[0], p[0], p[0], p[1])))
+        }
+        png::ColorType::Rgb => Box::new(data.chunks_exact(3).map(|p| (p[0], p[1], p[2], 255))),
+        png::ColorType::Rgba => Box::new(data.chunks_exact(4).map(|p| (p[0], p[1], p[2], p[3]))),
+        // Indexed images are expanded to RGB by the decoder
+        png::ColorType::Indexed => Box::new(std::iter::empty()),
+    };
+
+    // Count and sum of channels for each bucket
+    let mut buckets = vec![(0u64, [0u64; 3]); 16 * 16 * 16];
+    for (r, g, b, a) in pixels {
+        if a < MIN_ALPHA {
+            continue;
+        }
+        let index = (usize::from(r >> 4) << 8) | (usize::from(g >> 4) << 4) | usize::from(b >> 4);
+        let (count, sum) = &mut buckets[index];
+        *count += 1;
+        sum[0] += u64::from(r);
+        sum[1] += u64::from(g);
+        sum[2] += u64::from(b);
+    }
+
+    let (count, sum) = buckets
+        .into_iter()
+        .max_by_key(|(count, _)| *count)
+        .filter(|(count, _)| *count > 0)
+        .ok_or(PassError::ImageEmpty)?;
+    let channel = |sum: u64| ((sum + count / 2) / count) as u8;
+    Ok(Color {
+        r: channel(sum[0]),
+        g: channel(sum[1]),
+        b: channel(sum[2]),
+    })
+}
+
+/// Picks foreground and label colors for background color.
+///
+/// Foreground is black or white, whichever contrasts more with background.
+/// Label is a tint of background mixed with foreground, just enough to reach `min_contrast`
+/// (see [CONTRAST_AA](crate::visual_appearance::CONTRAST_AA)). If label can't reach it, foreground color is used.
+///
+/// ```
+/// use neopasses::palette;
+/// use neopasses::visual_appearance::{CONTRAST_AA, Color};
+///
+/// let background = Color::from_hex("#1d3557").unwrap();
+/// let appearance = palette::appearance_for(background, CONTRAST_AA);
+///
+/// assert_eq!(Color::white(), appearance.foreground_color);
+/// assert!(appearance.meets_contrast(CONTRAST_AA));
+/// ```
+pub fn appearance_for(background: Color, min_contrast: f64) -> VisualAppearance {
+    let white = Color {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    let black = Color { r: 0, g: 0, b: 0 };
+    let foreground = if white.contrast_ratio(&background) >= black.contrast_ratio(&background) {
+        white
+    } else {
+        black
+    };
+
+    // Move from background towards foreground until label is readable
+    let label = (1..=20)
+        .map(|step| mix(background, foreground, f64::from(step) / 20.0))
+        .find(|label| label.contrast_ratio(&background) >= min_contrast)
+        .unwrap_or(foreground);
+
+    VisualAppearance {
+        label_color: Some(label),
+        foreground_color: Some(foreground),
+        background_color: Some(background),
+    }
+}
+
+/// Finds image for color extraction, in order of preference: background, strip, logo.
+///
+/// Standard size is preferred over other versions of the same image.
+pub fn source_image(resources: &[Resource]) -> Option<&Resource> {
+    let kinds: [fn(resource::Version) -> resource::Type; 3] = [
+        resource::Type::Background,
+        resource::Type::Strip,
+        resource::Type::Logo,
+    ];
+    let versions = [
+        resource::Version::Standard,
+        resource::Version::Size2X,
+        resource::Version::Size3X,
+    ];
+    kinds.iter().find_map(|kind| {
+        versions.iter().find_map(|version| {
+            let image_type = kind(version.clone());
+            resources.iter().find(|r| r.get_type() == image_type)
+        })
+    })
+}
+
+/// Linear mix of two colors, `amount` 0 is `from` and 1 is `to`
+fn mix(from: Color, to: Color, amount: f64) -> Color {
+    let channel =
+        |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * amount).round() as u8;
+    Color {
+        r: channel(from.r, to.r),
+        g: channel(from.g, to.g),
+        b: channel(from.b, to.b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::visual_appearance::{CONTRAST_AA, CONTRAST_AAA};
+
+    use super::*;
+
+    /// Encodes RGBA image where `pixel(x, y)` gives color of each pixel
+    fn make_png(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4]) -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        let data: Vec<u8> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| pixel(x, y))
+            .collect();
+        writer.write_image_data(&data).unwrap();
+        writer.finish().unwrap();
+        png
+    }
+
+    #[test]
+    fn dominant_color_of_image() {
+        // Mostly navy with a red stripe
+        let png = make_png(10, 10, |x, _| {
+            if x < 3 {
+                [230, 57, 70, 255]
+            } else {
+                [29, 53, 87, 255]
+            }
+        });
+        let color = dominant_color(&png).unwrap();
+        assert_eq!(Color::from_hex("#1d3557").unwrap(), color);
+    }
+
+    #[test]
+    fn dominant_color_skips_transparent() {
+        // Logo: small orange mark on transparent background
+        let png = make_png(10, 10, |x, y| {
+            if x < 2 && y < 2 {
+                [255, 140, 0, 255]
+            } else {
+                [0, 0, 0, 0]
+            }
+        });
+        let color = dominant_color(&png).unwrap();
+        assert_eq!(Color::from_hex("#ff8c00").unwrap(), color);
+
+        let png = make_png(2, 2, |_, _| [0, 0, 0, 0]);
+        assert!(matches!(dominant_color(&png), Err(PassError::ImageEmpty)));
+
+        assert!(matches!(
+            dominant_color(&[0u8; 16]),
+            Err(PassError::ImageDecode(_))
+        ));
+    }
+
+    #[test]
+    fn appearance_meets_contrast() {
+        for hex in ["#ffffff", "#000000", "#ffcc00", "#008fd4", "#1d3557"] {
+            let background = Color::from_hex(hex).unwrap();
+            let appearance = appearance_for(background, CONTRAST_AA);
+            assert!(appearance.meets_contrast(CONTRAST_AA), "{}", hex);
+        }
+
+        let appearance = appearance_for(Color::from_hex("#1d3557").unwrap(), CONTRAST_AAA);
+        assert!(appearance.meets_contrast(CONTRAST_AAA));
+
+        let appearance = appearance_for(Color::from_hex("#ffcc00").unwrap(), CONTRAST_AA);
+        assert_eq!(Color::black(), appearance.foreground_color);
+        // Label is a tint, not the same as foreground
+        assert_ne!(appearance.foreground_color, appearance.label_color);
+    }
+
+    #[test]
+    fn package_auto_appearance() {
+        use crate::package::Package;
+        use crate::pass::{PassBuilder, make_config};
+
+        let pass = PassBuilder::new(make_config()).build();
+        let mut package = Package::new(pass);
+
+        assert!(matches!(
+            package.auto_appearance(CONTRAST_AA),
+            Err(PassError::MissingImage)
+        ));
+
+        // Strip is preferred over logo
+        let logo = make_png(4, 4, |_, _| [255, 140, 0, 255]);
+        let strip = make_png(4, 4, |_, _| [29, 53, 87, 255]);
+        package
+            .add_resource(resource::Type::Logo(resource::Version::Standard), &logo[..])
+            .unwrap();
+        package
+            .add_resource(resource::Type::Strip(resource::Version::Size2X), &strip[..])
+            .unwrap();
+        package.auto_appearance(CONTRAST_AA).unwrap();
+
+        let appearance = package.pass.appearance.unwrap();
+        assert_eq!(Color::from_hex("#1d3557"), appearance.background_color);
+        assert!(appearance.meets_contrast(CONTRAST_AA));
+    }
+}
@@ -0,0 +1,787 @@
+use std::fmt::Write;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+
+use crate::pass::barcode::{Barcode, BarcodeFormat, RenderOptions};
+use crate::pass::fields::{
+    Content, FIELD_GAP, Section, Style, TransitType, display_value, text_width,
+};
+use crate::pass::visual_appearance::Color;
+
+use super::Package;
+use super::resource::{self, Resource, Version};
+
+/// Width of rendered pass, in points
+pub const WIDTH: f64 = 320.0;
+
+/// Minimum height of rendered pass, in points
+pub const MIN_HEIGHT: f64 = 420.0;
+
+/// Space between the edge of the pass and its content, in points
+const PADDING: f64 = 12.0;
+
+/// Height of logo and header fields, in points
+const HEADER_HEIGHT: f64 = 40.0;
+
+/// Maximum width of logo, in points
+const LOGO_MAX_WIDTH: f64 = 120.0;
+
+/// Size of thumbnail image, in points
+const THUMBNAIL_SIZE: f64 = 90.0;
+
+/// Height of footer image, in points
+const FOOTER_HEIGHT: f64 = 15.0;
+
+/// Fonts used by Wallet, with fallbacks
+const FONT_FAMILY: &str =
+    "-apple-system, 'SF Pro Text', 'Helvetica Neue', Helvetica, Arial, sans-serif";
+
+/// Label and value colors of the back of a pass
+const BACK_LABEL_COLOR: &str = "#8e8e93";
+const BACK_VALUE_COLOR: &str = "#000000";
+
+/// Side of the pass to render
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Images, header, primary, secondary and auxiliary fields, and the barcode
+    Front,
+    /// Back fields
+    Back,
+}
+
+impl Package {
+    /// Render preview of the pass as SVG.
+    ///
+    /// The layout approximates Wallet: colors of [appearance](crate::Pass::appearance),
+    /// logo, strip, thumbnail, background and footer images the [style](Style) displays,
+    /// fields of each section and the first barcode (see [Barcode::render]). Images are
+    /// embedded as data URLs. Texts that don't fit are truncated, see [text_width].
+    ///
+    /// ```
+    /// use neopasses::{Package, PassBuilder, PassConfig};
+    /// use neopasses::render::Side;
+    ///
+    /// let pass = PassBuilder::new(PassConfig {
+    ///     organization_name: "Apple inc.".into(),
+    ///     description: "Example pass".into(),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
+    ///     serial_number: "ABCDEFG1234567890".into(),
+    /// })
+    /// .logo_text("Example".into())
+    /// .build();
+    ///
+    /// let svg = Package::new(pass).render_svg(Side::Front);
+    /// assert!(svg.starts_with("<svg"));
+    /// assert!(svg.contains(">Example</text>"));
+    /// ```
+    pub fn render_svg(&self, side: Side) -> String {
+        let colors = Colors::of(self);
+        let mut svg = Svg::default();
+        let height = match side {
+            Side::Front => self.render_front(&mut svg, &colors),
+            Side::Back => self.render_back(&mut svg),
+        };
+        let background = match side {
+            Side::Front => colors.background.as_str(),
+            Side::Back => "#ffffff",
+        };
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{font}">"#,
+                r#"<rect width="{w}" height="{h}" rx="12" fill="{bg}"/>{body}</svg>"#
+            ),
+            w = WIDTH,
+            h = height,
+            font = FONT_FAMILY,
+            bg = background,
+            body = svg.body,
+        )
+    }
+
+    /// Render preview of the pass as PNG, `scale` is the number of pixels per point.
+    ///
+    /// Rasterizes [render_svg](Package::render_svg) with fonts installed in the system.
+    #[cfg(feature = "raster")]
+    pub fn render_png(&self, side: Side, scale: f32) -> Result<Vec<u8>, crate::PassError> {
+        use crate::PassError;
+
+        let svg = self.render_svg(side);
+        let mut options = resvg::usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree = resvg::usvg::Tree::from_str(&svg, &options)
+            .map_err(|err| PassError::Render(err.to_string()))?;
+        let size = tree
+            .size()
+            .to_int_size()
+            .scale_by(scale)
+            .ok_or_else(|| PassError::Render(format!("invalid scale {}", scale)))?;
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+            .ok_or_else(|| PassError::Render(String::from("invalid image size")))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+        pixmap
+            .encode_png()
+            .map_err(|err| PassError::Render(err.to_string()))
+    }
+
+    /// Draws the front, returns its height
+    fn render_front(&self, svg: &mut Svg, colors: &Colors) -> f64 {
+        let style = self.pass.fields.style();
+        let image = |kind: fn(Version) -> resource::Type| {
+            let resource = find_image(&self.resources, kind)?;
+            style
+                .allows_resource(&resource.get_type())
+                .then_some(resource)
+        };
+        let strip = image(resource::Type::Strip);
+        // Event tickets display strip image instead of background and thumbnail
+        let background = image(resource::Type::Background).filter(|_| strip.is_none());
+        let thumbnail = image(resource::Type::Thumbnail).filter(|_| strip.is_none());
+
+        if let Some(background) = background {
+            svg.image(background, 0.0, 0.0, WIDTH, MIN_HEIGHT, "xMidYMid slice");
+        }
+
+        // Header: logo, logo text and header fields
+        let mut y = PADDING;
+        let mut logo_end = PADDING;
+        if let Some(logo) = find_image(&self.resources, resource::Type::Logo) {
+            let width = image_aspect(logo)
+                .map(|aspect| (aspect * HEADER_HEIGHT).min(LOGO_MAX_WIDTH))
+                .unwrap_or(HEADER_HEIGHT);
+            svg.image(logo, PADDING, y, width, HEADER_HEIGHT, "xMinYMid meet");
+            logo_end += width + FIELD_GAP;
+        }
+        let header = self.pass.fields.fields().section(Section::Header);
+        let (label_size, value_size) = font_sizes(style, Section::Header);
+        let header_width: f64 = header
+            .iter()
+            .map(|field| field_width(field, label_size, value_size) + FIELD_GAP)
+            .sum();
+        if let Some(logo_text) = &self.pass.logo_text {
+            let width = (WIDTH - PADDING - header_width - logo_end).max(0.0);
+            svg.text(
+                logo_end,
+                y + 26.0,
+                value_size,
+                &colors.foreground,
+                Anchor::Start,
+                true,
+                &fit(logo_text, value_size, width),
+            );
+        }
+        let mut x = WIDTH - PADDING;
+        for field in header.iter().rev() {
+            let width = field_width(field, label_size, value_size);
+            svg.field(
+                field,
+                x,
+                y,
+                width,
+                (label_size, value_size),
+                colors,
+                Anchor::End,
+            );
+            x -= width + FIELD_GAP;
+        }
+        y += HEADER_HEIGHT + FIELD_GAP;
+
+        // Primary fields, over the strip image if any
+        let primary = self.pass.fields.fields().section(Section::Primary);
+        let primary_height = match (strip, style) {
+            (Some(_), Style::EventTicket) => 98.0,
+            (Some(_), _) => 123.0,
+            (None, _) => 72.0,
+        };
+        if let Some(strip) = strip {
+            svg.image(strip, 0.0, y, WIDTH, primary_height, "xMidYMid slice");
+        }
+        let thumbnail_width = if thumbnail.is_some() {
+            THUMBNAIL_SIZE + FIELD_GAP
+        } else {
+            0.0
+        };
+        if let Some(thumbnail) = thumbnail {
+            svg.image(
+                thumbnail,
+                WIDTH - PADDING - THUMBNAIL_SIZE,
+                y,
+                THUMBNAIL_SIZE,
+                THUMBNAIL_SIZE,
+                "xMidYMid meet",
+            );
+        }
+        let (label_size, value_size) = font_sizes(style, Section::Primary);
+        let field_top = if strip.is_some() { y + 16.0 } else { y };
+        match style {
+            Style::BoardingPass(transit_type) => {
+                let row = WIDTH - 2.0 * PADDING;
+                let width = style.slot_width_in(Section::Primary, 2, row).unwrap_or(row);
+                if let Some(field) = primary.first() {
+                    svg.field(
+                        field,
+                        PADDING,
+                        field_top,
+                        width,
+                        (label_size, value_size),
+                        colors,
+                        Anchor::Start,
+                    );
+                }
+                if let Some(field) = primary.get(1) {
+                    svg.field(
+                        field,
+                        WIDTH - PADDING,
+                        field_top,
+                        width,
+                        (label_size, value_size),
+                        colors,
+                        Anchor::End,
+                    );
+                }
+                svg.text(
+                    WIDTH / 2.0,
+                    field_top + 40.0,
+                    24.0,
+                    &colors.label,
+                    Anchor::Middle,
+                    false,
+                    transit_symbol(transit_type),
+                );
+            }
+            _ => {
+                let row = WIDTH - 2.0 * PADDING - thumbnail_width;
+                for (i, field, width) in row_slots(style, Section::Primary, primary, row) {
+                    svg.field(
+                        field,
+                        PADDING + i * (width + FIELD_GAP),
+                        field_top,
+                        width,
+                        (label_size, value_size),
+                        colors,
+                        Anchor::Start,
+                    );
+                }
+            }
+        }
+        y += primary_height + FIELD_GAP;
+
+        // Secondary and auxiliary fields next to the thumbnail
+        for section in [Section::Secondary, Section::Auxiliary] {
+            let fields = self.pass.fields.fields().section(section);
+            if fields.is_empty() {
+                continue;
+            }
+            let row = WIDTH - 2.0 * PADDING - thumbnail_width;
+            for (i, field, width) in row_slots(style, section, fields, row) {
+                svg.field(
+                    field,
+                    PADDING + i * (width + FIELD_GAP),
+                    y,
+                    width,
+                    font_sizes(style, section),
+                    colors,
+                    Anchor::Start,
+                );
+            }
+            y += 44.0;
+        }
+
+        if let Some(footer) = image(resource::Type::Footer) {
+            y += 8.0;
+            svg.image(
+                footer,
+                PADDING,
+                y,
+                WIDTH - 2.0 * PADDING,
+                FOOTER_HEIGHT,
+                "xMidYMid meet",
+            );
+            y += FOOTER_HEIGHT;
+        }
+
+        if let Some(barcode) = self.pass.barcodes.first() {
+            y += 16.0;
+            y = svg.barcode(barcode, y);
+        }
+
+        (y + PADDING).max(MIN_HEIGHT)
+    }
+
+    /// Draws back fields, returns height of the back
+    fn render_back(&self, svg: &mut Svg) -> f64 {
+        let width = WIDTH - 2.0 * PADDING;
+        let mut y = PADDING + 8.0;
+        for field in self.pass.fields.fields().section(Section::Back) {
+            if let Some(label) = &field.options.label {
+                y += 13.0;
+                svg.text(
+                    PADDING,
+                    y,
+                    13.0,
+                    BACK_LABEL_COLOR,
+                    Anchor::Start,
+                    false,
+                    &fit(label, 13.0, width),
+                );
+                y += 4.0;
+            }
+            for line in wrap(&display_value(field), 15.0, width) {
+                y += 19.0;
+                svg.text(
+                    PADDING,
+                    y,
+                    15.0,
+                    BACK_VALUE_COLOR,
+                    Anchor::Start,
+                    false,
+                    &line,
+                );
+            }
+            y += 12.0;
+            svg.body.push_str(&format!(
+                r##"<rect x="{}" y="{}" width="{}" height="0.5" fill="#c6c6c8"/>"##,
+                PADDING, y, width
+            ));
+            y += 8.0;
+        }
+        (y + PADDING).max(MIN_HEIGHT)
+    }
+}
+
+/// CSS colors of the front of the pass
+struct Colors {
+    background: String,
+    foreground: String,
+    label: String,
+}
+
+impl Colors {
+    /// Colors of appearance, Wallet defaults for colors that are not set
+    fn of(package: &Package) -> Self {
+        let appearance = package.pass.appearance.as_ref();
+        let color = |color: Option<&Color>, default: &str| {
+            color
+                .map(Color::to_hex)
+                .unwrap_or_else(|| String::from(default))
+        };
+        let foreground = color(
+            appearance.and_then(|a| a.foreground_color.as_ref()),
+            "#000000",
+        );
+        Self {
+            background: color(
+                appearance.and_then(|a| a.background_color.as_ref()),
+                "#ffffff",
+            ),
+            label: color(appearance.and_then(|a| a.label_color.as_ref()), &foreground),
+            foreground,
+        }
+    }
+}
+
+/// Text alignment relative to x
+#[derive(Clone, Copy)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// SVG elements of the pass
+#[derive(Default)]
+struct Svg {
+    body: String,
+}
+
+impl Svg {
+    #[allow(clippy::too_many_arguments)]
+    fn text(
+        &mut self,
+        x: f64,
+        y: f64,
+        size: f64,
+        fill: &str,
+        anchor: Anchor,
+        bold: bool,
+        text: &str,
+    ) {
+        let anchor = match anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+            Anchor::End => "end",
+        };
+        let weight = if bold { r#" font-weight="600""# } else { "" };
+        let _ = write!(
+            self.body,
+            r#"<text x="{:.1}" y="{:.1}" font-size="{}" fill="{}" text-anchor="{}"{}>{}</text>"#,
+            x,
+            y,
+            size,
+            fill,
+            anchor,
+            weight,
+            escape(text)
+        );
+    }
+
+    fn image(
+        &mut self,
+        resource: &Resource,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        aspect: &str,
+    ) {
+        let _ = write!(
+            self.body,
+            r#"<image x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" preserveAspectRatio="{}" href="data:image/png;base64,{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            aspect,
+            BASE64.encode(resource.as_bytes())
+        );
+    }
+
+    /// Label above value, `x` is the start or the end of the slot
+    #[allow(clippy::too_many_arguments)]
+    fn field(
+        &mut self,
+        field: &Content,
+        x: f64,
+        y: f64,
+        width: f64,
+        (label_size, value_size): (f64, f64),
+        colors: &Colors,
+        anchor: Anchor,
+    ) {
+        if let Some(label) = &field.options.label {
+            let label = fit(&label.to_uppercase(), label_size, width);
+            self.text(
+                x,
+                y + label_size,
+                label_size,
+                &colors.label,
+                anchor,
+                true,
+                &label,
+            );
+        }
+        let value = fit(&display_value(field), value_size, width);
+        self.text(
+            x,
+            y + label_size + 4.0 + value_size,
+            value_size,
+            &colors.foreground,
+            anchor,
+            false,
+            &value,
+        );
+    }
+
+    /// Draws barcode centered at `y`, returns the bottom of the barcode
+    fn barcode(&mut self, barcode: &Barcode, y: f64) -> f64 {
+        let (width, height) = match barcode.format {
+            BarcodeFormat::QR | BarcodeFormat::Aztec => (120.0, 120.0),
+            BarcodeFormat::PDF417 => (240.0, 80.0),
+            BarcodeFormat::Code128 => (240.0, 60.0),
+        };
+        let x = (WIDTH - width) / 2.0;
+        let alt_height = if barcode.alt_text.is_some() {
+            16.0
+        } else {
+            0.0
+        };
+        let _ = write!(
+            self.body,
+            r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="6" fill="#ffffff"/>"##,
+            x - 8.0,
+            y - 8.0,
+            width + 16.0,
+            height + 16.0 + alt_height
+        );
+        let options = RenderOptions {
+            quiet_zone: Some(0),
+            ..Default::default()
+        };
+        match barcode.render(&options) {
+            Ok(image) => self.body.push_str(&image.svg_in(x, y, width, height)),
+            // Message of the barcode in place of the symbol Wallet can't display
+            Err(_) => {
+                let _ = write!(
+                    self.body,
+                    r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="none" stroke="#000000" stroke-dasharray="4 4"/>"##,
+                    x, y, width, height
+                );
+                self.text(
+                    WIDTH / 2.0,
+                    y + height / 2.0 + 4.0,
+                    10.0,
+                    "#000000",
+                    Anchor::Middle,
+                    false,
+                    &fit(&barcode.message, 10.0, width - 8.0),
+                );
+            }
+        }
+        let mut bottom = y + height;
+        if let Some(alt_text) = &barcode.alt_text {
+            bottom += alt_height;
+            self.text(
+                WIDTH / 2.0,
+                bottom - 2.0,
+                11.0,
+                "#000000",
+                Anchor::Middle,
+                false,
+                &fit(alt_text, 11.0, width),
+            );
+        }
+        bottom + 8.0
+    }
+}
+
+/// Image of kind, preferring sizes for high resolution screens
+fn find_image(resources: &[Resource], kind: fn(Version) -> resource::Type) -> Option<&Resource> {
+    [Version::Size2X, Version::Size3X, Version::Standard]
+        .into_iter()
+        .find_map(|version| {
+            let image_type = kind(version);
+            resources.iter().find(|r| r.get_type() == image_type)
+        })
+}
+
+/// Width to height ratio of PNG image
+fn image_aspect(resource: &Resource) -> Option<f64> {
+    let decoder = png::Decoder::new(std::io::Cursor::new(resource.as_bytes()));
+    let reader = decoder.read_info().ok()?;
+    let info = reader.info();
+    (info.height > 0).then(|| f64::from(info.width) / f64::from(info.height))
+}
+
+/// Width of the wider of label and value
+fn field_width(field: &Content, label_size: f64, value_size: f64) -> f64 {
+    let label = field
+        .options
+        .label
+        .as_ref()
+        .map(|label| text_width(&label.to_uppercase(), label_size))
+        .unwrap_or(0.0);
+    label.max(text_width(&display_value(field), value_size))
+}
+
+/// Font sizes of label and value of front section
+fn font_sizes(style: Style, section: Section) -> (f64, f64) {
+    style
+        .font_sizes(section)
+        .expect("front sections have font sizes")
+}
+
+/// Index, field and width of each field of section sharing the row, see [Style::slot_width]
+fn row_slots(
+    style: Style,
+    section: Section,
+    fields: &[Content],
+    row: f64,
+) -> impl Iterator<Item = (f64, &Content, f64)> {
+    let width = style
+        .slot_width_in(section, fields.len(), row)
+        .unwrap_or(row);
+    fields
+        .iter()
+        .enumerate()
+        .map(move |(i, field)| (i as f64, field, width))
+}
+
+/// Text truncated with `…` to fit `width`
+fn fit(text: &str, size: f64, width: f64) -> String {
+    if text_width(text, size) <= width {
+        return String::from(text);
+    }
+    let mut fitted = String::new();
+    for c in text.chars() {
+        let mut candidate = fitted.clone();
+        candidate.push(c);
+        candidate.push('…');
+        if text_width(&candidate, size) > width {
+            break;
+        }
+        fitted.push(c);
+    }
+    fitted.push('…');
+    fitted
+}
+
+/// Text split into lines that fit `width`, at spaces when possible
+fn wrap(text: &str, size: f64, width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() {
+                String::from(word)
+            } else {
+                format!("{} {}", line, word)
+            };
+            if text_width(&candidate, size) <= width || line.is_empty() {
+                line = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut line, String::from(word)));
+            }
+            // Words longer than the line are truncated
+            if text_width(&line, size) > width {
+                line = fit(&line, size, width);
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Symbol between primary fields of boarding passes
+fn transit_symbol(transit_type: TransitType) -> &'static str {
+    match transit_type {
+        TransitType::Air => "✈",
+        TransitType::Boat => "⛴",
+        TransitType::Bus => "🚌",
+        TransitType::Train => "🚆",
+        TransitType::Generic => "→",
+    }
+}
+
+/// Escapes text for XML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::visual_appearance::VisualAppearance;
+    use crate::pass::{PassBuilder, fields, make_config};
+
+    /// 4x1 opaque PNG image
+    fn make_png() -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, 4, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[200u8; 12]).unwrap();
+        writer.finish().unwrap();
+        png
+    }
+
+    fn make_package() -> Package {
+        let pass = PassBuilder::new(make_config())
+            .logo_text("Concerts & More".into())
+            .appearance(VisualAppearance {
+                label_color: Color::from_hex("#ffcc00"),
+                foreground_color: Color::white(),
+                background_color: Color::from_hex("#1d3557"),
+            })
+            .fields(
+                fields::Type::EventTicket {
+                    pass_fields: fields::Fields::default(),
+                }
+                .add_header_field(fields::Content::new(
+                    "row",
+                    "12",
+                    fields::ContentOptions {
+                        label: String::from("Row").into(),
+                        ..Default::default()
+                    },
+                ))
+                .add_primary_field(fields::Content::new(
+                    "event",
+                    "The <Band>",
+                    Default::default(),
+                ))
+                .add_secondary_field(fields::Content::new(
+                    "venue",
+                    "A venue with a name too long to fit in the slot of the field",
+                    Default::default(),
+                ))
+                .add_back_field(fields::Content::new(
+                    "terms",
+                    "No refunds. No exchanges. Doors open one hour before the show.",
+                    fields::ContentOptions {
+                        label: String::from("Terms").into(),
+                        ..Default::default()
+                    },
+                )),
+            )
+            .add_barcode(Barcode {
+                message: String::from("ABCDEFG1234567890"),
+                alt_text: Some(String::from("ABCDEFG")),
+                ..Default::default()
+            })
+            .build();
+
+        let mut package = Package::new(pass);
+        for image in ["icon.png", "logo@2x.png", "strip.png", "thumbnail.png"] {
+            package
+                .add_resource(image.parse().unwrap(), &make_png()[..])
+                .unwrap();
+        }
+        package
+    }
+
+    #[test]
+    fn render_front() {
+        let svg = make_package().render_svg(Side::Front);
+        println!("{}", svg);
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="320""#));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(r##"fill="#1d3557""##));
+        assert!(svg.contains(">Concerts &amp; More</text>"));
+        assert!(svg.contains(">The &lt;Band&gt;</text>"));
+        assert!(svg.contains(">ROW</text>"));
+        assert!(svg.contains("…</text>"));
+        assert!(svg.contains(">ABCDEFG</text>"));
+        assert!(svg.contains(r#"shape-rendering="crispEdges""#));
+        // Logo and strip, thumbnail is not displayed with strip
+        assert_eq!(2, svg.matches("<image").count());
+        assert!(!svg.contains("No refunds"));
+    }
+
+    #[test]
+    fn render_back() {
+        let svg = make_package().render_svg(Side::Back);
+        println!("{}", svg);
+
+        assert!(svg.contains(">Terms</text>"));
+        assert!(svg.contains("No refunds."));
+        assert!(!svg.contains("<image"));
+    }
+
+    #[cfg(feature = "raster")]
+    #[test]
+    fn render_png() {
+        let png = make_package().render_png(Side::Front, 2.0).unwrap();
+
+        let decoder = png::Decoder::new(std::io::Cursor::new(&png[..]));
+        let reader = decoder.read_info().unwrap();
+        assert_eq!(640, reader.info().width);
+        assert!(reader.info().height >= 840);
+    }
+
+    #[test]
+    fn wrap_and_fit() {
+        assert_eq!("abc", fit("abc", 10.0, 100.0));
+        assert_eq!("abcd…", fit("abcdefghijklmnop", 10.0, 30.0));
+        let lines = wrap("one two three four five six", 10.0, 50.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| text_width(line, 10.0) <= 50.0));
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 use regex::Regex;
 
 /// Represents image file, saved in .pkpass package
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Resource {
     /// Type of image (represents file name)
     image_type: Type,
@@ -59,7 +59,7 @@ impl Read for Resource {
 }
 
 /// Image size versions
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Version {
     Standard,
     Size2X,
@@ -101,7 +101,7 @@ impl FromStr for Version {
 /// * On iPhone 6 and 6 Plus The allotted space is 375 x 98 points for event tickets, 375 x 144 points for gift cards and coupons, and 375 x 123 in all other cases.
 /// * On prior hardware The allotted space is 320 x 84 points for event tickets, 320 x 110 points for other pass styles with a square barcode on devices with 3.5 inch screens, and 320 x 123 in all other cases.
 /// * The thumbnail image (thumbnail.png) displayed next to the fields on the front of the pass. The allotted space is 90 x 90 points. The aspect ratio should be in the range of 2:3 to 3:2, otherwise the image is cropped.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Type {
     /// The background image (background.png)
     Background(Version),
@@ -117,6 +117,33 @@ pub enum Type {
     Thumbnail(Version),
 }
 
+impl Type {
+    /// Size version of the image.
+    pub fn version(&self) -> &Version {
+        match self {
+            Type::Background(v)
+            | Type::Footer(v)
+            | Type::Icon(v)
+            | Type::Logo(v)
+            | Type::Strip(v)
+            | Type::Thumbnail(v) => v,
+        }
+    }
+
+    /// The same image in [Standard](Version::Standard) size.
+    pub fn standard(&self) -> Type {
+        let v = Version::Standard;
+        match self {
+            Type::Background(_) => Type::Background(v),
+            Type::Footer(_) => Type::Footer(v),
+            Type::Icon(_) => Type::Icon(v),
+            Type::Logo(_) => Type::Logo(v),
+            Type::Strip(_) => Type::Strip(v),
+            Type::Thumbnail(_) => Type::Thumbnail(v),
+        }
+    }
+}
+
 impl ToString for Type {
     fn to_string(&self) -> String {
         match self {
@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::error::Violation;
+use crate::pass::fields::Style;
+
+use super::Package;
+use super::resource::{self, Version};
+
+/// Severity of [Diagnostic]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Wallet rejects the package
+    Error,
+    /// Wallet accepts the package, but doesn't display it as intended
+    Warning,
+}
+
+/// Finding of [Package::validate]
+///
+/// Images are reported in [Standard](Version::Standard) size when all sizes are affected.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum Diagnostic {
+    #[error("icon.png is required")]
+    MissingIcon,
+    #[error("`{}` is added more than once", .0.to_string())]
+    DuplicateResource(resource::Type),
+    #[error("`{}` is added without `{}`", .0.to_string(), .0.standard().to_string())]
+    MissingBaseImage(resource::Type),
+    #[error("`{}` is not displayed on {style:?} passes", .image.to_string())]
+    ResourceNotAllowed { image: resource::Type, style: Style },
+    #[error("`{}` is not displayed together with `{}`", .image.to_string(), .with.to_string())]
+    ConflictingResources {
+        image: resource::Type,
+        with: resource::Type,
+    },
+    #[error(transparent)]
+    Pass(Violation),
+}
+
+impl Diagnostic {
+    /// Severity of the finding.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::MissingIcon | Diagnostic::DuplicateResource(_) | Diagnostic::Pass(_) => {
+                Severity::Error
+            }
+            Diagnostic::MissingBaseImage(_)
+            | Diagnostic::ResourceNotAllowed { .. }
+            | Diagnostic::ConflictingResources { .. } => Severity::Warning,
+        }
+    }
+
+    /// Checks that Wallet rejects the package.
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl Package {
+    /// Check that Wallet accepts the package and displays all of its images.
+    ///
+    /// Checks pass constraints (see [Pass::validate](crate::Pass::validate)), the required icon,
+    /// resources added more than once, `@2x` and `@3x` images without the base image,
+    /// and images the pass style doesn't display (see [Style::allows_resource]).
+    ///
+    /// ```
+    /// use neopasses::{Package, PassBuilder, PassConfig, resource};
+    /// use neopasses::validation::Diagnostic;
+    ///
+    /// let pass = PassBuilder::new(PassConfig {
+    ///     organization_name: "Apple inc.".into(),
+    ///     description: "Example pass".into(),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
+    ///     serial_number: "ABCDEFG1234567890".into(),
+    /// })
+    /// .build();
+    ///
+    /// let mut package = Package::new(pass);
+    /// assert_eq!(vec![Diagnostic::MissingIcon], package.validate());
+    ///
+    /// package
+    ///     .add_resource(resource::Type::Icon(resource::Version::Standard), &[0u8; 16][..])
+    ///     .unwrap();
+    /// assert!(package.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if let Err(err) = self.pass.validate() {
+            diagnostics.extend(err.violations.into_iter().map(Diagnostic::Pass));
+        }
+
+        let mut types = HashSet::new();
+        let mut duplicates = Vec::new();
+        for resource in &self.resources {
+            let image = resource.get_type();
+            if !types.insert(image.clone()) && !duplicates.contains(&image) {
+                duplicates.push(image);
+            }
+        }
+
+        let icon = resource::Type::Icon(Version::Standard);
+        if !types.contains(&icon) {
+            diagnostics.push(Diagnostic::MissingIcon);
+        }
+        diagnostics.extend(duplicates.into_iter().map(Diagnostic::DuplicateResource));
+
+        // In order of resources, each image once
+        let mut images = Vec::new();
+        for resource in &self.resources {
+            let image = resource.get_type();
+            if !images.contains(&image) {
+                images.push(image);
+            }
+        }
+
+        for image in &images {
+            let base = image.standard();
+            if image != &base && base != icon && !types.contains(&base) {
+                diagnostics.push(Diagnostic::MissingBaseImage(image.clone()));
+            }
+        }
+
+        let style = self.pass.fields.style();
+        let mut bases: Vec<resource::Type> = Vec::new();
+        for base in images.iter().map(resource::Type::standard) {
+            if !bases.contains(&base) {
+                bases.push(base);
+            }
+        }
+        for base in &bases {
+            if !style.allows_resource(base) {
+                diagnostics.push(Diagnostic::ResourceNotAllowed {
+                    image: base.clone(),
+                    style,
+                });
+            }
+        }
+
+        // Event tickets display strip image instead of background and thumbnail
+        let strip = resource::Type::Strip(Version::Standard);
+        if style == Style::EventTicket && bases.contains(&strip) {
+            for base in &bases {
+                if matches!(
+                    base,
+                    resource::Type::Background(_) | resource::Type::Thumbnail(_)
+                ) {
+                    diagnostics.push(Diagnostic::ConflictingResources {
+                        image: base.clone(),
+                        with: strip.clone(),
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::{PassBuilder, fields, make_config};
+
+    fn make_package(fields: fields::Type, images: &[&str]) -> Package {
+        let pass = PassBuilder::new(make_config()).fields(fields).build();
+
+        let mut package = Package::new(pass);
+        for image in images {
+            package
+                .add_resource(image.parse().unwrap(), &[0u8; 16][..])
+                .unwrap();
+        }
+        package
+    }
+
+    #[test]
+    fn valid_package() {
+        let package = make_package(
+            fields::Type::BoardingPass {
+                pass_fields: fields::Fields::default(),
+                transit_type: fields::TransitType::Air,
+            },
+            &["icon.png", "icon@2x.png", "logo.png", "footer.png"],
+        );
+        assert!(package.validate().is_empty());
+    }
+
+    #[test]
+    fn resource_diagnostics() {
+        let package = make_package(
+            fields::Type::BoardingPass {
+                pass_fields: fields::Fields::default(),
+                transit_type: fields::TransitType::Air,
+            },
+            &[
+                "icon@2x.png",
+                "logo.png",
+                "logo.png",
+                "footer@2x.png",
+                "thumbnail.png",
+                "thumbnail@3x.png",
+            ],
+        );
+        let diagnostics = package.validate();
+        for diagnostic in &diagnostics {
+            println!("{:?}: {}", diagnostic.severity(), diagnostic);
+        }
+
+        use resource::Type::*;
+        assert_eq!(
+            vec![
+                Diagnostic::MissingIcon,
+                Diagnostic::DuplicateResource(Logo(Version::Standard)),
+                Diagnostic::MissingBaseImage(Footer(Version::Size2X)),
+                Diagnostic::ResourceNotAllowed {
+                    image: Thumbnail(Version::Standard),
+                    style: Style::BoardingPass(fields::TransitType::Air),
+                },
+            ],
+            diagnostics
+        );
+        assert_eq!(
+            vec![
+                Severity::Error,
+                Severity::Error,
+                Severity::Warning,
+                Severity::Warning
+            ],
+            diagnostics
+                .iter()
+                .map(Diagnostic::severity)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn event_ticket_strip_conflicts() {
+        let package = make_package(
+            fields::Type::EventTicket {
+                pass_fields: fields::Fields::default(),
+            }
+            .add_primary_field(fields::Content::new("event", "Concert", Default::default()))
+            .add_back_field(fields::Content::new(
+                "event",
+                "Concert",
+                Default::default(),
+            )),
+            &[
+                "icon.png",
+                "background.png",
+                "strip.png",
+                "thumbnail@2x.png",
+            ],
+        );
+        let diagnostics = package.validate();
+        for diagnostic in &diagnostics {
+            println!("{}", diagnostic);
+        }
+
+        use resource::Type::*;
+        assert_eq!(
+            vec![
+                Diagnostic::Pass(Violation::DuplicateFieldKey(String::from("event"))),
+                Diagnostic::MissingBaseImage(Thumbnail(Version::Size2X)),
+                Diagnostic::ConflictingResources {
+                    image: Background(Version::Standard),
+                    with: Strip(Version::Standard),
+                },
+                Diagnostic::ConflictingResources {
+                    image: Thumbnail(Version::Standard),
+                    with: Strip(Version::Standard),
+                },
+            ],
+            diagnostics
+        );
+        assert!(diagnostics[0].is_error());
+        assert!(!diagnostics[1].is_error());
+    }
+}
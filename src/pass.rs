@@ -1,11 +1,17 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
 use is_empty::IsEmpty;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use self::barcode::Barcode;
+use crate::error::{BcbpError, BuildError, Violation};
+
+use self::barcode::{Barcode, BarcodeFormat};
 use self::beacon::Beacon;
+use self::identifiers::{AppStoreId, PassTypeId, TeamId};
 use self::location::Location;
 use self::nfc::NFC;
+use self::relevant_date::RelevantDate;
 use self::semantic_tags::SemanticTags;
 use self::visual_appearance::VisualAppearance;
 use self::web_service::WebService;
@@ -13,17 +19,33 @@ use self::web_service::WebService;
 pub mod barcode;
 pub mod beacon;
 mod date_format;
+pub mod diff;
 pub mod fields;
+mod formats;
+pub mod identifiers;
+pub mod itinerary;
+pub mod lint;
 pub mod location;
+pub mod migration;
 pub mod nfc;
+pub mod patch;
+pub mod relevant_date;
 pub mod semantic_tags;
+pub mod transit;
+pub mod travel;
 pub mod visual_appearance;
 pub mod web_service;
 
+/// Maximum number of [locations](Pass::locations) in a pass
+pub const MAX_LOCATIONS: usize = 10;
+
+/// Maximum number of [beacons](Pass::beacons) in a pass
+pub const MAX_BEACONS: usize = 10;
+
 /// Required fields for [Pass]
 ///
 /// Used for [Pass] construction
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PassConfig {
     /// The name of the organization.
@@ -34,10 +56,10 @@ pub struct PassConfig {
 
     /// The pass type identifier that’s registered with Apple.
     /// The value must be the same as the distribution certificate used to sign the pass.
-    pub pass_type_identifier: String,
+    pub pass_type_identifier: PassTypeId,
 
     /// The Team ID for the Apple Developer Program account that registered the pass type identifier.
-    pub team_identifier: String,
+    pub team_identifier: TeamId,
 
     /// An alphanumeric serial number.
     /// The combination of the serial number and pass type identifier must be unique for each pass.
@@ -53,14 +75,15 @@ pub struct PassConfig {
 /// let pass = PassBuilder::new(PassConfig {
 ///     organization_name: String::from("Apple inc."),
 ///     description: String::from("Example pass"),
-///     pass_type_identifier: String::from("com.example.pass"),
-///     team_identifier: String::from("AA00AA0A0A"),
+///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+///     team_identifier: "AA00AA0A0A".parse().unwrap(),
 ///     serial_number: String::from("ABCDEFG1234567890"),
 /// })
 /// .build();
 /// ```
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[schemars(description = "Represents a pass (pass.json file)")]
 pub struct Pass {
     /// The version of the file format. The value must be 1.
     format_version: u32,
@@ -90,16 +113,25 @@ pub struct Pass {
     pub logo_text: Option<String>,
 
     /// The date and time when the pass becomes relevant
+    ///
+    /// Deprecated in iOS 18, use [relevant_dates](Pass::relevant_dates) instead.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "date_format")]
-    pub relevant_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub relevant_date: Option<DateTime<FixedOffset>>,
+
+    /// Dates and date intervals when the pass becomes relevant (iOS 18 and later).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub relevant_dates: Vec<RelevantDate>,
 
     /// The date and time the pass expires.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "date_format")]
-    pub expiration_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub expiration_date: Option<DateTime<FixedOffset>>,
 
     /// A URL to be passed to the associated app when launching it.
     #[serde(default)]
@@ -110,7 +142,7 @@ pub struct Pass {
     /// An array of App Store identifiers for apps associated with the pass.
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub associated_store_identifiers: Vec<i32>,
+    pub associated_store_identifiers: Vec<AppStoreId>,
 
     /// Implement a web server to register, update, and unregister a pass on a device.
     ///
@@ -194,8 +226,8 @@ impl Pass {
     /// let pass = PassBuilder::new(PassConfig {
     ///     organization_name: String::from("Apple inc."),
     ///     description: String::from("Example pass"),
-    ///     pass_type_identifier: String::from("com.example.pass"),
-    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
     ///     serial_number: String::from("ABCDEFG1234567890"),
     /// })
     /// .build();
@@ -206,7 +238,7 @@ impl Pass {
     ///   "formatVersion": 1,
     ///   "organizationName": "Apple inc.",
     ///   "description": "Example pass",
-    ///   "passTypeIdentifier": "com.example.pass",
+    ///   "passTypeIdentifier": "pass.com.example",
     ///   "teamIdentifier": "AA00AA0A0A",
     ///   "serialNumber": "ABCDEFG1234567890",
     ///   "generic": {
@@ -225,8 +257,23 @@ impl Pass {
         Ok(json)
     }
 
+    /// Build JSON output for pass (pass.json) with options, see [JsonOptions](migration::JsonOptions)
+    pub fn make_json_with(
+        &self,
+        options: &migration::JsonOptions,
+    ) -> Result<String, serde_json::Error> {
+        if options.legacy_keys {
+            return serde_json::to_string_pretty(&migration::WithLegacyKeys::new(self));
+        }
+        self.make_json()
+    }
+
     /// Build pass (pass.json) from json data
     ///
+    /// Deprecated `barcode` is moved to [barcodes](Pass::barcodes), or dropped if `barcodes` is present.
+    /// Deprecated `relevantDate` is kept as is, see [from_json_migrated](Pass::from_json_migrated) to
+    /// upgrade it and get a report of upgrades.
+    ///
     /// ```
     /// use neopasses::Pass;
     ///
@@ -234,7 +281,7 @@ impl Pass {
     ///   "formatVersion": 1,
     ///   "organizationName": "Apple inc.",
     ///   "description": "Example pass",
-    ///   "passTypeIdentifier": "com.example.pass",
+    ///   "passTypeIdentifier": "pass.com.example",
     ///   "teamIdentifier": "AA00AA0A0A",
     ///   "serialNumber": "ABCDEFG1234567890",
     ///   "generic": {
@@ -251,14 +298,95 @@ impl Pass {
     /// assert_eq!(json_expected, json);
     /// ```
     pub fn from_json(data: &str) -> Result<Self, serde_json::Error> {
-        let pass: Pass = serde_json::from_str(data)?;
-        Ok(pass)
+        let mut json: serde_json::Value = serde_json::from_str(data)?;
+        if migration::migrate_barcode(&mut json).is_none() {
+            // Typed errors keep their position when the data is unchanged
+            return serde_json::from_str(data);
+        }
+        serde_json::from_value(json)
+    }
+
+    /// JSON Schema (draft 2020-12) of pass.json, generated from the library types.
+    ///
+    /// ```
+    /// use neopasses::Pass;
+    ///
+    /// let schema = Pass::json_schema();
+    /// assert_eq!("https://json-schema.org/draft/2020-12/schema", schema["$schema"]);
+    /// assert!(schema["$defs"]["TransitType"].is_object());
+    /// ```
+    pub fn json_schema() -> serde_json::Value {
+        let generator = schemars::generate::SchemaSettings::draft2020_12().into_generator();
+        let schema = generator.into_root_schema_for::<Pass>();
+        schema.to_value()
+    }
+
+    /// Check pass constraints
+    ///
+    /// Collects all violations: number of locations and beacons, field keys
+    /// used more than once, text alignment of primary and back fields,
+    /// formatting options that don't match field values, and barcode messages
+    /// that can't be encoded or don't fit the format with
+    /// [medium](barcode::ErrorCorrection::Medium) error correction.
+    pub fn validate(&self) -> Result<(), BuildError> {
+        let mut violations = Vec::new();
+
+        if self.locations.len() > MAX_LOCATIONS {
+            violations.push(Violation::TooManyLocations {
+                count: self.locations.len(),
+                max: MAX_LOCATIONS,
+            });
+        }
+        if self.beacons.len() > MAX_BEACONS {
+            violations.push(Violation::TooManyBeacons {
+                count: self.beacons.len(),
+                max: MAX_BEACONS,
+            });
+        }
+
+        for (index, date) in self.relevant_dates.iter().enumerate() {
+            if !date.is_valid() {
+                violations.push(Violation::InvalidRelevantDate { index });
+            }
+        }
+
+        for (index, barcode) in self.barcodes.iter().enumerate() {
+            if let Err(error) = barcode.validate(barcode::ErrorCorrection::Medium) {
+                violations.push(Violation::InvalidBarcode { index, error });
+            }
+        }
+
+        for key in self.fields.duplicate_keys() {
+            violations.push(Violation::DuplicateFieldKey(String::from(key)));
+        }
+
+        for (section, field) in self.fields.iter() {
+            if field.options.text_alignment.is_some()
+                && matches!(section, fields::Section::Primary | fields::Section::Back)
+            {
+                violations.push(Violation::TextAlignmentNotAllowed {
+                    key: field.key.clone(),
+                    section,
+                });
+            }
+            if let Err(err) = field.validate() {
+                violations.push(err.into());
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(BuildError { violations })
+        }
     }
 }
 
 /// Builder for pass (represents pass.json file)
 pub struct PassBuilder {
     pass: Pass,
+    watch_fallback: Option<BarcodeFormat>,
+    signed_message: Option<String>,
 }
 
 impl PassBuilder {
@@ -273,6 +401,7 @@ impl PassBuilder {
             appearance: None,
             logo_text: None,
             relevant_date: None,
+            relevant_dates: Vec::new(),
             expiration_date: None,
             app_launch_url: None,
             associated_store_identifiers: Vec::new(),
@@ -292,7 +421,11 @@ impl PassBuilder {
                 },
             },
         };
-        Self { pass }
+        Self {
+            pass,
+            watch_fallback: None,
+            signed_message: None,
+        }
     }
 
     /// Adding [grouping_identifier](Pass::grouping_identifier)
@@ -322,22 +455,66 @@ impl PassBuilder {
     /// let pass = PassBuilder::new(PassConfig {
     ///     organization_name: String::from("Apple inc."),
     ///     description: String::from("Example pass"),
-    ///     pass_type_identifier: String::from("com.example.pass"),
-    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
     ///     serial_number: String::from("ABCDEFG1234567890"),
     /// }).relevant_date(Utc.with_ymd_and_hms(2024, 02, 07, 0, 0, 0).unwrap());
     /// ```
-    pub fn relevant_date(mut self, field: DateTime<Utc>) -> PassBuilder {
-        self.pass.relevant_date = Some(field);
+    ///
+    /// The time zone offset of `field` is kept in pass.json.
+    pub fn relevant_date<Tz: TimeZone>(mut self, field: DateTime<Tz>) -> PassBuilder {
+        self.pass.relevant_date = Some(field.fixed_offset());
         self
     }
 
+    /// Adding [relevant_date](Pass::relevant_date) from local time in time zone
+    ///
+    /// ```
+    /// use chrono::prelude::*;
+    /// use chrono_tz::Asia::Tokyo;
+    /// use neopasses::{PassBuilder, PassConfig};
+    ///
+    /// let doors_open = NaiveDate::from_ymd_opt(2024, 2, 7)
+    ///     .unwrap()
+    ///     .and_hms_opt(19, 0, 0)
+    ///     .unwrap();
+    /// let pass = PassBuilder::new(PassConfig {
+    ///     organization_name: String::from("Apple inc."),
+    ///     description: String::from("Example pass"),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
+    ///     serial_number: String::from("ABCDEFG1234567890"),
+    /// })
+    /// .relevant_date_local(Tokyo, doors_open)
+    /// .build();
+    ///
+    /// assert_eq!(
+    ///     "2024-02-07T19:00:00+09:00",
+    ///     pass.relevant_date.unwrap().to_rfc3339()
+    /// );
+    /// ```
+    ///
+    /// Ambiguous local time resolves to the earliest date, local time skipped
+    /// by a daylight saving transition is shifted forward by the gap.
+    pub fn relevant_date_local(self, tz: Tz, local: NaiveDateTime) -> PassBuilder {
+        self.relevant_date(date_format::from_local(tz, local))
+    }
+
     /// Adding [expiration_date](Pass::expiration_date)
-    pub fn expiration_date(mut self, field: DateTime<Utc>) -> PassBuilder {
-        self.pass.expiration_date = Some(field);
+    ///
+    /// The time zone offset of `field` is kept in pass.json.
+    pub fn expiration_date<Tz: TimeZone>(mut self, field: DateTime<Tz>) -> PassBuilder {
+        self.pass.expiration_date = Some(field.fixed_offset());
         self
     }
 
+    /// Adding [expiration_date](Pass::expiration_date) from local time in time zone
+    ///
+    /// Resolves local time the same way as [relevant_date_local](PassBuilder::relevant_date_local).
+    pub fn expiration_date_local(self, tz: Tz, local: NaiveDateTime) -> PassBuilder {
+        self.expiration_date(date_format::from_local(tz, local))
+    }
+
     /// Adding [app_launch_url](Pass::app_launch_url)
     pub fn app_launch_url(mut self, field: String) -> PassBuilder {
         self.pass.app_launch_url = Some(field);
@@ -345,7 +522,7 @@ impl PassBuilder {
     }
 
     /// Adding [associated_store_identifiers](Pass::associated_store_identifiers)
-    pub fn add_associated_store_identifier(mut self, id: i32) -> PassBuilder {
+    pub fn add_associated_store_identifier(mut self, id: AppStoreId) -> PassBuilder {
         self.pass.associated_store_identifiers.push(id);
         self
     }
@@ -368,6 +545,12 @@ impl PassBuilder {
         self
     }
 
+    /// Adding [RelevantDate] to [relevant_dates](Pass::relevant_dates)
+    pub fn add_relevant_date(mut self, date: RelevantDate) -> PassBuilder {
+        self.pass.relevant_dates.push(date);
+        self
+    }
+
     /// Adding [voided](Pass::voided)
     pub fn voided(mut self, field: bool) -> PassBuilder {
         self.pass.voided = field;
@@ -380,18 +563,67 @@ impl PassBuilder {
         self
     }
 
+    /// Sign [Payload](barcode::signed::Payload) of the pass with `claims`, and set it as
+    /// message of [barcodes](Pass::barcodes) with empty message when building, adding QR barcode
+    /// if there is none. Barcodes with a message, such as [bcbp_barcode](PassBuilder::bcbp_barcode),
+    /// are kept.
+    ///
+    /// Whether the payload fits format of each barcode is checked by [try_build](PassBuilder::try_build).
+    pub fn signed_barcode(
+        mut self,
+        claims: barcode::signed::Claims,
+        key: &barcode::signed::SigningKey,
+    ) -> PassBuilder {
+        let payload = barcode::signed::Payload {
+            serial_number: self.pass.config.serial_number.clone(),
+            pass_type_identifier: self.pass.config.pass_type_identifier.clone(),
+            claims,
+        };
+        self.signed_message = Some(payload.sign(key));
+        self
+    }
+
+    /// Add barcode of [BoardingPass](barcode::bcbp::BoardingPass) in `format`, and write
+    /// semantic tags of its `leg` (see [write_semantics](barcode::bcbp::BoardingPass::write_semantics)),
+    /// so that barcode and semantics agree.
+    pub fn bcbp_barcode(
+        mut self,
+        bcbp: &barcode::bcbp::BoardingPass,
+        leg: usize,
+        format: BarcodeFormat,
+    ) -> Result<PassBuilder, BcbpError> {
+        if leg >= bcbp.legs.len() {
+            return Err(BcbpError::NoSuchLeg(leg));
+        }
+        let message = bcbp.encode()?;
+        bcbp.write_semantics(leg, &mut self.pass.semantics);
+        self.pass.barcodes.push(Barcode {
+            message,
+            format,
+            ..Default::default()
+        });
+        Ok(self)
+    }
+
+    /// Add copy of Code128 barcode in `format` when building, if the pass has no barcode
+    /// Apple Watch displays (see [Pass::add_watch_fallback_barcode])
+    pub fn watch_fallback_barcode(mut self, format: BarcodeFormat) -> PassBuilder {
+        self.watch_fallback = Some(format);
+        self
+    }
+
     /// Adding [Beacon] to [beacons](Pass::beacons)
+    ///
+    /// A pass can have up to [MAX_BEACONS] beacons, checked by [try_build](PassBuilder::try_build).
     pub fn add_beacon(mut self, beacon: Beacon) -> PassBuilder {
         self.pass.beacons.push(beacon);
         self
     }
 
     /// Adding [Location] to [locations](Pass::locations)
+    ///
+    /// A pass can have up to [MAX_LOCATIONS] locations, checked by [try_build](PassBuilder::try_build).
     pub fn add_location(mut self, location: Location) -> PassBuilder {
-        assert!(
-            self.pass.locations.len() < 10,
-            "Reached limit for geographic locations (maximum - 10)"
-        );
         self.pass.locations.push(location);
         self
     }
@@ -416,8 +648,8 @@ impl PassBuilder {
     /// let pass = PassBuilder::new(PassConfig {
     ///     organization_name: String::from("Apple inc."),
     ///     description: String::from("Example pass"),
-    ///     pass_type_identifier: String::from("com.example.pass"),
-    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
     ///     serial_number: String::from("ABCDEFG1234567890"),
     /// }).semantics(semantic_tags::SemanticTags {
     ///     airline_code: String::from("EX123").into(),
@@ -442,8 +674,8 @@ impl PassBuilder {
     /// let pass = PassBuilder::new(PassConfig {
     ///     organization_name: String::from("Apple inc."),
     ///     description: String::from("Example pass"),
-    ///     pass_type_identifier: String::from("com.example.pass"),
-    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
     ///     serial_number: String::from("ABCDEFG1234567890"),
     /// }).fields(
     ///     fields::Type::BoardingPass {
@@ -476,9 +708,65 @@ impl PassBuilder {
     }
 
     /// Makes `Pass`.
-    pub fn build(self) -> Pass {
+    ///
+    /// Pass constraints are not checked, use [try_build](PassBuilder::try_build) to validate the pass.
+    pub fn build(mut self) -> Pass {
+        if let Some(message) = self.signed_message.take() {
+            let barcodes = &mut self.pass.barcodes;
+            if barcodes.iter().all(|barcode| !barcode.message.is_empty()) {
+                barcodes.push(Barcode::default());
+            }
+            for barcode in barcodes.iter_mut().filter(|b| b.message.is_empty()) {
+                barcode.message = message.clone();
+                // Text of another message would not match the payload
+                barcode.alt_text = None;
+            }
+        }
+        if let Some(format) = self.watch_fallback.take() {
+            self.pass.add_watch_fallback_barcode(format);
+        }
         self.pass
     }
+
+    /// Makes `Pass`, checking constraints with [Pass::validate].
+    ///
+    /// ```
+    /// use neopasses::{PassBuilder, PassConfig, Violation, location::Location};
+    ///
+    /// let mut builder = PassBuilder::new(PassConfig {
+    ///     organization_name: String::from("Apple inc."),
+    ///     description: String::from("Example pass"),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
+    ///     serial_number: String::from("ABCDEFG1234567890"),
+    /// });
+    /// for _ in 0..11 {
+    ///     builder = builder.add_location(Location::default());
+    /// }
+    ///
+    /// let err = builder.try_build().unwrap_err();
+    /// assert_eq!(
+    ///     vec![Violation::TooManyLocations { count: 11, max: 10 }],
+    ///     err.violations
+    /// );
+    /// ```
+    pub fn try_build(self) -> Result<Pass, BuildError> {
+        let pass = self.build();
+        pass.validate()?;
+        Ok(pass)
+    }
+}
+
+/// Config of passes made in tests
+#[cfg(test)]
+pub(crate) fn make_config() -> PassConfig {
+    PassConfig {
+        organization_name: "Apple inc.".into(),
+        description: "Example pass".into(),
+        pass_type_identifier: "pass.com.example".parse().unwrap(),
+        team_identifier: "AA00AA0A0A".parse().unwrap(),
+        serial_number: "ABCDEFG1234567890".into(),
+    }
 }
 
 #[cfg(test)]
@@ -487,6 +775,7 @@ mod tests {
     use tests::{fields, semantic_tags::SemanticTagLocation, visual_appearance::Color};
 
     use super::*;
+    use crate::BarcodeError;
 
     #[test]
     fn make_minimal_pass() {
@@ -494,8 +783,8 @@ mod tests {
         let pass = PassBuilder::new(PassConfig {
             organization_name: String::from("Apple inc."),
             description: String::from("Example pass"),
-            pass_type_identifier: String::from("com.example.pass"),
-            team_identifier: String::from("AA00AA0A0A"),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
             serial_number: String::from("ABCDEFG1234567890"),
         })
         .build();
@@ -508,7 +797,7 @@ mod tests {
   "formatVersion": 1,
   "organizationName": "Apple inc.",
   "description": "Example pass",
-  "passTypeIdentifier": "com.example.pass",
+  "passTypeIdentifier": "pass.com.example",
   "teamIdentifier": "AA00AA0A0A",
   "serialNumber": "ABCDEFG1234567890",
   "generic": {
@@ -534,8 +823,8 @@ mod tests {
         let pass = PassBuilder::new(PassConfig {
             organization_name: String::from("Apple inc."),
             description: String::from("Example pass"),
-            pass_type_identifier: String::from("com.example.pass"),
-            team_identifier: String::from("AA00AA0A0A"),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
             serial_number: String::from("ABCDEFG1234567890"),
         })
         .grouping_identifier(String::from("com.example.pass.app"))
@@ -548,7 +837,7 @@ mod tests {
         .relevant_date(Utc.with_ymd_and_hms(2024, 02, 07, 0, 0, 0).unwrap())
         .expiration_date(Utc.with_ymd_and_hms(2024, 02, 08, 0, 0, 0).unwrap())
         .app_launch_url(String::from("testapp:param?index=1"))
-        .add_associated_store_identifier(100)
+        .add_associated_store_identifier(AppStoreId::from(100))
         .web_service(WebService {
             authentication_token: String::from("abcdefg01234567890abcdefg"),
             web_service_url: String::from("https://example.com/passes/"),
@@ -563,14 +852,13 @@ mod tests {
             ..Default::default()
         })
         .add_beacon(Beacon {
-            proximity_uuid: String::from("e286373b-15b5-4f4e-bf91-e9e64787724a"),
+            proximity_uuid: "e286373b-15b5-4f4e-bf91-e9e64787724a".parse().unwrap(),
             major: Some(2),
             minor: Some(150),
             relevant_text: Some(String::from("The simple beacon")),
         })
         .add_location(Location {
-            latitude: 37.334606,
-            longitude: -122.009102,
+            coordinates: location::LatLon::new(37.334606, -122.009102).unwrap(),
             relevant_text: Some(String::from("Apple Park, Cupertino, CA, USA")),
             ..Default::default()
         })
@@ -650,7 +938,7 @@ mod tests {
   "formatVersion": 1,
   "organizationName": "Apple inc.",
   "description": "Example pass",
-  "passTypeIdentifier": "com.example.pass",
+  "passTypeIdentifier": "pass.com.example",
   "teamIdentifier": "AA00AA0A0A",
   "serialNumber": "ABCDEFG1234567890",
   "groupingIdentifier": "com.example.pass.app",
@@ -750,6 +1038,195 @@ mod tests {
         let json = pass.make_json().unwrap();
         assert_eq!(json_expected, json);
     }
+
+    #[test]
+    fn keep_date_offsets() {
+        let doors_open = NaiveDate::from_ymd_opt(2024, 2, 7)
+            .unwrap()
+            .and_hms_opt(19, 0, 0)
+            .unwrap();
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+        .relevant_date_local(chrono_tz::Asia::Tokyo, doors_open)
+        .expiration_date(
+            FixedOffset::west_opt(5 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2024, 2, 8, 0, 0, 0)
+                .unwrap(),
+        )
+        .build();
+
+        let json = pass.make_json().unwrap();
+        assert!(json.contains(r#""relevantDate": "2024-02-07T19:00:00+09:00""#));
+        assert!(json.contains(r#""expirationDate": "2024-02-08T00:00:00-05:00""#));
+
+        // Deserialization test
+        let pass: Pass = Pass::from_json(&json).unwrap();
+        assert_eq!(
+            9 * 3600,
+            pass.relevant_date.unwrap().offset().local_minus_utc()
+        );
+        assert_eq!(json, pass.make_json().unwrap());
+    }
+
+    #[test]
+    fn try_build_collects_violations() {
+        let mut builder = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
+            serial_number: String::from("ABCDEFG1234567890"),
+        });
+        for _ in 0..11 {
+            builder = builder.add_beacon(Beacon::default());
+        }
+        let builder = builder.add_barcode(Barcode {
+            message: String::from("Ticket €25"),
+            ..Default::default()
+        });
+        let builder = builder.fields(
+            fields::Type::Generic {
+                pass_fields: fields::Fields::default(),
+            }
+            .add_header_field(fields::Content::new("gate", "21", Default::default()))
+            .add_primary_field(fields::Content::new(
+                "gate",
+                "21",
+                fields::ContentOptions {
+                    text_alignment: fields::TextAlignment::Center.into(),
+                    ..Default::default()
+                },
+            ))
+            .add_back_field(fields::Content::new(
+                "price",
+                "25",
+                fields::ContentOptions {
+                    currency_code: String::from("USD").into(),
+                    ..Default::default()
+                },
+            )),
+        );
+
+        let err = builder.try_build().unwrap_err();
+        assert_eq!(
+            vec![
+                Violation::TooManyBeacons { count: 11, max: 10 },
+                Violation::InvalidBarcode {
+                    index: 0,
+                    error: BarcodeError::Unencodable(barcode::MessageEncoding::Iso8859_1),
+                },
+                Violation::DuplicateFieldKey(String::from("gate")),
+                Violation::TextAlignmentNotAllowed {
+                    key: String::from("gate"),
+                    section: fields::Section::Primary,
+                },
+                Violation::InvalidField(fields::ContentError::OptionRequiresCurrency {
+                    key: String::from("price"),
+                    option: "currencyCode",
+                }),
+            ],
+            err.violations
+        );
+        println!("{}", err);
+    }
+
+    #[test]
+    fn try_build_valid_pass() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            team_identifier: "AA00AA0A0A".parse().unwrap(),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+        .add_location(Location::default())
+        .fields(
+            fields::Type::EventTicket {
+                pass_fields: fields::Fields::default(),
+            }
+            .add_primary_field(fields::Content::currency("price", 25.0, "USD")),
+        )
+        .try_build();
+
+        assert!(pass.is_ok());
+    }
+
+    #[test]
+    fn json_schema_matches_model() {
+        let schema = Pass::json_schema();
+        println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+
+        assert_eq!(
+            "https://json-schema.org/draft/2020-12/schema",
+            schema["$schema"]
+        );
+        // camelCase names of required keys
+        assert_eq!(
+            serde_json::json!([
+                "formatVersion",
+                "organizationName",
+                "description",
+                "passTypeIdentifier",
+                "teamIdentifier",
+                "serialNumber"
+            ]),
+            schema["required"]
+        );
+        assert!(schema["properties"]["relevantDate"].is_object());
+        // Rust examples of the docs are left out of descriptions
+        assert_eq!(
+            Some("Represents a pass (pass.json file)"),
+            schema["description"].as_str()
+        );
+        assert!(!schema.to_string().contains("```"));
+
+        let defs = &schema["$defs"];
+        // Documented variants are listed with their descriptions
+        let formats: Vec<&str> = defs["BarcodeFormat"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|format| format["const"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                "PKBarcodeFormatQR",
+                "PKBarcodeFormatPDF417",
+                "PKBarcodeFormatAztec",
+                "PKBarcodeFormatCode128"
+            ],
+            formats
+        );
+        assert_eq!("PKTransitTypeTrain", defs["TransitType"]["enum"][4]);
+        assert_eq!("^[A-Za-z0-9]{10}$", defs["TeamId"]["pattern"]);
+        assert!(defs["Content"]["properties"]["attributedValue"].is_object());
+        assert!(defs["SemanticTags"].is_object());
+
+        // Pass style is one of the field sets
+        let styles: Vec<&str> = schema["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|style| style["required"].as_array().unwrap())
+            .map(|key| key.as_str().unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                "boardingPass",
+                "coupon",
+                "eventTicket",
+                "generic",
+                "storeCard"
+            ],
+            styles
+        );
+    }
 }
 
 // For serde skipping - if boolean false
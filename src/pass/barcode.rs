@@ -1,7 +1,18 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+pub use self::encoding::*;
+pub use self::render::*;
+
+pub mod bcbp;
+pub mod signed;
+
+mod capacity;
+mod encoding;
+mod render;
+
 /// Represents a barcode on a pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Barcode {
     /// (Required) The message or payload to display as a barcode.
@@ -20,7 +31,7 @@ pub struct Barcode {
     pub alt_text: Option<String>,
 
     /// (Required) The IANA character set name of the text encoding to use to convert message from a string representation to a data representation that the system renders as a barcode.
-    pub message_encoding: String,
+    pub message_encoding: MessageEncoding,
 }
 
 impl Default for Barcode {
@@ -30,13 +41,13 @@ impl Default for Barcode {
             message: String::new(),
             format: BarcodeFormat::QR,
             alt_text: None,
-            message_encoding: String::from("iso-8859-1"),
+            message_encoding: MessageEncoding::Iso8859_1,
         }
     }
 }
 
 /// Barcode format
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum BarcodeFormat {
     /// QR - <https://en.wikipedia.org/wiki/QR_code>
     #[serde(rename = "PKBarcodeFormatQR")]
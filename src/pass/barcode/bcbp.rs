@@ -0,0 +1,874 @@
+//! IATA Resolution 792 Bar Coded Boarding Pass (BCBP).
+//!
+//! BCBP is fixed-width ASCII text: mandatory items of the passenger and of each leg,
+//! conditional items in sections prefixed with their size in hex, and optional security data.
+//! Conditional sections are written up to their last non-empty item.
+//!
+//! ```
+//! use neopasses::barcode::bcbp::BoardingPass;
+//!
+//! let bcbp: BoardingPass = "M1DESMARAIS/LUC       EABC123 YULFRAAC 0834 326J001A0025 100"
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!("DESMARAIS/LUC", bcbp.passenger_name);
+//! assert_eq!("834", bcbp.legs[0].flight_number);
+//! assert_eq!("1A", bcbp.legs[0].seat);
+//!
+//! let semantics = bcbp.semantics(0).unwrap();
+//! assert_eq!(Some(834), semantics.flight_number);
+//! assert_eq!(Some("YUL"), semantics.departure_airport_code.as_deref());
+//!
+//! assert_eq!(
+//!     "M1DESMARAIS/LUC       EABC123 YULFRAAC 0834 326J001A0025 100",
+//!     bcbp.encode().unwrap()
+//! );
+//! ```
+
+use std::{fmt::Write, str::FromStr};
+
+use chrono::Datelike;
+
+use crate::BcbpError;
+use crate::pass::semantic_tags::{SemanticTagPersonNameComponents, SemanticTagSeat, SemanticTags};
+
+/// Version of conditional items written by [BoardingPass::encode]
+pub const VERSION: u8 = 6;
+
+/// Boarding pass of a passenger for up to 9 legs
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardingPass {
+    /// Passenger name in form `SURNAME/GIVEN NAME`, up to 20 characters
+    pub passenger_name: String,
+
+    /// Ticket is electronic
+    pub electronic_ticket: bool,
+
+    /// Conditional items of the boarding pass, written in the first leg
+    pub conditional: Option<Conditional>,
+
+    /// Flights of the boarding pass
+    pub legs: Vec<Leg>,
+
+    /// Signature of the issuer
+    pub security: Option<SecurityData>,
+}
+
+/// Conditional items of [BoardingPass], these are one-character codes unless noted
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Conditional {
+    /// Version of the BCBP standard
+    pub version: u8,
+    pub passenger_description: String,
+    pub source_of_check_in: String,
+    pub source_of_issuance: String,
+    /// Last digit of the year and the day of the year, such as `4038`
+    pub date_of_issue: String,
+    pub document_type: String,
+    /// Airline designator of the issuer, up to 3 characters
+    pub issuer: String,
+    /// Baggage tag licence plate numbers, 13 characters
+    pub baggage_tag: String,
+    pub first_non_consecutive_baggage_tag: String,
+    pub second_non_consecutive_baggage_tag: String,
+}
+
+/// Flight of [BoardingPass]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    /// Booking reference of the operating carrier, up to 7 characters
+    pub pnr: String,
+
+    /// IATA code of the departure airport
+    pub from_airport: String,
+
+    /// IATA code of the destination airport
+    pub to_airport: String,
+
+    /// Designator of the operating carrier, such as `AC`
+    pub operating_carrier: String,
+
+    /// Flight number with optional suffix letter, such as `834` or `834A`
+    pub flight_number: String,
+
+    /// Day of the year of the flight, 1 to 366
+    pub date_of_flight: u16,
+
+    /// Compartment code, such as `Y` for economy
+    pub compartment: char,
+
+    /// Seat, such as `1A`
+    pub seat: String,
+
+    /// Check-in sequence number, such as `25`
+    pub check_in_sequence: String,
+
+    /// Passenger status, such as `1` for checked in
+    pub passenger_status: char,
+
+    /// Conditional items of the leg
+    pub conditional: Option<LegConditional>,
+
+    /// Data for individual use of the airline
+    pub airline_data: String,
+}
+
+/// Conditional items of [Leg], these are one-character codes unless noted
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LegConditional {
+    /// 3 digits
+    pub airline_numeric_code: String,
+    /// 10 digits
+    pub document_serial_number: String,
+    pub selectee: String,
+    pub international_documentation_verification: String,
+    /// Up to 3 characters
+    pub marketing_carrier: String,
+    /// Up to 3 characters
+    pub frequent_flyer_airline: String,
+    /// Up to 16 characters
+    pub frequent_flyer_number: String,
+    pub id_ad_indicator: String,
+    /// Up to 3 characters, such as `20K`
+    pub free_baggage_allowance: String,
+    pub fast_track: String,
+}
+
+/// Security data of [BoardingPass]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityData {
+    /// Type of security data
+    pub kind: char,
+
+    /// Security data, up to 255 characters
+    pub data: String,
+}
+
+impl Conditional {
+    fn items(&self) -> [(&'static str, &str, usize); 9] {
+        [
+            ("passenger description", &self.passenger_description, 1),
+            ("source of check-in", &self.source_of_check_in, 1),
+            ("source of issuance", &self.source_of_issuance, 1),
+            ("date of issue", &self.date_of_issue, 4),
+            ("document type", &self.document_type, 1),
+            ("issuer", &self.issuer, 3),
+            ("baggage tag", &self.baggage_tag, 13),
+            (
+                "first non-consecutive baggage tag",
+                &self.first_non_consecutive_baggage_tag,
+                13,
+            ),
+            (
+                "second non-consecutive baggage tag",
+                &self.second_non_consecutive_baggage_tag,
+                13,
+            ),
+        ]
+    }
+
+    fn read(version: u8, mut section: Reader) -> Self {
+        Self {
+            version,
+            passenger_description: section.take_conditional(1),
+            source_of_check_in: section.take_conditional(1),
+            source_of_issuance: section.take_conditional(1),
+            date_of_issue: section.take_conditional(4),
+            document_type: section.take_conditional(1),
+            issuer: section.take_conditional(3),
+            baggage_tag: section.take_conditional(13),
+            first_non_consecutive_baggage_tag: section.take_conditional(13),
+            second_non_consecutive_baggage_tag: section.take_conditional(13),
+        }
+    }
+}
+
+impl LegConditional {
+    fn items(&self) -> [(&'static str, &str, usize); 10] {
+        [
+            ("airline numeric code", &self.airline_numeric_code, 3),
+            ("document serial number", &self.document_serial_number, 10),
+            ("selectee indicator", &self.selectee, 1),
+            (
+                "international documentation verification",
+                &self.international_documentation_verification,
+                1,
+            ),
+            ("marketing carrier", &self.marketing_carrier, 3),
+            ("frequent flyer airline", &self.frequent_flyer_airline, 3),
+            ("frequent flyer number", &self.frequent_flyer_number, 16),
+            ("ID/AD indicator", &self.id_ad_indicator, 1),
+            ("free baggage allowance", &self.free_baggage_allowance, 3),
+            ("fast track", &self.fast_track, 1),
+        ]
+    }
+
+    fn read(mut section: Reader) -> Self {
+        Self {
+            airline_numeric_code: section.take_conditional(3),
+            document_serial_number: section.take_conditional(10),
+            selectee: section.take_conditional(1),
+            international_documentation_verification: section.take_conditional(1),
+            marketing_carrier: section.take_conditional(3),
+            frequent_flyer_airline: section.take_conditional(3),
+            frequent_flyer_number: section.take_conditional(16),
+            id_ad_indicator: section.take_conditional(1),
+            free_baggage_allowance: section.take_conditional(3),
+            fast_track: section.take_conditional(1),
+        }
+    }
+}
+
+impl BoardingPass {
+    /// Encode as BCBP string, the barcode message of the boarding pass.
+    pub fn encode(&self) -> Result<String, BcbpError> {
+        if self.legs.is_empty() || self.legs.len() > 9 {
+            return Err(BcbpError::LegCount(self.legs.len()));
+        }
+
+        let mut bcbp = format!("M{}", self.legs.len());
+        item(&mut bcbp, "passenger name", &self.passenger_name, 20)?;
+        bcbp.push(if self.electronic_ticket { 'E' } else { ' ' });
+
+        for (index, leg) in self.legs.iter().enumerate() {
+            item(&mut bcbp, "PNR code", &leg.pnr, 7)?;
+            item(&mut bcbp, "from airport", &leg.from_airport, 3)?;
+            item(&mut bcbp, "to airport", &leg.to_airport, 3)?;
+            item(&mut bcbp, "operating carrier", &leg.operating_carrier, 3)?;
+            numbered(&mut bcbp, "flight number", &leg.flight_number, 5)?;
+            if !(1..=366).contains(&leg.date_of_flight) {
+                return Err(BcbpError::InvalidItem {
+                    item: "date of flight",
+                    value: leg.date_of_flight.to_string(),
+                });
+            }
+            let _ = write!(bcbp, "{:03}", leg.date_of_flight);
+            item(
+                &mut bcbp,
+                "compartment code",
+                &leg.compartment.to_string(),
+                1,
+            )?;
+            numbered(&mut bcbp, "seat number", &leg.seat, 4)?;
+            numbered(
+                &mut bcbp,
+                "check-in sequence number",
+                &leg.check_in_sequence,
+                5,
+            )?;
+            item(
+                &mut bcbp,
+                "passenger status",
+                &leg.passenger_status.to_string(),
+                1,
+            )?;
+
+            let repeated = match &leg.conditional {
+                Some(conditional) => section(&conditional.items())?,
+                None => String::new(),
+            };
+            let mut variable = String::new();
+            if index == 0 {
+                if self.conditional.is_some() || leg.conditional.is_some() {
+                    let (version, unique) = match &self.conditional {
+                        Some(conditional) => (conditional.version, section(&conditional.items())?),
+                        None => (VERSION, String::new()),
+                    };
+                    if version > 9 {
+                        return Err(BcbpError::InvalidItem {
+                            item: "version",
+                            value: version.to_string(),
+                        });
+                    }
+                    let _ = write!(variable, ">{}", version);
+                    sized(&mut variable, "conditional items", &unique)?;
+                    sized(&mut variable, "conditional items of the leg", &repeated)?;
+                }
+            } else if leg.conditional.is_some() || !leg.airline_data.is_empty() {
+                sized(&mut variable, "conditional items of the leg", &repeated)?;
+            }
+            if !leg.airline_data.is_ascii() {
+                return Err(BcbpError::InvalidItem {
+                    item: "airline data",
+                    value: leg.airline_data.clone(),
+                });
+            }
+            variable.push_str(&leg.airline_data);
+            sized(&mut bcbp, "variable size field", &variable)?;
+        }
+
+        if let Some(security) = &self.security {
+            bcbp.push('^');
+            item(
+                &mut bcbp,
+                "type of security data",
+                &security.kind.to_string(),
+                1,
+            )?;
+            if !security.data.is_ascii() {
+                return Err(BcbpError::InvalidItem {
+                    item: "security data",
+                    value: security.data.clone(),
+                });
+            }
+            sized(&mut bcbp, "security data", &security.data)?;
+        }
+        Ok(bcbp)
+    }
+
+    /// Decode BCBP string.
+    pub fn decode(bcbp: &str) -> Result<Self, BcbpError> {
+        if !bcbp.is_ascii() {
+            return Err(BcbpError::FormatCode);
+        }
+        let mut reader = Reader(bcbp);
+        if reader.take(1, "format code")? != "M" {
+            return Err(BcbpError::FormatCode);
+        }
+        let legs = reader.take(1, "number of legs")?;
+        let legs = match legs.parse::<usize>() {
+            Ok(legs @ 1..=9) => legs,
+            _ => {
+                return Err(BcbpError::InvalidItem {
+                    item: "number of legs",
+                    value: String::from(legs),
+                });
+            }
+        };
+        let passenger_name = reader.take(20, "passenger name")?.trim_end().to_string();
+        let electronic_ticket = reader.take(1, "electronic ticket indicator")? == "E";
+
+        let mut conditional = None;
+        let mut boarding_legs = Vec::with_capacity(legs);
+        for index in 0..legs {
+            let pnr = reader.take(7, "PNR code")?.trim_end().to_string();
+            let from_airport = reader.take(3, "from airport")?.trim_end().to_string();
+            let to_airport = reader.take(3, "to airport")?.trim_end().to_string();
+            let operating_carrier = reader.take(3, "operating carrier")?.trim_end().to_string();
+            let flight_number = unnumbered(reader.take(5, "flight number")?);
+            let date = reader.take(3, "date of flight")?;
+            let date_of_flight = date.trim().parse().map_err(|_| BcbpError::InvalidItem {
+                item: "date of flight",
+                value: String::from(date),
+            })?;
+            let compartment = reader.char("compartment code")?;
+            let seat = unnumbered(reader.take(4, "seat number")?);
+            let check_in_sequence = unnumbered(reader.take(5, "check-in sequence number")?);
+            let passenger_status = reader.char("passenger status")?;
+
+            let size = reader.hex("variable size field")?;
+            let mut variable = Reader(reader.take(size, "variable size field")?);
+            let mut leg_conditional = None;
+            if index == 0 {
+                if variable.0.starts_with('>') {
+                    variable.take(1, "conditional items")?;
+                    let version = variable.take(1, "version")?;
+                    let version = version.parse().map_err(|_| BcbpError::InvalidItem {
+                        item: "version",
+                        value: String::from(version),
+                    })?;
+                    // Version is kept even without conditional items
+                    let size = variable.hex("conditional items")?;
+                    let section = Reader(variable.take(size, "conditional items")?);
+                    conditional = Some(Conditional::read(version, section));
+                    leg_conditional = variable.section()?;
+                }
+            } else if !variable.0.is_empty() {
+                leg_conditional = variable.section()?;
+            }
+
+            boarding_legs.push(Leg {
+                pnr,
+                from_airport,
+                to_airport,
+                operating_carrier,
+                flight_number,
+                date_of_flight,
+                compartment,
+                seat,
+                check_in_sequence,
+                passenger_status,
+                conditional: leg_conditional,
+                airline_data: String::from(variable.0),
+            });
+        }
+
+        let mut security = None;
+        if reader.0.starts_with('^') {
+            reader.take(1, "security data")?;
+            let kind = reader.char("type of security data")?;
+            let size = reader.hex("security data")?;
+            let data = String::from(reader.take(size, "security data")?);
+            security = Some(SecurityData { kind, data });
+        }
+        if !reader.0.is_empty() {
+            return Err(BcbpError::InvalidItem {
+                item: "data after the last leg",
+                value: String::from(reader.0),
+            });
+        }
+
+        Ok(Self {
+            passenger_name,
+            electronic_ticket,
+            conditional,
+            legs: boarding_legs,
+            security,
+        })
+    }
+
+    /// Write passenger and items of the leg to semantic tags of its pass.
+    ///
+    /// Sets passenger name, airline code, flight number and code, airport codes,
+    /// confirmation number (PNR), seat, boarding sequence number (check-in sequence)
+    /// and membership program number (frequent flyer number). Does nothing if there is no such leg.
+    pub fn write_semantics(&self, leg: usize, semantics: &mut SemanticTags) {
+        let Some(leg) = self.legs.get(leg) else {
+            return;
+        };
+        let non_empty = |s: &str| (!s.is_empty()).then(|| String::from(s));
+
+        let (family_name, given_name) = self
+            .passenger_name
+            .split_once('/')
+            .unwrap_or((&self.passenger_name, ""));
+        semantics.passenger_name = Some(SemanticTagPersonNameComponents {
+            family_name: non_empty(family_name.trim()),
+            given_name: non_empty(given_name.trim()),
+            ..Default::default()
+        });
+        semantics.airline_code = non_empty(&leg.operating_carrier);
+        let (number, _) = split_number(&leg.flight_number);
+        semantics.flight_number = number.parse().ok();
+        semantics.flight_code = Some(format!("{}{}", leg.operating_carrier, leg.flight_number));
+        semantics.departure_airport_code = non_empty(&leg.from_airport);
+        semantics.destination_airport_code = non_empty(&leg.to_airport);
+        semantics.confirmation_number = non_empty(&leg.pnr);
+        semantics.boarding_sequence_number = non_empty(&leg.check_in_sequence);
+        semantics.seats = match non_empty(&leg.seat) {
+            Some(seat) => {
+                let (row, letter) = split_number(&seat);
+                vec![SemanticTagSeat {
+                    seat_row: non_empty(row),
+                    seat_number: non_empty(letter),
+                    seat_identifier: Some(seat),
+                    ..Default::default()
+                }]
+            }
+            None => Vec::new(),
+        };
+        if let Some(number) = leg
+            .conditional
+            .as_ref()
+            .and_then(|conditional| non_empty(&conditional.frequent_flyer_number))
+        {
+            semantics.membership_program_number = Some(number);
+        }
+    }
+
+    /// Semantic tags of the pass of the leg, see [write_semantics](BoardingPass::write_semantics).
+    pub fn semantics(&self, leg: usize) -> Option<SemanticTags> {
+        self.legs.get(leg)?;
+        let mut semantics = SemanticTags::default();
+        self.write_semantics(leg, &mut semantics);
+        Some(semantics)
+    }
+
+    /// One-leg boarding pass of semantic tags of the pass.
+    ///
+    /// Requires passenger name, airline code, flight number, airport codes and
+    /// departure date (current or original). The passenger is checked in to economy
+    /// with an electronic ticket.
+    pub fn from_semantics(semantics: &SemanticTags) -> Result<Self, BcbpError> {
+        let name = semantics
+            .passenger_name
+            .as_ref()
+            .ok_or(BcbpError::MissingSemantic("passengerName"))?;
+        let mut passenger_name = name.family_name.clone().unwrap_or_default().to_uppercase();
+        if let Some(given_name) = &name.given_name {
+            passenger_name = format!("{}/{}", passenger_name, given_name.to_uppercase());
+        }
+        // Names longer than the item are truncated
+        let passenger_name = passenger_name.chars().take(20).collect();
+
+        let airline_code = semantics
+            .airline_code
+            .clone()
+            .ok_or(BcbpError::MissingSemantic("airlineCode"))?;
+        let flight_number = semantics
+            .flight_number
+            .ok_or(BcbpError::MissingSemantic("flightNumber"))?;
+        let from_airport = semantics
+            .departure_airport_code
+            .clone()
+            .ok_or(BcbpError::MissingSemantic("departureAirportCode"))?;
+        let to_airport = semantics
+            .destination_airport_code
+            .clone()
+            .ok_or(BcbpError::MissingSemantic("destinationAirportCode"))?;
+        let departure = semantics
+            .current_departure_date
+            .or(semantics.original_departure_date)
+            .ok_or(BcbpError::MissingSemantic("originalDepartureDate"))?;
+
+        let seat = semantics
+            .seats
+            .first()
+            .and_then(|seat| {
+                seat.seat_identifier.clone().or_else(|| {
+                    let row = seat.seat_row.as_deref().unwrap_or_default();
+                    let number = seat.seat_number.as_deref().unwrap_or_default();
+                    Some(format!("{}{}", row, number)).filter(|seat| !seat.is_empty())
+                })
+            })
+            .unwrap_or_default();
+        let conditional =
+            semantics
+                .membership_program_number
+                .as_ref()
+                .map(|number| LegConditional {
+                    frequent_flyer_airline: airline_code.clone(),
+                    frequent_flyer_number: number.clone(),
+                    ..Default::default()
+                });
+
+        Ok(Self {
+            passenger_name,
+            electronic_ticket: true,
+            conditional: None,
+            legs: vec![Leg {
+                pnr: semantics.confirmation_number.clone().unwrap_or_default(),
+                from_airport,
+                to_airport,
+                operating_carrier: airline_code,
+                flight_number: flight_number.to_string(),
+                date_of_flight: departure.ordinal() as u16,
+                compartment: 'Y',
+                seat,
+                check_in_sequence: semantics
+                    .boarding_sequence_number
+                    .clone()
+                    .unwrap_or_default(),
+                passenger_status: '1',
+                conditional,
+                airline_data: String::new(),
+            }],
+            security: None,
+        })
+    }
+}
+
+impl FromStr for BoardingPass {
+    type Err = BcbpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BoardingPass::decode(s)
+    }
+}
+
+/// Writes item padded with spaces to its width
+fn item(bcbp: &mut String, item: &'static str, value: &str, width: usize) -> Result<(), BcbpError> {
+    if !value.is_ascii() {
+        return Err(BcbpError::InvalidItem {
+            item,
+            value: String::from(value),
+        });
+    }
+    if value.len() > width {
+        return Err(BcbpError::TooLong {
+            item,
+            max: width,
+            value: String::from(value),
+        });
+    }
+    let _ = write!(bcbp, "{:<width$}", value);
+    Ok(())
+}
+
+/// Writes number padded with zeros and followed by optional letter, such as `0834 ` or `001A`
+fn numbered(
+    bcbp: &mut String,
+    name: &'static str,
+    value: &str,
+    width: usize,
+) -> Result<(), BcbpError> {
+    let (number, suffix) = split_number(value);
+    if number.is_empty() || number.len() >= width || suffix.len() > 1 {
+        // Not a number, such as `INF` seat
+        return item(bcbp, name, value, width);
+    }
+    let _ = write!(bcbp, "{:0>digits$}", number, digits = width - 1);
+    item(bcbp, name, suffix, 1)
+}
+
+/// Writes section prefixed with its size in hex
+fn sized(bcbp: &mut String, item: &'static str, section: &str) -> Result<(), BcbpError> {
+    if section.len() > 0xff {
+        return Err(BcbpError::TooLong {
+            item,
+            max: 0xff,
+            value: String::from(section),
+        });
+    }
+    let _ = write!(bcbp, "{:02X}{}", section.len(), section);
+    Ok(())
+}
+
+/// Conditional items up to the last non-empty one
+fn section(items: &[(&'static str, &str, usize)]) -> Result<String, BcbpError> {
+    let count = items
+        .iter()
+        .rposition(|(_, value, _)| !value.is_empty())
+        .map_or(0, |last| last + 1);
+    let mut section = String::new();
+    for (name, value, width) in &items[..count] {
+        item(&mut section, name, value, *width)?;
+    }
+    Ok(section)
+}
+
+/// Leading digits and the rest
+pub(crate) fn split_number(value: &str) -> (&str, &str) {
+    let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+    value.split_at(digits)
+}
+
+/// Number without padding, such as `834` of `0834 `
+fn unnumbered(value: &str) -> String {
+    let value = value.trim();
+    let (number, suffix) = split_number(value);
+    if number.is_empty() {
+        return String::from(value);
+    }
+    let number = number.trim_start_matches('0');
+    let number = if number.is_empty() { "0" } else { number };
+    format!("{}{}", number, suffix)
+}
+
+/// Remaining BCBP text
+struct Reader<'a>(&'a str);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize, item: &'static str) -> Result<&'a str, BcbpError> {
+        if self.0.len() < len {
+            return Err(BcbpError::Truncated(item));
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn char(&mut self, item: &'static str) -> Result<char, BcbpError> {
+        Ok(self.take(1, item)?.chars().next().unwrap_or(' '))
+    }
+
+    /// Size of section in hex
+    fn hex(&mut self, item: &'static str) -> Result<usize, BcbpError> {
+        let size = self.take(2, item)?;
+        usize::from_str_radix(size, 16).map_err(|_| BcbpError::InvalidItem {
+            item,
+            value: String::from(size),
+        })
+    }
+
+    /// Conditional item, empty or shorter when the section is truncated
+    fn take_conditional(&mut self, len: usize) -> String {
+        let len = len.min(self.0.len());
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        String::from(taken.trim_end())
+    }
+
+    /// Conditional items of leg prefixed with its size
+    fn section(&mut self) -> Result<Option<LegConditional>, BcbpError> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+        let size = self.hex("conditional items of the leg")?;
+        if size == 0 {
+            return Ok(None);
+        }
+        let section = Reader(self.take(size, "conditional items of the leg")?);
+        Ok(Some(LegConditional::read(section)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+    use crate::pass::barcode::BarcodeFormat;
+    use crate::pass::{PassBuilder, make_config};
+
+    const MANDATORY: &str = "M1DESMARAIS/LUC       EABC123 YULFRAAC 0834 326J001A0025 100";
+
+    fn make_leg(from: &str, to: &str, flight_number: &str) -> Leg {
+        Leg {
+            pnr: String::from("ABC123"),
+            from_airport: String::from(from),
+            to_airport: String::from(to),
+            operating_carrier: String::from("AC"),
+            flight_number: String::from(flight_number),
+            date_of_flight: 326,
+            compartment: 'J',
+            seat: String::from("1A"),
+            check_in_sequence: String::from("25"),
+            passenger_status: '1',
+            conditional: None,
+            airline_data: String::new(),
+        }
+    }
+
+    #[test]
+    fn decode_mandatory() {
+        let bcbp = BoardingPass::decode(MANDATORY).unwrap();
+        println!("{:#?}", bcbp);
+
+        assert_eq!(
+            BoardingPass {
+                passenger_name: String::from("DESMARAIS/LUC"),
+                electronic_ticket: true,
+                conditional: None,
+                legs: vec![make_leg("YUL", "FRA", "834")],
+                security: None,
+            },
+            bcbp
+        );
+        assert_eq!(MANDATORY, bcbp.encode().unwrap());
+    }
+
+    #[test]
+    fn multiple_legs_with_conditional_items() {
+        let mut first = make_leg("YUL", "FRA", "834");
+        first.conditional = Some(LegConditional {
+            airline_numeric_code: String::from("014"),
+            document_serial_number: String::from("1234567890"),
+            international_documentation_verification: String::from("1"),
+            marketing_carrier: String::from("AC"),
+            frequent_flyer_airline: String::from("AC"),
+            frequent_flyer_number: String::from("1234567890123"),
+            free_baggage_allowance: String::from("20K"),
+            ..Default::default()
+        });
+        first.airline_data = String::from("YLX58Z");
+        let mut second = make_leg("FRA", "GVA", "3664A");
+        second.operating_carrier = String::from("LH");
+        second.seat = String::from("12C");
+        second.airline_data = String::from("LH");
+
+        let bcbp = BoardingPass {
+            passenger_name: String::from("DESMARAIS/LUC"),
+            electronic_ticket: true,
+            conditional: Some(Conditional {
+                version: VERSION,
+                passenger_description: String::from("0"),
+                source_of_check_in: String::from("O"),
+                source_of_issuance: String::from("O"),
+                date_of_issue: String::from("7325"),
+                document_type: String::from("B"),
+                issuer: String::from("AC"),
+                ..Default::default()
+            }),
+            legs: vec![first, second],
+            security: Some(SecurityData {
+                kind: '1',
+                data: String::from(
+                    "GIWVC5EH7JNT684FVNJ91W2QA4DVN5J8K4F0L0GEQ3DF5TGBN8709HKT5D3DW3GBHFCVHMY7J5T6HFR4",
+                ),
+            }),
+        };
+
+        let encoded = bcbp.encode().unwrap();
+        println!("{}", encoded);
+        assert!(encoded.starts_with("M2DESMARAIS/LUC       EABC123 YULFRAAC 0834 326J001A0025 1"));
+        assert!(encoded.contains(">60B0OO7325BAC 29014"));
+        assert!(encoded.contains("FRAGVALH 3664A326J012C0025 104"));
+        assert!(encoded.contains("^150GIWVC5"));
+
+        assert_eq!(bcbp, BoardingPass::decode(&encoded).unwrap());
+    }
+
+    #[test]
+    fn invalid_bcbp() {
+        assert_eq!(
+            Err(BcbpError::FormatCode),
+            "S1DESMARAIS".parse::<BoardingPass>()
+        );
+        assert_eq!(
+            Err(BcbpError::Truncated("variable size field")),
+            BoardingPass::decode(&MANDATORY[..MANDATORY.len() - 1])
+        );
+        assert_eq!(
+            Err(BcbpError::Truncated("PNR code")),
+            BoardingPass::decode(&MANDATORY.replacen("M1", "M2", 1))
+        );
+
+        let mut bcbp = BoardingPass::decode(MANDATORY).unwrap();
+        bcbp.legs[0].pnr = String::from("ABCDEFGH");
+        assert_eq!(
+            Err(BcbpError::TooLong {
+                item: "PNR code",
+                max: 7,
+                value: String::from("ABCDEFGH")
+            }),
+            bcbp.encode()
+        );
+        bcbp.legs.clear();
+        assert_eq!(Err(BcbpError::LegCount(0)), bcbp.encode());
+    }
+
+    #[test]
+    fn builder_leg_out_of_range() {
+        let bcbp = BoardingPass::decode(MANDATORY).unwrap();
+        let builder = PassBuilder::new(make_config());
+        let err = builder
+            .bcbp_barcode(&bcbp, 1, BarcodeFormat::PDF417)
+            .err()
+            .unwrap();
+        assert_eq!(BcbpError::NoSuchLeg(1), err);
+    }
+
+    #[test]
+    fn version_without_conditional_items() {
+        let message = format!("{}06>50000", &MANDATORY[..MANDATORY.len() - 2]);
+        let bcbp = BoardingPass::decode(&message).unwrap();
+        let conditional = bcbp.conditional.as_ref().unwrap();
+        assert_eq!(5, conditional.version);
+        assert!(conditional.issuer.is_empty());
+        assert_eq!(message, bcbp.encode().unwrap());
+    }
+
+    #[test]
+    fn semantics_mapping() {
+        let mut bcbp = BoardingPass::decode(MANDATORY).unwrap();
+        let semantics = bcbp.semantics(0).unwrap();
+        let name = semantics.passenger_name.as_ref().unwrap();
+        assert_eq!(Some("DESMARAIS"), name.family_name.as_deref());
+        assert_eq!(Some("LUC"), name.given_name.as_deref());
+        assert_eq!(Some("AC"), semantics.airline_code.as_deref());
+        assert_eq!(Some("AC834"), semantics.flight_code.as_deref());
+        assert_eq!(Some("FRA"), semantics.destination_airport_code.as_deref());
+        assert_eq!(Some("ABC123"), semantics.confirmation_number.as_deref());
+        assert_eq!(Some("25"), semantics.boarding_sequence_number.as_deref());
+        assert_eq!(Some("1"), semantics.seats[0].seat_row.as_deref());
+        assert_eq!(Some("A"), semantics.seats[0].seat_number.as_deref());
+        assert!(bcbp.semantics(1).is_none());
+
+        // Back from semantics, with the date of the flight
+        let semantics = SemanticTags {
+            original_departure_date: FixedOffset::west_opt(5 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2024, 11, 21, 18, 30, 0)
+                .single(),
+            ..semantics
+        };
+        let from_semantics = BoardingPass::from_semantics(&semantics).unwrap();
+        bcbp.legs[0].compartment = 'Y';
+        assert_eq!(bcbp, from_semantics);
+
+        assert_eq!(
+            Err(BcbpError::MissingSemantic("passengerName")),
+            BoardingPass::from_semantics(&SemanticTags::default())
+        );
+    }
+}
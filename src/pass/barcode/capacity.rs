@@ -0,0 +1,193 @@
+use crate::BarcodeError;
+
+use super::super::Pass;
+use super::{Barcode, BarcodeFormat, ErrorCorrection};
+
+/// Codewords of the largest Aztec symbol, 32 layers of 12-bit codewords
+const AZTEC_CODEWORDS: usize = 1437;
+
+/// Codewords of the largest PDF417 symbol, including symbol length descriptor
+const PDF417_CODEWORDS: usize = 928;
+
+impl BarcodeFormat {
+    /// Maximum message size of the format, in bytes, `None` for Code128 that has no fixed limit.
+    ///
+    /// Messages are encoded byte by byte, like Wallet does, so capacities are these of
+    /// binary data: up to 2953 bytes in QR, 1932 in Aztec and 1106 in PDF417.
+    ///
+    /// ```
+    /// use neopasses::barcode::{BarcodeFormat, ErrorCorrection};
+    ///
+    /// assert_eq!(Some(2331), BarcodeFormat::QR.capacity(ErrorCorrection::Medium));
+    /// assert_eq!(None, BarcodeFormat::Code128.capacity(ErrorCorrection::Medium));
+    /// ```
+    pub fn capacity(&self, error_correction: ErrorCorrection) -> Option<usize> {
+        let level = error_correction as usize;
+        match self {
+            // Version 40 in byte mode
+            BarcodeFormat::QR => Some([2953, 2331, 1663, 1273][level]),
+            BarcodeFormat::Aztec => {
+                // Error correction percent plus 3 codewords, see ISO/IEC 24778
+                let percent = [10, 23, 36, 50][level];
+                let data = AZTEC_CODEWORDS - (AZTEC_CODEWORDS * percent).div_ceil(100) - 3;
+                // Binary shift takes 5 bits, and 5 + 11 bits for length
+                Some((data * 12 - 21) / 8)
+            }
+            BarcodeFormat::PDF417 => {
+                let level = [1, 2, 4, 6][level];
+                // Length descriptor, error correction and byte compaction latch
+                let data = PDF417_CODEWORDS - 1 - (2 << level) - 1;
+                // 5 codewords for every 6 bytes, one codeword for each of the rest
+                Some(data / 5 * 6 + data % 5)
+            }
+            BarcodeFormat::Code128 => None,
+        }
+    }
+
+    /// Checks that Apple Watch displays the format, it doesn't display Code128.
+    pub fn is_watch_compatible(&self) -> bool {
+        *self != BarcodeFormat::Code128
+    }
+}
+
+impl Barcode {
+    /// Checks that message can be encoded in [message_encoding](Barcode::message_encoding)
+    /// and fits [capacity](BarcodeFormat::capacity) of the format.
+    ///
+    /// ```
+    /// use neopasses::BarcodeError;
+    /// use neopasses::barcode::{Barcode, BarcodeFormat, ErrorCorrection, MessageEncoding};
+    ///
+    /// let barcode = Barcode {
+    ///     message: "A".repeat(2000),
+    ///     format: BarcodeFormat::QR,
+    ///     ..Default::default()
+    /// };
+    /// assert!(barcode.validate(ErrorCorrection::Medium).is_ok());
+    /// assert_eq!(
+    ///     Err(BarcodeError::TooLong { bytes: 2000, max: 1663 }),
+    ///     barcode.validate(ErrorCorrection::Quartile)
+    /// );
+    ///
+    /// let barcode = Barcode {
+    ///     message: String::from("日本"),
+    ///     message_encoding: MessageEncoding::Iso8859_1,
+    ///     ..Default::default()
+    /// };
+    /// assert!(barcode.validate(ErrorCorrection::Medium).is_err());
+    /// ```
+    pub fn validate(&self, error_correction: ErrorCorrection) -> Result<(), BarcodeError> {
+        let bytes = self.message_bytes()?.len();
+        match self.format.capacity(error_correction) {
+            Some(max) if bytes > max => Err(BarcodeError::TooLong { bytes, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Pass {
+    /// Add copy of the first Code128 barcode in `format`, for Apple Watch that doesn't
+    /// display Code128.
+    ///
+    /// Does nothing if the pass already has a barcode Apple Watch displays,
+    /// or `format` is Code128. Returns whether a barcode was added.
+    pub fn add_watch_fallback_barcode(&mut self, format: BarcodeFormat) -> bool {
+        if !format.is_watch_compatible()
+            || self
+                .barcodes
+                .iter()
+                .any(|barcode| barcode.format.is_watch_compatible())
+        {
+            return false;
+        }
+        let Some(code128) = self.barcodes.first() else {
+            return false;
+        };
+        let fallback = Barcode {
+            format,
+            ..code128.clone()
+        };
+        self.barcodes.push(fallback);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::{PassBuilder, make_config};
+
+    #[test]
+    fn capacities() {
+        let capacities = |format: BarcodeFormat| {
+            [
+                ErrorCorrection::Low,
+                ErrorCorrection::Medium,
+                ErrorCorrection::Quartile,
+                ErrorCorrection::High,
+            ]
+            .map(|level| format.capacity(level).unwrap())
+        };
+        assert_eq!([2953, 2331, 1663, 1273], capacities(BarcodeFormat::QR));
+        assert_eq!([1106, 1101, 1072, 957], capacities(BarcodeFormat::PDF417));
+        assert_eq!([1932, 1651, 1371, 1069], capacities(BarcodeFormat::Aztec));
+    }
+
+    #[test]
+    fn fit_and_render() {
+        for format in [
+            BarcodeFormat::QR,
+            BarcodeFormat::Aztec,
+            BarcodeFormat::PDF417,
+        ] {
+            let max = format.capacity(ErrorCorrection::Medium).unwrap();
+            let barcode = Barcode {
+                message: "é".repeat(max),
+                format: format.clone(),
+                ..Default::default()
+            };
+            assert!(barcode.validate(ErrorCorrection::Medium).is_ok());
+            // The largest message is rendered
+            assert!(barcode.render(&Default::default()).is_ok());
+
+            let barcode = Barcode {
+                message: "é".repeat(max + 1),
+                ..barcode
+            };
+            assert_eq!(
+                Err(BarcodeError::TooLong {
+                    bytes: max + 1,
+                    max
+                }),
+                barcode.validate(ErrorCorrection::Medium)
+            );
+        }
+    }
+
+    #[test]
+    fn watch_fallback() {
+        let code128 = Barcode {
+            message: String::from("1234567890"),
+            format: BarcodeFormat::Code128,
+            alt_text: Some(String::from("1234567890")),
+            ..Default::default()
+        };
+        let mut pass = PassBuilder::new(make_config())
+            .add_barcode(code128.clone())
+            .build();
+
+        assert!(!pass.add_watch_fallback_barcode(BarcodeFormat::Code128));
+        assert!(pass.add_watch_fallback_barcode(BarcodeFormat::QR));
+        assert!(!pass.add_watch_fallback_barcode(BarcodeFormat::Aztec));
+        assert_eq!(
+            vec![
+                code128.clone(),
+                Barcode {
+                    format: BarcodeFormat::QR,
+                    ..code128
+                }
+            ],
+            pass.barcodes
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use encoding_rs::Encoding;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+
+use crate::BarcodeError;
+use crate::error::ValueError;
+
+use super::Barcode;
+
+/// IANA character set of [message_encoding](Barcode::message_encoding)
+///
+/// Parsed case-insensitively from its IANA name or a common alias, serialized as the IANA name.
+/// Other names are read from pass.json as [Other](MessageEncoding::Other), and
+/// reported by [Pass::validate](crate::Pass::validate) if they can't be used.
+///
+/// ```
+/// use neopasses::barcode::MessageEncoding;
+///
+/// let encoding: MessageEncoding = "latin1".parse().unwrap();
+/// assert_eq!(MessageEncoding::Iso8859_1, encoding);
+/// assert_eq!("iso-8859-1", encoding.name());
+///
+/// assert_eq!(vec![0x43, 0x61, 0x66, 0xe9], encoding.encode("Café").unwrap());
+/// assert!(encoding.encode("€").is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[serde(from = "String", into = "String")]
+pub enum MessageEncoding {
+    /// Latin-1, every character is one byte
+    #[default]
+    Iso8859_1,
+    /// Latin-2, Central European
+    Iso8859_2,
+    /// Cyrillic
+    Iso8859_5,
+    /// Greek
+    Iso8859_7,
+    /// Latin-9, Latin-1 with euro sign
+    Iso8859_15,
+    /// Central European Windows code page
+    Windows1250,
+    /// Cyrillic Windows code page
+    Windows1251,
+    /// Western Windows code page, superset of Latin-1
+    Windows1252,
+    /// 7-bit ASCII
+    UsAscii,
+    Utf8,
+    /// UTF-16 with byte order mark, little-endian like Apple devices
+    Utf16,
+    /// UTF-16 big-endian without byte order mark
+    Utf16Be,
+    /// UTF-16 little-endian without byte order mark
+    Utf16Le,
+    /// Japanese
+    ShiftJis,
+    /// Japanese
+    EucJp,
+    /// Korean
+    EucKr,
+    /// Simplified Chinese
+    Gb18030,
+    /// Traditional Chinese
+    Big5,
+    /// Any other character set, by name
+    Other(String),
+}
+
+impl MessageEncoding {
+    /// All named encodings.
+    pub const ALL: [MessageEncoding; 18] = [
+        MessageEncoding::Iso8859_1,
+        MessageEncoding::Iso8859_2,
+        MessageEncoding::Iso8859_5,
+        MessageEncoding::Iso8859_7,
+        MessageEncoding::Iso8859_15,
+        MessageEncoding::Windows1250,
+        MessageEncoding::Windows1251,
+        MessageEncoding::Windows1252,
+        MessageEncoding::UsAscii,
+        MessageEncoding::Utf8,
+        MessageEncoding::Utf16,
+        MessageEncoding::Utf16Be,
+        MessageEncoding::Utf16Le,
+        MessageEncoding::ShiftJis,
+        MessageEncoding::EucJp,
+        MessageEncoding::EucKr,
+        MessageEncoding::Gb18030,
+        MessageEncoding::Big5,
+    ];
+
+    /// IANA name of the encoding.
+    pub fn name(&self) -> &str {
+        match self {
+            MessageEncoding::Iso8859_1 => "iso-8859-1",
+            MessageEncoding::Iso8859_2 => "iso-8859-2",
+            MessageEncoding::Iso8859_5 => "iso-8859-5",
+            MessageEncoding::Iso8859_7 => "iso-8859-7",
+            MessageEncoding::Iso8859_15 => "iso-8859-15",
+            MessageEncoding::Windows1250 => "windows-1250",
+            MessageEncoding::Windows1251 => "windows-1251",
+            MessageEncoding::Windows1252 => "windows-1252",
+            MessageEncoding::UsAscii => "us-ascii",
+            MessageEncoding::Utf8 => "utf-8",
+            MessageEncoding::Utf16 => "utf-16",
+            MessageEncoding::Utf16Be => "utf-16be",
+            MessageEncoding::Utf16Le => "utf-16le",
+            MessageEncoding::ShiftJis => "shift_jis",
+            MessageEncoding::EucJp => "euc-jp",
+            MessageEncoding::EucKr => "euc-kr",
+            MessageEncoding::Gb18030 => "gb18030",
+            MessageEncoding::Big5 => "big5",
+            MessageEncoding::Other(name) => name,
+        }
+    }
+
+    /// Aliases accepted by `FromStr` besides the name
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            MessageEncoding::Iso8859_1 => &["iso8859-1", "iso_8859-1", "latin1", "l1"],
+            MessageEncoding::Iso8859_2 => &["iso8859-2", "iso_8859-2", "latin2", "l2"],
+            MessageEncoding::Iso8859_5 => &["iso8859-5", "iso_8859-5", "cyrillic"],
+            MessageEncoding::Iso8859_7 => &["iso8859-7", "iso_8859-7", "greek"],
+            MessageEncoding::Iso8859_15 => &["iso8859-15", "iso_8859-15", "latin-9"],
+            MessageEncoding::Windows1250 => &["cp1250"],
+            MessageEncoding::Windows1251 => &["cp1251"],
+            MessageEncoding::Windows1252 => &["cp1252"],
+            MessageEncoding::UsAscii => &["ascii", "us"],
+            MessageEncoding::Utf8 => &["utf8"],
+            MessageEncoding::Utf16 => &["utf16"],
+            MessageEncoding::Utf16Be => &["utf16be"],
+            MessageEncoding::Utf16Le => &["utf16le"],
+            MessageEncoding::ShiftJis => &["shift-jis", "sjis", "ms_kanji"],
+            MessageEncoding::EucJp => &["eucjp"],
+            MessageEncoding::EucKr => &["euckr"],
+            MessageEncoding::Gb18030 => &["gb-18030"],
+            MessageEncoding::Big5 => &["big-5", "cn-big5"],
+            MessageEncoding::Other(_) => &[],
+        }
+    }
+
+    /// Encode text, fails if some characters can't be represented.
+    ///
+    /// [Other](MessageEncoding::Other) encodings are supported if `encoding_rs` knows them.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, BarcodeError> {
+        let unencodable = || BarcodeError::Unencodable(self.clone());
+        match self {
+            // `encoding_rs` treats these as windows-1252, that maps more characters
+            MessageEncoding::Iso8859_1 | MessageEncoding::UsAscii => {
+                let max = match self {
+                    MessageEncoding::UsAscii => 0x7f,
+                    _ => 0xff,
+                };
+                text.chars()
+                    .map(|c| {
+                        u8::try_from(c)
+                            .ok()
+                            .filter(|b| *b <= max)
+                            .ok_or_else(unencodable)
+                    })
+                    .collect()
+            }
+            // `encoding_rs` encodes UTF-16 as UTF-8
+            MessageEncoding::Utf16 | MessageEncoding::Utf16Be | MessageEncoding::Utf16Le => {
+                let big_endian = *self == MessageEncoding::Utf16Be;
+                let bom = match self {
+                    MessageEncoding::Utf16 => Some('\u{feff}'),
+                    _ => None,
+                };
+                let mut buf = [0u16; 2];
+                Ok(bom
+                    .into_iter()
+                    .chain(text.chars())
+                    .flat_map(|c| c.encode_utf16(&mut buf).to_vec())
+                    .flat_map(|unit| match big_endian {
+                        true => unit.to_be_bytes(),
+                        false => unit.to_le_bytes(),
+                    })
+                    .collect())
+            }
+            MessageEncoding::Other(name) => {
+                // `encoding_rs` can't encode to UTF-16 and replacement encodings
+                let encoding = Encoding::for_label(name.trim().as_bytes())
+                    .filter(|encoding| encoding.output_encoding() == *encoding)
+                    .ok_or_else(|| BarcodeError::UnknownEncoding(name.clone()))?;
+                let (bytes, _, unmappable) = encoding.encode(text);
+                if unmappable {
+                    return Err(unencodable());
+                }
+                Ok(bytes.into_owned())
+            }
+            _ => {
+                let encoding = Encoding::for_label(self.name().as_bytes())
+                    .expect("names are known to encoding_rs");
+                let (bytes, _, unmappable) = encoding.encode(text);
+                if unmappable {
+                    return Err(unencodable());
+                }
+                Ok(bytes.into_owned())
+            }
+        }
+    }
+}
+
+impl fmt::Display for MessageEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for MessageEncoding {
+    type Err = ValueError;
+
+    /// Parse `MessageEncoding` from its IANA name or alias, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label = s.trim().to_ascii_lowercase();
+        MessageEncoding::ALL
+            .into_iter()
+            .find(|encoding| encoding.name() == label || encoding.aliases().contains(&&*label))
+            .ok_or_else(|| ValueError::MessageEncoding(String::from(s)))
+    }
+}
+
+impl From<String> for MessageEncoding {
+    /// Parse `MessageEncoding`, unknown names are kept as [Other](MessageEncoding::Other)
+    fn from(s: String) -> Self {
+        s.parse().unwrap_or(MessageEncoding::Other(s))
+    }
+}
+
+impl From<MessageEncoding> for String {
+    fn from(encoding: MessageEncoding) -> Self {
+        match encoding {
+            MessageEncoding::Other(name) => name,
+            encoding => String::from(encoding.name()),
+        }
+    }
+}
+
+impl JsonSchema for MessageEncoding {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("MessageEncoding")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let names: Vec<&str> = MessageEncoding::ALL.iter().map(|e| e.name()).collect();
+        json_schema!({
+            "type": "string",
+            "examples": names
+        })
+    }
+}
+
+impl Barcode {
+    /// Message converted to bytes with [message_encoding](Barcode::message_encoding).
+    pub fn message_bytes(&self) -> Result<Vec<u8>, BarcodeError> {
+        self.message_encoding.encode(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::barcode::ErrorCorrection;
+
+    #[test]
+    fn encode_message() {
+        let encode = |encoding: MessageEncoding, text: &str| encoding.encode(text);
+
+        assert_eq!(Ok(vec![0x80]), encode(MessageEncoding::Windows1252, "€"));
+        assert_eq!(Ok(vec![0xa4]), encode(MessageEncoding::Iso8859_15, "€"));
+        assert_eq!(
+            Ok("Café".as_bytes().to_vec()),
+            encode(MessageEncoding::Utf8, "Café")
+        );
+        assert_eq!(
+            Ok(vec![0xff, 0xfe, 0x41, 0x00]),
+            encode(MessageEncoding::Utf16, "A")
+        );
+        assert_eq!(
+            Ok(vec![0xd8, 0x3d, 0xdc, 0xa9]),
+            encode(MessageEncoding::Utf16Be, "💩")
+        );
+        assert_eq!(
+            Ok(vec![0x93, 0xfa, 0x96, 0x7b]),
+            encode(MessageEncoding::ShiftJis, "日本")
+        );
+        assert_eq!(
+            Err(BarcodeError::Unencodable(MessageEncoding::UsAscii)),
+            encode(MessageEncoding::UsAscii, "é")
+        );
+        assert_eq!(
+            Err(BarcodeError::Unencodable(MessageEncoding::Iso8859_5)),
+            encode(MessageEncoding::Iso8859_5, "日本")
+        );
+
+        // Other encodings known to encoding_rs
+        let turkish = MessageEncoding::Other(String::from("iso-8859-9"));
+        assert_eq!(Ok(vec![0xdd]), encode(turkish, "İ"));
+        let klingon = MessageEncoding::Other(String::from("klingon"));
+        assert_eq!(
+            Err(BarcodeError::UnknownEncoding(String::from("klingon"))),
+            encode(klingon, "A")
+        );
+        let utf16 = MessageEncoding::Other(String::from("unicodefffe"));
+        assert!(encode(utf16, "A").is_err());
+    }
+
+    #[test]
+    fn parse_encoding() {
+        for encoding in MessageEncoding::ALL {
+            assert_eq!(Ok(encoding.clone()), encoding.name().parse());
+            assert_eq!(Ok(encoding.clone()), encoding.name().to_uppercase().parse());
+        }
+        assert_eq!(Ok(MessageEncoding::Utf8), "UTF8".parse());
+        assert_eq!(
+            Err(ValueError::MessageEncoding(String::from("klingon"))),
+            "klingon".parse::<MessageEncoding>()
+        );
+
+        let encoding: MessageEncoding = serde_json::from_str(r#""Shift_JIS""#).unwrap();
+        assert_eq!(MessageEncoding::ShiftJis, encoding);
+        assert_eq!(r#""shift_jis""#, serde_json::to_string(&encoding).unwrap());
+
+        // Unknown names are kept, validation reports them
+        let encoding: MessageEncoding = serde_json::from_str(r#""klingon""#).unwrap();
+        assert_eq!(MessageEncoding::Other(String::from("klingon")), encoding);
+        assert_eq!(r#""klingon""#, serde_json::to_string(&encoding).unwrap());
+
+        let barcode: Barcode = serde_json::from_str(
+            r#"{"message": "A", "format": "PKBarcodeFormatQR", "messageEncoding": "klingon"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(BarcodeError::UnknownEncoding(String::from("klingon"))),
+            barcode.validate(ErrorCorrection::Medium)
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::fmt::Write;
+
+use rxing::pdf417::encoder::Dimensions;
+use rxing::{EncodeHints, MultiFormatWriter, Writer};
+
+use crate::BarcodeError;
+
+use super::{Barcode, BarcodeFormat};
+
+/// Error correction level, mapped to the closest level of each format
+///
+/// | Level      | QR | Aztec | PDF417 |
+/// |------------|----|-------|--------|
+/// | `Low`      | L  | 10%   | 1      |
+/// | `Medium`   | M  | 23%   | 2      |
+/// | `Quartile` | Q  | 36%   | 4      |
+/// | `High`     | H  | 50%   | 6      |
+///
+/// Code128 has no error correction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorCorrection {
+    Low,
+    #[default]
+    Medium,
+    Quartile,
+    High,
+}
+
+impl ErrorCorrection {
+    /// Level in the form of `rxing` hint
+    fn hint(self, format: &BarcodeFormat) -> Option<String> {
+        let levels = match format {
+            BarcodeFormat::QR => ["L", "M", "Q", "H"],
+            BarcodeFormat::Aztec => ["10", "23", "36", "50"],
+            BarcodeFormat::PDF417 => ["1", "2", "4", "6"],
+            BarcodeFormat::Code128 => return None,
+        };
+        Some(String::from(levels[self as usize]))
+    }
+}
+
+/// Options of [Barcode::render]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Size of the narrowest bar or the smallest square, in pixels
+    pub module_size: u32,
+
+    /// Blank margin around the symbol, in modules.
+    ///
+    /// Default of the format when `None`: 4 for QR, 1 for Aztec, 2 for PDF417 and 10 for Code128.
+    pub quiet_zone: Option<u32>,
+
+    /// Error correction level of 2D formats
+    pub error_correction: ErrorCorrection,
+
+    /// Height of Code128 bars, in modules
+    pub bar_height: u32,
+}
+
+impl Default for RenderOptions {
+    /// Creates `RenderOptions` with 4 pixel modules and default quiet zone.
+    fn default() -> Self {
+        Self {
+            module_size: 4,
+            quiet_zone: None,
+            error_correction: ErrorCorrection::default(),
+            bar_height: 40,
+        }
+    }
+}
+
+/// Barcode symbol rendered by [Barcode::render]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarcodeImage {
+    /// Dark modules of the symbol, row by row
+    modules: Vec<bool>,
+    columns: u32,
+    rows: u32,
+    /// Height of a row, in modules
+    row_height: u32,
+    quiet_zone: u32,
+    module_size: u32,
+}
+
+impl Barcode {
+    /// Render barcode the way Wallet displays it.
+    ///
+    /// The message is converted to bytes with [message_encoding](Barcode::message_encoding)
+    /// (see [message_bytes](Barcode::message_bytes)) and encoded without ECI, like Wallet does.
+    ///
+    /// ```
+    /// use neopasses::barcode::{Barcode, BarcodeFormat, ErrorCorrection, RenderOptions};
+    ///
+    /// let barcode = Barcode {
+    ///     message: String::from("ABCDEFG1234567890"),
+    ///     format: BarcodeFormat::QR,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let image = barcode
+    ///     .render(&RenderOptions {
+    ///         module_size: 2,
+    ///         quiet_zone: Some(1),
+    ///         error_correction: ErrorCorrection::High,
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// // Version 2 QR code is 25 modules wide
+    /// assert_eq!((25 + 2) * 2, image.width());
+    /// assert!(image.to_svg().starts_with("<svg"));
+    /// assert!(image.to_png().starts_with(b"\x89PNG"));
+    /// ```
+    pub fn render(&self, options: &RenderOptions) -> Result<BarcodeImage, BarcodeError> {
+        if options.module_size == 0 {
+            return Err(BarcodeError::ModuleSize);
+        }
+
+        // Each byte as a Latin-1 character, encoded back to the same byte
+        let contents: String = self.message_bytes()?.into_iter().map(char::from).collect();
+        let format = match self.format {
+            BarcodeFormat::QR => rxing::BarcodeFormat::QR_CODE,
+            BarcodeFormat::PDF417 => rxing::BarcodeFormat::PDF_417,
+            BarcodeFormat::Aztec => rxing::BarcodeFormat::AZTEC,
+            BarcodeFormat::Code128 => rxing::BarcodeFormat::CODE_128,
+        };
+        let hints = EncodeHints {
+            ErrorCorrection: options.error_correction.hint(&self.format),
+            Margin: Some(String::from("0")),
+            // Up to 90 rows allowed by the standard, instead of 30
+            Pdf417Dimensions: Some(Dimensions::new(1, 30, 3, 90)),
+            ..Default::default()
+        };
+        let matrix = MultiFormatWriter
+            .encode_with_hints(&contents, &format, 0, 0, &hints)
+            .map_err(|err| BarcodeError::Encode(err.to_string()))?;
+
+        let (columns, rows) = (matrix.getWidth(), matrix.getHeight());
+        let modules = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .map(|(x, y)| matrix.get(x, y))
+            .collect();
+        let default_quiet_zone = match self.format {
+            BarcodeFormat::QR => 4,
+            BarcodeFormat::Aztec => 1,
+            BarcodeFormat::PDF417 => 2,
+            BarcodeFormat::Code128 => 10,
+        };
+        let image = BarcodeImage {
+            modules,
+            columns,
+            rows,
+            row_height: match self.format {
+                BarcodeFormat::Code128 => options.bar_height.max(1),
+                _ => 1,
+            },
+            quiet_zone: options.quiet_zone.unwrap_or(default_quiet_zone),
+            module_size: options.module_size,
+        };
+        // Sizes are computed unchecked later, the number of pixels must fit too
+        image
+            .checked_size()
+            .and_then(|(width, height)| width.checked_mul(height))
+            .ok_or(BarcodeError::ImageTooLarge)?;
+        Ok(image)
+    }
+}
+
+impl BarcodeImage {
+    /// Width of the symbol without quiet zone, in modules
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Height of the symbol without quiet zone, in modules
+    pub fn rows(&self) -> u32 {
+        self.rows * self.row_height
+    }
+
+    /// Checks that module of the symbol is dark, quiet zone is not included
+    pub fn is_dark(&self, column: u32, row: u32) -> bool {
+        column < self.columns()
+            && row < self.rows()
+            && self.modules[(row / self.row_height * self.columns + column) as usize]
+    }
+
+    /// Width and height of the image in pixels, `None` on overflow
+    fn checked_size(&self) -> Option<(u32, u32)> {
+        let quiet_zone = self.quiet_zone.checked_mul(2)?;
+        let size = |modules: u32| {
+            modules
+                .checked_add(quiet_zone)?
+                .checked_mul(self.module_size)
+        };
+        Some((
+            size(self.columns)?,
+            size(self.rows.checked_mul(self.row_height)?)?,
+        ))
+    }
+
+    /// Width of the image, in pixels
+    pub fn width(&self) -> u32 {
+        (self.columns() + 2 * self.quiet_zone) * self.module_size
+    }
+
+    /// Height of the image, in pixels
+    pub fn height(&self) -> u32 {
+        (self.rows() + 2 * self.quiet_zone) * self.module_size
+    }
+
+    /// Image as SVG, dark modules are drawn as a single path.
+    pub fn to_svg(&self) -> String {
+        self.svg_element(&format!(
+            r#"xmlns="http://www.w3.org/2000/svg" width="{}" height="{}""#,
+            self.width(),
+            self.height()
+        ))
+    }
+
+    /// SVG element scaled to fit the box, to be embedded into another SVG
+    pub(crate) fn svg_in(&self, x: f64, y: f64, width: f64, height: f64) -> String {
+        self.svg_element(&format!(
+            r#"x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" preserveAspectRatio="xMidYMid meet""#,
+            x, y, width, height
+        ))
+    }
+
+    fn svg_element(&self, attributes: &str) -> String {
+        let width = self.columns() + 2 * self.quiet_zone;
+        let height = self.rows() + 2 * self.quiet_zone;
+        // Horizontal runs of dark modules, one per each row of the symbol
+        let mut path = String::new();
+        for row in 0..self.rows {
+            let y = self.quiet_zone + row * self.row_height;
+            let mut column = 0;
+            while column < self.columns {
+                if !self.is_dark(column, row * self.row_height) {
+                    column += 1;
+                    continue;
+                }
+                let start = column;
+                while column < self.columns && self.is_dark(column, row * self.row_height) {
+                    column += 1;
+                }
+                let _ = write!(
+                    path,
+                    "M{} {}h{}v{}h-{}z",
+                    self.quiet_zone + start,
+                    y,
+                    column - start,
+                    self.row_height,
+                    column - start
+                );
+            }
+        }
+        format!(
+            concat!(
+                r#"<svg {} viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+                r##"<rect width="{}" height="{}" fill="#ffffff"/><path d="{}" fill="#000000"/></svg>"##
+            ),
+            attributes, width, height, width, height, path
+        )
+    }
+
+    /// Image as 8-bit grayscale PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        let (width, height) = (self.width(), self.height());
+        let offset = self.quiet_zone * self.module_size;
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let dark = x >= offset
+                    && y >= offset
+                    && self.is_dark(
+                        (x - offset) / self.module_size,
+                        (y - offset) / self.module_size,
+                    );
+                pixels.push(if dark { 0 } else { 255 });
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width, height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        // Writing to memory fails only on empty image, module size is checked in render
+        let mut writer = encoder.write_header().expect("image is not empty");
+        writer
+            .write_image_data(&pixels)
+            .expect("image data matches its size");
+        writer.finish().expect("image is complete");
+        png
+    }
+
+    /// Image as 8-bit grayscale pixels, row by row
+    #[cfg(test)]
+    fn to_luma(&self) -> Vec<u8> {
+        let decoder = png::Decoder::new(std::io::Cursor::new(self.to_png()));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut pixels).unwrap();
+        pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::barcode::MessageEncoding;
+
+    fn decode(image: &BarcodeImage, format: rxing::BarcodeFormat) -> String {
+        let result = rxing::helpers::detect_in_luma(
+            image.to_luma(),
+            image.width(),
+            image.height(),
+            Some(format),
+        )
+        .unwrap();
+        String::from(result.getText())
+    }
+
+    #[test]
+    fn render_all_formats() {
+        let formats = [
+            (BarcodeFormat::QR, rxing::BarcodeFormat::QR_CODE),
+            (BarcodeFormat::Aztec, rxing::BarcodeFormat::AZTEC),
+            (BarcodeFormat::PDF417, rxing::BarcodeFormat::PDF_417),
+            (BarcodeFormat::Code128, rxing::BarcodeFormat::CODE_128),
+        ];
+        for (format, rxing_format) in formats {
+            let barcode = Barcode {
+                message: String::from("TICKET-1234567890"),
+                format: format.clone(),
+                ..Default::default()
+            };
+            let image = barcode.render(&RenderOptions::default()).unwrap();
+            println!("{:?}: {}x{} modules", format, image.columns(), image.rows());
+
+            assert_eq!("TICKET-1234567890", decode(&image, rxing_format));
+            assert!(image.to_svg().contains("<path d=\"M"));
+        }
+    }
+
+    #[test]
+    fn render_options() {
+        let barcode = Barcode {
+            message: String::from("Hello world!"),
+            ..Default::default()
+        };
+        let low = barcode
+            .render(&RenderOptions {
+                module_size: 1,
+                quiet_zone: Some(0),
+                error_correction: ErrorCorrection::Low,
+                ..Default::default()
+            })
+            .unwrap();
+        let high = barcode
+            .render(&RenderOptions {
+                module_size: 3,
+                error_correction: ErrorCorrection::High,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(21, low.columns());
+        assert_eq!(21, low.width());
+        assert!(high.columns() > low.columns());
+        assert_eq!((high.columns() + 8) * 3, high.width());
+        assert_eq!(high.width(), high.height());
+        // Finder pattern in the corner
+        assert!(low.is_dark(0, 0) && low.is_dark(6, 6) && !low.is_dark(7, 7));
+
+        let code128 = Barcode {
+            format: BarcodeFormat::Code128,
+            ..barcode.clone()
+        }
+        .render(&RenderOptions {
+            module_size: 2,
+            bar_height: 30,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(30, code128.rows());
+        assert_eq!((30 + 20) * 2, code128.height());
+
+        let err = barcode
+            .render(&RenderOptions {
+                module_size: 0,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(BarcodeError::ModuleSize, err);
+
+        // Size in pixels overflows
+        let err = barcode
+            .render(&RenderOptions {
+                module_size: u32::MAX / 8,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(BarcodeError::ImageTooLarge, err);
+        let err = barcode
+            .render(&RenderOptions {
+                quiet_zone: Some(u32::MAX),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(BarcodeError::ImageTooLarge, err);
+    }
+
+    #[test]
+    fn message_encoding() {
+        // Latin-1 bytes are encoded as is
+        let image = Barcode {
+            message: String::from("Café"),
+            message_encoding: MessageEncoding::Iso8859_1,
+            ..Default::default()
+        }
+        .render(&RenderOptions::default())
+        .unwrap();
+        assert_eq!("Café", decode(&image, rxing::BarcodeFormat::QR_CODE));
+
+        let err = Barcode {
+            message: String::from("€"),
+            ..Default::default()
+        }
+        .render(&RenderOptions::default())
+        .unwrap_err();
+        assert_eq!(BarcodeError::Unencodable(MessageEncoding::Iso8859_1), err);
+    }
+}
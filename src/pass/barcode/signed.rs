@@ -0,0 +1,496 @@
+//! Signed barcode payloads, for gate scanners to reject forged tickets.
+//!
+//! A payload binds [serial number](crate::PassConfig::serial_number) and
+//! [pass type identifier](crate::PassConfig::pass_type_identifier) of the pass,
+//! an optional validity window and custom claims. It's signed with Ed25519, so scanners
+//! only need the public key, or with HMAC-SHA256 shared between the issuer and scanners.
+//!
+//! Payload is ASCII text `<algorithm>.<claims>.<signature>`, where algorithm is `E1` (Ed25519)
+//! or `H1` (HMAC-SHA256), claims are JSON with short keys and both claims and signature are
+//! base64url without padding. The signature covers `<algorithm>.<claims>`.
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use neopasses::{PassBuilder, PassConfig, SignedPayloadError};
+//! use neopasses::barcode::signed::{Claims, SigningKey, Verifier};
+//!
+//! let key = SigningKey::HmacSha256(b"gate scanner secret".to_vec());
+//! let pass = PassBuilder::new(PassConfig {
+//!     organization_name: "Apple inc.".into(),
+//!     description: "Event ticket".into(),
+//!     pass_type_identifier: "pass.com.example".parse().unwrap(),
+//!     team_identifier: "AA00AA0A0A".parse().unwrap(),
+//!     serial_number: "ABCDEFG1234567890".into(),
+//! })
+//! .signed_barcode(
+//!     Claims {
+//!         not_after: Utc.with_ymd_and_hms(2024, 2, 8, 0, 0, 0).single(),
+//!         ..Default::default()
+//!     }
+//!     .claim("seat", "A12"),
+//!     &key,
+//! )
+//! .build();
+//!
+//! // At the gate
+//! let verifier = Verifier::new(key.verifying_key())
+//!     .pass_type_identifier("pass.com.example".parse().unwrap());
+//! let message = &pass.barcodes[0].message;
+//!
+//! let now = Utc.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap();
+//! let payload = verifier.verify_at(message, now).unwrap();
+//! assert_eq!("ABCDEFG1234567890", payload.serial_number);
+//! assert_eq!("A12", payload.claims.custom["seat"]);
+//!
+//! let later = Utc.with_ymd_and_hms(2024, 2, 9, 0, 0, 0).unwrap();
+//! assert!(matches!(verifier.verify_at(message, later), Err(SignedPayloadError::Expired(_))));
+//! ```
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD as BASE64};
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signer, ed25519::SignatureBytes};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+
+use crate::SignedPayloadError;
+use crate::pass::identifiers::PassTypeId;
+
+/// Algorithm of Ed25519 signed payloads
+const ED25519: &str = "E1";
+
+/// Algorithm of HMAC-SHA256 signed payloads
+const HMAC_SHA256: &str = "H1";
+
+/// Key signing payloads
+#[derive(Clone)]
+pub enum SigningKey {
+    /// Ed25519 private key
+    Ed25519(ed25519_dalek::SigningKey),
+    /// Secret shared with scanners
+    HmacSha256(Vec<u8>),
+}
+
+impl SigningKey {
+    /// Key verifying payloads signed with this key.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        match self {
+            SigningKey::Ed25519(key) => VerifyingKey::Ed25519(key.verifying_key()),
+            SigningKey::HmacSha256(secret) => VerifyingKey::HmacSha256(secret.clone()),
+        }
+    }
+
+    fn algorithm(&self) -> &'static str {
+        match self {
+            SigningKey::Ed25519(_) => ED25519,
+            SigningKey::HmacSha256(_) => HMAC_SHA256,
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            SigningKey::Ed25519(key) => key.sign(data).to_bytes().to_vec(),
+            SigningKey::HmacSha256(secret) => {
+                hmac_sha256(secret, data).finalize().into_bytes().to_vec()
+            }
+        }
+    }
+}
+
+/// Key verifying payloads
+///
+/// HMAC secret is not printed by `Debug`.
+#[derive(Clone)]
+pub enum VerifyingKey {
+    /// Ed25519 public key
+    Ed25519(ed25519_dalek::VerifyingKey),
+    /// Secret shared with the issuer
+    HmacSha256(Vec<u8>),
+}
+
+impl VerifyingKey {
+    fn algorithm(&self) -> &'static str {
+        match self {
+            VerifyingKey::Ed25519(_) => ED25519,
+            VerifyingKey::HmacSha256(_) => HMAC_SHA256,
+        }
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        match self {
+            VerifyingKey::Ed25519(key) => SignatureBytes::try_from(signature)
+                .map(|bytes| ed25519_dalek::Signature::from_bytes(&bytes))
+                .is_ok_and(|signature| key.verify_strict(data, &signature).is_ok()),
+            // Compared in constant time
+            VerifyingKey::HmacSha256(secret) => {
+                hmac_sha256(secret, data).verify_slice(signature).is_ok()
+            }
+        }
+    }
+}
+
+impl fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyingKey::Ed25519(key) => f.debug_tuple("Ed25519").field(key).finish(),
+            VerifyingKey::HmacSha256(_) => f
+                .debug_tuple("HmacSha256")
+                .field(&format_args!("<redacted>"))
+                .finish(),
+        }
+    }
+}
+
+fn hmac_sha256(secret: &[u8], data: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any size");
+    mac.update(data);
+    mac
+}
+
+/// Validity window and custom claims of [Payload]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Claims {
+    /// Payload is not valid before the time
+    pub not_before: Option<DateTime<Utc>>,
+
+    /// Payload is not valid after the time
+    pub not_after: Option<DateTime<Utc>>,
+
+    /// Claims of the issuer, such as seat or ticket class
+    pub custom: Map<String, Value>,
+}
+
+impl Claims {
+    /// Adding custom claim
+    pub fn claim(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.custom.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Signed content of barcode
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payload {
+    /// Serial number of the pass
+    pub serial_number: String,
+
+    /// Pass type identifier of the pass
+    pub pass_type_identifier: PassTypeId,
+
+    /// Validity window and custom claims
+    pub claims: Claims,
+}
+
+/// Claims in JSON, with short keys and timestamps in seconds
+#[derive(Serialize, Deserialize)]
+struct Encoded {
+    sn: String,
+    pt: PassTypeId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nbf: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    c: Map<String, Value>,
+}
+
+impl Payload {
+    /// Sign payload, result is used as barcode message.
+    ///
+    /// Timestamps are signed with precision of seconds.
+    pub fn sign(&self, key: &SigningKey) -> String {
+        let encoded = Encoded {
+            sn: self.serial_number.clone(),
+            pt: self.pass_type_identifier.clone(),
+            nbf: self.claims.not_before.map(|time| time.timestamp()),
+            exp: self.claims.not_after.map(|time| time.timestamp()),
+            c: self.claims.custom.clone(),
+        };
+        let json = serde_json::to_vec(&encoded).expect("claims are serializable");
+        let signed = format!("{}.{}", key.algorithm(), BASE64.encode(json));
+        let signature = BASE64.encode(key.sign(signed.as_bytes()));
+        format!("{}.{}", signed, signature)
+    }
+}
+
+/// Verifier of signed payloads, for gate scanners
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    key: VerifyingKey,
+    pass_type_identifier: Option<PassTypeId>,
+    leeway: Duration,
+}
+
+impl Verifier {
+    /// Creates `Verifier` accepting payloads of any pass type, without leeway.
+    pub fn new(key: VerifyingKey) -> Self {
+        Self {
+            key,
+            pass_type_identifier: None,
+            leeway: Duration::zero(),
+        }
+    }
+
+    /// Accept only payloads of the pass type.
+    pub fn pass_type_identifier(mut self, id: PassTypeId) -> Self {
+        self.pass_type_identifier = Some(id);
+        self
+    }
+
+    /// Extend validity window by `leeway` on both sides, for scanners with inaccurate clocks.
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Verify payload at current time.
+    pub fn verify(&self, message: &str) -> Result<Payload, SignedPayloadError> {
+        self.verify_at(message, Utc::now())
+    }
+
+    /// Verify payload at time `now`.
+    ///
+    /// Checks the signature before reading claims, then validity window and pass type.
+    pub fn verify_at(
+        &self,
+        message: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Payload, SignedPayloadError> {
+        let mut parts = message.split('.');
+        let (Some(algorithm), Some(claims), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(SignedPayloadError::Malformed);
+        };
+        if algorithm != ED25519 && algorithm != HMAC_SHA256 {
+            return Err(SignedPayloadError::Algorithm(String::from(algorithm)));
+        }
+        if algorithm != self.key.algorithm() {
+            return Err(SignedPayloadError::KeyMismatch);
+        }
+
+        let signature = BASE64
+            .decode(signature)
+            .map_err(|_| SignedPayloadError::Malformed)?;
+        let signed = &message[..algorithm.len() + 1 + claims.len()];
+        if !self.key.verify(signed.as_bytes(), &signature) {
+            return Err(SignedPayloadError::Signature);
+        }
+
+        let json = BASE64
+            .decode(claims)
+            .map_err(|_| SignedPayloadError::Malformed)?;
+        let encoded: Encoded =
+            serde_json::from_slice(&json).map_err(|_| SignedPayloadError::Malformed)?;
+
+        let time = |seconds: Option<i64>| match seconds {
+            Some(seconds) => DateTime::from_timestamp(seconds, 0)
+                .map(Some)
+                .ok_or(SignedPayloadError::Malformed),
+            None => Ok(None),
+        };
+        let (not_before, not_after) = (time(encoded.nbf)?, time(encoded.exp)?);
+
+        if let Some(not_before) = not_before
+            && now + self.leeway < not_before
+        {
+            return Err(SignedPayloadError::NotYetValid(not_before));
+        }
+        if let Some(not_after) = not_after
+            && now - self.leeway > not_after
+        {
+            return Err(SignedPayloadError::Expired(not_after));
+        }
+        if let Some(id) = &self.pass_type_identifier
+            && *id != encoded.pt
+        {
+            return Err(SignedPayloadError::PassType(String::from(
+                encoded.pt.as_str(),
+            )));
+        }
+
+        Ok(Payload {
+            serial_number: encoded.sn,
+            pass_type_identifier: encoded.pt,
+            claims: Claims {
+                not_before,
+                not_after,
+                custom: encoded.c,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::pass::barcode::{BarcodeFormat, ErrorCorrection};
+    use crate::pass::make_config;
+
+    fn make_payload() -> Payload {
+        Payload {
+            serial_number: String::from("ABCDEFG1234567890"),
+            pass_type_identifier: "pass.com.example".parse().unwrap(),
+            claims: Claims {
+                not_before: Utc.with_ymd_and_hms(2024, 2, 7, 17, 0, 0).single(),
+                not_after: Utc.with_ymd_and_hms(2024, 2, 7, 23, 0, 0).single(),
+                ..Default::default()
+            }
+            .claim("seat", "A12")
+            .claim("vip", true),
+        }
+    }
+
+    fn ed25519_key() -> SigningKey {
+        SigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]))
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let payload = make_payload();
+        let now = Utc.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap();
+
+        for key in [ed25519_key(), SigningKey::HmacSha256(b"secret".to_vec())] {
+            let message = payload.sign(&key);
+            println!("{} ({} bytes)", message, message.len());
+
+            let verifier = Verifier::new(key.verifying_key());
+            assert_eq!(Ok(payload.clone()), verifier.verify_at(&message, now));
+            // Deterministic
+            assert_eq!(message, payload.sign(&key));
+            // Compact enough for the smallest capacity of QR and Aztec
+            assert!(
+                message.len()
+                    <= BarcodeFormat::Aztec
+                        .capacity(ErrorCorrection::High)
+                        .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn debug_redacts_secret() {
+        let key = SigningKey::HmacSha256(b"secret".to_vec());
+        let verifier = Verifier::new(key.verifying_key());
+        let debug = format!("{:?}", verifier);
+        println!("{}", debug);
+        assert!(debug.contains("HmacSha256(<redacted>)"));
+        assert!(!debug.contains("115, 101, 99"));
+    }
+
+    #[test]
+    fn signed_barcode_added_later() {
+        let key = SigningKey::HmacSha256(b"secret".to_vec());
+        let pass = crate::pass::PassBuilder::new(make_config())
+            .signed_barcode(Claims::default(), &key)
+            .add_barcode(crate::pass::barcode::Barcode {
+                format: BarcodeFormat::PDF417,
+                alt_text: Some(String::from("1234567890")),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(1, pass.barcodes.len());
+        let now = Utc.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap();
+        let payload = Verifier::new(key.verifying_key())
+            .verify_at(&pass.barcodes[0].message, now)
+            .unwrap();
+        assert_eq!("ABCDEFG1234567890", payload.serial_number);
+        assert_eq!(None, pass.barcodes[0].alt_text);
+    }
+
+    #[test]
+    fn signed_barcode_keeps_messages() {
+        let key = SigningKey::HmacSha256(b"secret".to_vec());
+        let code128 = crate::pass::barcode::Barcode {
+            message: String::from("1234567890"),
+            format: BarcodeFormat::Code128,
+            alt_text: Some(String::from("1234567890")),
+            ..Default::default()
+        };
+        let pass = crate::pass::PassBuilder::new(make_config())
+            .add_barcode(code128.clone())
+            .signed_barcode(Claims::default(), &key)
+            .build();
+
+        // Payload gets its own QR barcode
+        assert_eq!(code128, pass.barcodes[0]);
+        assert_eq!(BarcodeFormat::QR, pass.barcodes[1].format);
+        assert!(pass.barcodes[1].message.starts_with("H1."));
+    }
+
+    #[test]
+    fn reject_forged() {
+        let key = ed25519_key();
+        let verifier = Verifier::new(key.verifying_key());
+        let now = Utc.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap();
+        let message = make_payload().sign(&key);
+
+        // Claims changed
+        let mut forged = make_payload();
+        forged.serial_number = String::from("ABCDEFG0000000000");
+        let forged_claims = forged.sign(&key);
+        let (_, claims) = forged_claims.split_once('.').unwrap();
+        let claims = claims.split('.').next().unwrap();
+        let signature = message.rsplit('.').next().unwrap();
+        assert_eq!(
+            Err(SignedPayloadError::Signature),
+            verifier.verify_at(&format!("E1.{}.{}", claims, signature), now)
+        );
+
+        // Signed with another key
+        let other = SigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[8u8; 32]));
+        assert_eq!(
+            Err(SignedPayloadError::Signature),
+            verifier.verify_at(&make_payload().sign(&other), now)
+        );
+        assert_eq!(
+            Err(SignedPayloadError::KeyMismatch),
+            verifier.verify_at(&make_payload().sign(&SigningKey::HmacSha256(vec![1])), now)
+        );
+        assert_eq!(
+            Err(SignedPayloadError::Algorithm(String::from("X1"))),
+            verifier.verify_at(&message.replacen("E1", "X1", 1), now)
+        );
+        assert_eq!(
+            Err(SignedPayloadError::Malformed),
+            verifier.verify_at("ABCDEFG1234567890", now)
+        );
+    }
+
+    #[test]
+    fn check_window_and_pass_type() {
+        let key = SigningKey::HmacSha256(b"secret".to_vec());
+        let message = make_payload().sign(&key);
+        let verifier = Verifier::new(key.verifying_key());
+
+        let early = Utc.with_ymd_and_hms(2024, 2, 7, 16, 59, 0).unwrap();
+        let late = Utc.with_ymd_and_hms(2024, 2, 7, 23, 1, 0).unwrap();
+        assert!(matches!(
+            verifier.verify_at(&message, early),
+            Err(SignedPayloadError::NotYetValid(_))
+        ));
+        assert!(matches!(
+            verifier.verify_at(&message, late),
+            Err(SignedPayloadError::Expired(_))
+        ));
+
+        let lenient = verifier.clone().leeway(Duration::minutes(5));
+        assert!(lenient.verify_at(&message, early).is_ok());
+        assert!(lenient.verify_at(&message, late).is_ok());
+
+        let now = Utc.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap();
+        let other_type = verifier.pass_type_identifier("pass.com.other".parse().unwrap());
+        assert_eq!(
+            Err(SignedPayloadError::PassType(String::from(
+                "pass.com.example"
+            ))),
+            other_type.verify_at(&message, now)
+        );
+    }
+}
@@ -1,12 +1,15 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::identifiers::ProximityUuid;
+
 /// Represents the identify of a Bluetooth Low Energy beacon the system uses to show a relevant pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Beacon {
     /// (Required) The unique identifier of a Bluetooth Low Energy location beacon.
     #[serde(rename = "proximityUUID")]
-    pub proximity_uuid: String,
+    pub proximity_uuid: ProximityUuid,
 
     /// The major identifier of a Bluetooth Low Energy location beacon.
     #[serde(default)]
@@ -30,7 +33,7 @@ impl Default for Beacon {
     /// Creates an empty `Beacon`.
     fn default() -> Self {
         Self {
-            proximity_uuid: String::from("00000000-0000-0000-0000-000000000000"),
+            proximity_uuid: ProximityUuid::default(),
             major: None,
             minor: None,
             relevant_text: None,
@@ -46,7 +49,7 @@ mod tests {
     fn make_beacon() {
         // Serialization test
         let beacon = Beacon {
-            proximity_uuid: String::from("e286373b-15b5-4f4e-bf91-e9e64787724a"),
+            proximity_uuid: "e286373b-15b5-4f4e-bf91-e9e64787724a".parse().unwrap(),
             major: Some(2),
             minor: Some(150),
             relevant_text: Some(String::from("The simple beacon")),
@@ -1,39 +1,69 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{self, Deserialize, Deserializer, Serializer};
 
 /// Serialization to custom date format
-pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+///
+/// The time zone offset of the date is kept, e.g. `2024-02-07T19:00:00+09:00`.
+pub fn serialize<S>(date: &Option<DateTime<FixedOffset>>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    let s = format!("{}", date.unwrap().to_rfc3339());
-    serializer.serialize_str(&s)
+    match date {
+        Some(date) => serializer.serialize_str(&date.to_rfc3339()),
+        None => serializer.serialize_none(),
+    }
 }
 
 /// Deserialization from custom date format
-pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+///
+/// Dates without time zone are read as UTC.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
     if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
         // RFC3339
-        Ok(Some(dt.with_timezone(&Utc)))
+        Ok(Some(dt))
     } else if let Ok(dt) = DateTime::parse_from_rfc2822(&s) {
         // RFC2822
-        Ok(Some(dt.with_timezone(&Utc)))
+        Ok(Some(dt))
     } else if let Ok(dt) = NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S") {
         // Custom naive format
-        Ok(Some(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc)))
+        Ok(Some(Utc.from_utc_datetime(&dt).fixed_offset()))
     } else {
         Err(serde::de::Error::custom("Invalid DateTime format"))
     }
 }
 
+/// Resolves local (wall clock) time in time zone `tz` to date with fixed offset.
+///
+/// Ambiguous local time (e.g. when clocks go back) resolves to the earliest date.
+/// Local time skipped by a transition (e.g. when clocks go forward) is shifted
+/// by the length of the gap.
+pub(crate) fn from_local(tz: Tz, local: NaiveDateTime) -> DateTime<FixedOffset> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.fixed_offset(),
+        LocalResult::None => {
+            // Offset in effect before the transition. Gaps can be longer than an hour
+            // (Samoa skipped a whole day), so take the offset a day earlier in UTC
+            let offset = tz
+                .offset_from_utc_datetime(&(local - Duration::days(1)))
+                .fix();
+            offset
+                .from_local_datetime(&local)
+                .unwrap()
+                .with_timezone(&tz)
+                .fixed_offset()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{DateTime, Utc, prelude::*};
+    use chrono::{DateTime, FixedOffset, Utc, prelude::*};
     use serde::{Deserialize, Serialize};
 
     #[derive(Serialize, Deserialize, Debug)]
@@ -41,13 +71,17 @@ mod tests {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(with = "super")]
-        pub date: Option<DateTime<Utc>>,
+        pub date: Option<DateTime<FixedOffset>>,
     }
 
     #[test]
     fn serialize_check() {
         let date_struct = DateTest {
-            date: Some(Utc.with_ymd_and_hms(2024, 02, 07, 10, 15, 0).unwrap()),
+            date: Some(
+                Utc.with_ymd_and_hms(2024, 02, 07, 10, 15, 0)
+                    .unwrap()
+                    .fixed_offset(),
+            ),
         };
         let json = serde_json::to_string_pretty(&date_struct).unwrap();
         println!("{}", json);
@@ -57,6 +91,26 @@ mod tests {
         assert_eq!(json_expected, json);
     }
 
+    #[test]
+    fn offset_roundtrip_check() {
+        let json_expected = r#"{
+  "date": "2024-02-07T19:00:00+09:00"
+}"#;
+        let date_struct: DateTest = serde_json::from_str(json_expected).unwrap();
+        let date_expected = FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 2, 7, 19, 0, 0)
+            .unwrap();
+        assert_eq!(date_expected, date_struct.date.unwrap());
+        assert_eq!(
+            9 * 3600,
+            date_struct.date.unwrap().offset().local_minus_utc()
+        );
+
+        let json = serde_json::to_string_pretty(&date_struct).unwrap();
+        assert_eq!(json_expected, json);
+    }
+
     #[test]
     fn rfc3339_deserialize_check() {
         let json = r#"{
@@ -86,4 +140,46 @@ mod tests {
         let date_expected = Utc.with_ymd_and_hms(2024, 02, 07, 10, 15, 0).unwrap();
         assert_eq!(date_expected, date_struct.date.unwrap());
     }
+
+    #[test]
+    fn from_local_check() {
+        let local = NaiveDate::from_ymd_opt(2024, 2, 7)
+            .unwrap()
+            .and_hms_opt(19, 0, 0)
+            .unwrap();
+        let date = from_local(Tz::Asia__Tokyo, local);
+        assert_eq!("2024-02-07T19:00:00+09:00", date.to_rfc3339());
+
+        // Clocks go back: 01:30 happens twice, earliest is EDT
+        let local = NaiveDate::from_ymd_opt(2024, 11, 3)
+            .unwrap()
+            .and_hms_opt(1, 30, 0)
+            .unwrap();
+        let date = from_local(Tz::America__New_York, local);
+        assert_eq!("2024-11-03T01:30:00-04:00", date.to_rfc3339());
+
+        // Clocks go forward: 02:30 doesn't exist
+        let local = NaiveDate::from_ymd_opt(2024, 3, 10)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        let date = from_local(Tz::America__New_York, local);
+        assert_eq!("2024-03-10T03:30:00-04:00", date.to_rfc3339());
+
+        // East of UTC the gap is shifted forward too
+        let local = NaiveDate::from_ymd_opt(2024, 3, 31)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        let date = from_local(Tz::Europe__Berlin, local);
+        assert_eq!("2024-03-31T03:30:00+02:00", date.to_rfc3339());
+
+        // Samoa skipped 2011-12-30 when moving across the date line
+        let local = NaiveDate::from_ymd_opt(2011, 12, 30)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let date = from_local(Tz::Pacific__Apia, local);
+        assert_eq!("2011-12-31T12:00:00+14:00", date.to_rfc3339());
+    }
 }
@@ -0,0 +1,308 @@
+use serde_json::Value;
+
+use super::Pass;
+use super::barcode::Barcode;
+use super::fields::{Content, Section};
+
+/// Keys of pass.json displayed on the pass, changes of other keys are not visible to the user
+const VISIBLE_KEYS: [&str; 12] = [
+    "organizationName",
+    "logoText",
+    "foregroundColor",
+    "backgroundColor",
+    "labelColor",
+    "relevantDate",
+    "relevantDates",
+    "expirationDate",
+    "voided",
+    "suppressStripShine",
+    "barcodes",
+    "barcode",
+];
+
+/// Change of a field, see [Pass::diff]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldDiff {
+    /// Field exists only in the new pass
+    Added { section: Section, field: Content },
+
+    /// Field exists only in the old pass
+    Removed { section: Section, field: Content },
+
+    /// Field with the same key has different value, options or section
+    Modified {
+        old_section: Section,
+        new_section: Section,
+        old: Box<Content>,
+        new: Box<Content>,
+    },
+}
+
+impl FieldDiff {
+    /// Key of the field.
+    pub fn key(&self) -> &str {
+        match self {
+            FieldDiff::Added { field, .. } | FieldDiff::Removed { field, .. } => &field.key,
+            FieldDiff::Modified { new, .. } => &new.key,
+        }
+    }
+
+    /// Checks that the value of the field changed, only the formatting options can change otherwise.
+    pub fn is_value_changed(&self) -> bool {
+        match self {
+            FieldDiff::Modified { old, new, .. } => {
+                old.value != new.value
+                    || old.options.attributed_value != new.options.attributed_value
+            }
+            _ => true,
+        }
+    }
+
+    /// [change_message](crate::fields::ContentOptions::change_message) of modified field.
+    pub fn change_message(&self) -> Option<&str> {
+        match self {
+            FieldDiff::Modified { new, .. } => new.options.change_message.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Differences between two passes, see [Pass::diff]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PassDiff {
+    /// Top-level keys of pass.json that were added, removed or changed, in alphabetical order.
+    ///
+    /// Includes the key of pass style (such as `eventTicket`) if any field changed.
+    pub keys: Vec<String>,
+
+    /// Fields added, removed or modified, in order of sections
+    pub fields: Vec<FieldDiff>,
+
+    /// Barcodes before and after, if they changed
+    pub barcodes: Option<(Vec<Barcode>, Vec<Barcode>)>,
+}
+
+impl PassDiff {
+    /// Checks that passes are the same.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Checks that the change is displayed on the pass: fields, barcodes, colors, texts or dates.
+    ///
+    /// Changes of web service, locations, beacons or semantic tags are not visible.
+    pub fn is_user_visible(&self) -> bool {
+        !self.fields.is_empty()
+            || self
+                .keys
+                .iter()
+                .any(|key| VISIBLE_KEYS.contains(&key.as_str()))
+    }
+
+    /// Checks that any field with changed value has a change message, so the user is notified.
+    pub fn has_change_message(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.is_value_changed() && field.change_message().is_some())
+    }
+}
+
+impl Pass {
+    /// Compares this pass with `other` (newer version of the pass).
+    ///
+    /// ```
+    /// use neopasses::{PassBuilder, PassConfig};
+    /// use neopasses::fields::{Content, ContentOptions, Fields, Type};
+    ///
+    /// let make_pass = |gate: &str| {
+    ///     PassBuilder::new(PassConfig {
+    ///         organization_name: "Apple inc.".into(),
+    ///         description: "Boarding pass".into(),
+    ///         pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///         team_identifier: "AA00AA0A0A".parse().unwrap(),
+    ///         serial_number: "ABCDEFG1234567890".into(),
+    ///     })
+    ///     .fields(Type::Generic { pass_fields: Fields::default() }.add_header_field(Content::new(
+    ///         "gate",
+    ///         gate,
+    ///         ContentOptions {
+    ///             change_message: String::from("Gate changed to %@").into(),
+    ///             ..Default::default()
+    ///         },
+    ///     )))
+    ///     .build()
+    /// };
+    ///
+    /// let diff = make_pass("A1").diff(&make_pass("B7"));
+    /// assert_eq!(vec!["generic"], diff.keys);
+    /// assert_eq!("gate", diff.fields[0].key());
+    /// assert!(diff.is_user_visible());
+    /// assert!(diff.has_change_message());
+    /// ```
+    pub fn diff(&self, other: &Pass) -> PassDiff {
+        // Pass has string keys only, so it always converts to a JSON value
+        let old = serde_json::to_value(self).expect("pass converts to JSON value");
+        let new = serde_json::to_value(other).expect("pass converts to JSON value");
+        let mut keys = Vec::new();
+        if let (Value::Object(old), Value::Object(new)) = (&old, &new) {
+            for key in old
+                .keys()
+                .chain(new.keys().filter(|key| !old.contains_key(*key)))
+            {
+                if old.get(key) != new.get(key) {
+                    keys.push(key.clone());
+                }
+            }
+        }
+        keys.sort();
+
+        let barcodes = (self.barcodes != other.barcodes)
+            .then(|| (self.barcodes.clone(), other.barcodes.clone()));
+
+        PassDiff {
+            keys,
+            fields: diff_fields(self, other),
+            barcodes,
+        }
+    }
+}
+
+/// Matches fields by key
+fn diff_fields(old: &Pass, new: &Pass) -> Vec<FieldDiff> {
+    let mut diff = Vec::new();
+    for (old_section, field) in old.fields.iter() {
+        match new.fields.iter().find(|(_, f)| f.key == field.key) {
+            None => diff.push(FieldDiff::Removed {
+                section: old_section,
+                field: field.clone(),
+            }),
+            Some((new_section, new_field)) if new_section != old_section || new_field != field => {
+                diff.push(FieldDiff::Modified {
+                    old_section,
+                    new_section,
+                    old: Box::new(field.clone()),
+                    new: Box::new(new_field.clone()),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for (section, field) in new.fields.iter() {
+        if old.fields.find(&field.key).is_none() {
+            diff.push(FieldDiff::Added {
+                section,
+                field: field.clone(),
+            });
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::barcode::BarcodeFormat;
+    use crate::pass::location::Location;
+    use crate::pass::{PassBuilder, fields, make_config};
+
+    fn make_pass() -> Pass {
+        PassBuilder::new(make_config())
+            .fields(
+                fields::Type::EventTicket {
+                    pass_fields: fields::Fields::default(),
+                }
+                .add_primary_field(fields::Content::new("event", "Concert", Default::default()))
+                .add_secondary_field(fields::Content::new(
+                    "seat",
+                    "12A",
+                    fields::ContentOptions {
+                        change_message: String::from("Seat changed to %@").into(),
+                        ..Default::default()
+                    },
+                ))
+                .add_back_field(fields::Content::new(
+                    "terms",
+                    "No refunds",
+                    Default::default(),
+                )),
+            )
+            .build()
+    }
+
+    #[test]
+    fn same_pass() {
+        let pass = make_pass();
+        let diff = pass.diff(&pass.clone());
+        assert!(diff.is_empty());
+        assert!(!diff.is_user_visible());
+        assert_eq!(PassDiff::default(), diff);
+    }
+
+    #[test]
+    fn fields_diff() {
+        let old = make_pass();
+        let mut new = old.clone();
+        new.fields
+            .update_value("seat", fields::FieldValue::from("14C"))
+            .unwrap();
+        new.fields.remove("terms");
+        new.fields
+            .insert(
+                Section::Auxiliary,
+                fields::Content::new("doors", "19:00", Default::default()),
+            )
+            .unwrap();
+        new.fields.move_field("event", Section::Header).unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(vec!["eventTicket"], diff.keys);
+        assert_eq!(
+            vec!["event", "seat", "terms", "doors"],
+            diff.fields.iter().map(FieldDiff::key).collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            diff.fields[0],
+            FieldDiff::Modified {
+                old_section: Section::Primary,
+                new_section: Section::Header,
+                ..
+            }
+        ));
+        assert!(!diff.fields[0].is_value_changed());
+        assert_eq!(Some("Seat changed to %@"), diff.fields[1].change_message());
+        assert!(matches!(diff.fields[2], FieldDiff::Removed { .. }));
+        assert!(matches!(
+            diff.fields[3],
+            FieldDiff::Added {
+                section: Section::Auxiliary,
+                ..
+            }
+        ));
+        assert!(diff.is_user_visible());
+        assert!(diff.has_change_message());
+    }
+
+    #[test]
+    fn keys_diff() {
+        let old = make_pass();
+        let mut new = old.clone();
+        new.locations.push(Location::default());
+        new.config.description = String::from("Concert ticket");
+
+        let diff = old.diff(&new);
+        assert_eq!(vec!["description", "locations"], diff.keys);
+        assert!(diff.fields.is_empty());
+        assert!(!diff.is_user_visible());
+
+        new.barcodes.push(Barcode {
+            message: String::from("ABCDEFG1234567890"),
+            format: BarcodeFormat::QR,
+            ..Default::default()
+        });
+        let diff = old.diff(&new);
+        assert_eq!(vec!["barcodes", "description", "locations"], diff.keys);
+        assert_eq!(Some((vec![], new.barcodes.clone())), diff.barcodes);
+        assert!(diff.is_user_visible());
+        assert!(!diff.has_change_message());
+    }
+}
@@ -1,10 +1,25 @@
+use std::{borrow::Cow, cell::Cell, fmt};
+
+use chrono::{DateTime, FixedOffset, TimeZone};
 use is_empty::IsEmpty;
-use serde::{Deserialize, Serialize};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use thiserror::Error;
 
 use super::semantic_tags::SemanticTags;
 
+pub use self::attributed::*;
+pub use self::layout::*;
+pub use self::mapping::*;
+pub use self::style::*;
+
+mod attributed;
+mod layout;
+mod mapping;
+mod style;
+
 /// Represents the groups of fields that display information on the front and back of a pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Fields {
     /// Represents the fields that display additional information on the front of a pass.
@@ -36,15 +51,69 @@ impl Default for Fields {
     }
 }
 
+thread_local! {
+    /// Set while reading YAML and TOML, which can leave out empty field groups
+    static DEFAULT_GROUPS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with empty field groups filled in for groups left out of [Fields]
+///
+/// pass.json requires all field groups, so this is only used by YAML and TOML.
+pub(crate) fn with_default_groups<T>(f: impl FnOnce() -> T) -> T {
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            DEFAULT_GROUPS.set(self.0);
+        }
+    }
+
+    let _reset = Reset(DEFAULT_GROUPS.replace(true));
+    f()
+}
+
+impl<'de> Deserialize<'de> for Fields {
+    /// Deserialize `Fields`, all groups are required unless read by [with_default_groups]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Groups {
+            auxiliary_fields: Option<Vec<Content>>,
+            back_fields: Option<Vec<Content>>,
+            header_fields: Option<Vec<Content>>,
+            primary_fields: Option<Vec<Content>>,
+            secondary_fields: Option<Vec<Content>>,
+        }
+
+        let groups = Groups::deserialize(deserializer)?;
+        let group = |fields: Option<Vec<Content>>, key| match fields {
+            Some(fields) => Ok(fields),
+            None if DEFAULT_GROUPS.get() => Ok(Vec::new()),
+            None => Err(de::Error::missing_field(key)),
+        };
+        Ok(Fields {
+            auxiliary_fields: group(groups.auxiliary_fields, "auxiliaryFields")?,
+            back_fields: group(groups.back_fields, "backFields")?,
+            header_fields: group(groups.header_fields, "headerFields")?,
+            primary_fields: group(groups.primary_fields, "primaryFields")?,
+            secondary_fields: group(groups.secondary_fields, "secondaryFields")?,
+        })
+    }
+}
+
 /// Represents the information to display in a field on a pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[serde(from = "ContentRepr")]
 pub struct Content {
     /// (Required) A unique key that identifies a field in the pass; for example, “departure-gate”.
     pub key: String,
 
     /// (Required) The value to use for the field; for example, 42. A date or time value must include a time zone.
-    pub value: String,
+    pub value: FieldValue,
 
     /// All optionals
     #[serde(flatten)]
@@ -53,17 +122,370 @@ pub struct Content {
 
 impl Content {
     /// Creates `FieldContent`.
-    pub fn new(key: &str, value: &str, options: ContentOptions) -> Self {
+    ///
+    /// ```
+    /// use neopasses::fields::{Content, FieldValue};
+    ///
+    /// let text = Content::new("gate", "21", Default::default());
+    /// assert_eq!(FieldValue::Text(String::from("21")), text.value);
+    ///
+    /// let number = Content::new("points", 1200, Default::default());
+    /// assert_eq!(FieldValue::Number(1200.0), number.value);
+    /// ```
+    pub fn new(key: &str, value: impl Into<FieldValue>, options: ContentOptions) -> Self {
         Self {
             key: String::from(key),
-            value: String::from(value),
-            options: options,
+            value: value.into(),
+            options,
         }
     }
+
+    /// Creates `FieldContent` with a number value, displayed with [NumberStyle].
+    pub fn number(key: &str, value: f64, style: NumberStyle) -> Self {
+        Self::new(
+            key,
+            FieldValue::Number(value),
+            ContentOptions {
+                number_style: style.into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Creates `FieldContent` with a date value, displayed with [DateStyle].
+    ///
+    /// The time zone offset of `date` is kept in pass.json.
+    ///
+    /// ```
+    /// use chrono::prelude::*;
+    /// use neopasses::fields::{Content, DateStyle};
+    ///
+    /// let date = FixedOffset::east_opt(9 * 3600)
+    ///     .unwrap()
+    ///     .with_ymd_and_hms(2024, 2, 7, 19, 0, 0)
+    ///     .unwrap();
+    /// let field = Content::date("doors", date, DateStyle::Short);
+    ///
+    /// let json = serde_json::to_string(&field).unwrap();
+    /// assert_eq!(
+    ///     r#"{"key":"doors","value":"2024-02-07T19:00:00+09:00","dateStyle":"PKDateStyleShort"}"#,
+    ///     json
+    /// );
+    /// ```
+    pub fn date<Tz: TimeZone>(key: &str, date: DateTime<Tz>, style: DateStyle) -> Self {
+        Self::new(
+            key,
+            FieldValue::Date(date.fixed_offset()),
+            ContentOptions {
+                date_style: style.into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Creates `FieldContent` with a currency amount.
+    ///
+    /// `currency_code` is an ISO 4217 code, such as “USD”.
+    ///
+    /// ```
+    /// use neopasses::fields::Content;
+    ///
+    /// let field = Content::currency("balance", 25.5, "USD");
+    ///
+    /// let json = serde_json::to_string(&field).unwrap();
+    /// assert_eq!(r#"{"key":"balance","value":25.5,"currencyCode":"USD"}"#, json);
+    /// ```
+    pub fn currency(key: &str, amount: f64, currency_code: &str) -> Self {
+        Self::new(
+            key,
+            FieldValue::Currency(amount),
+            ContentOptions {
+                currency_code: String::from(currency_code).into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Checks that formatting options match the type of [value](Content::value).
+    ///
+    /// * `date_style`, `time_style`, `ignores_time_zone` and `is_relative` require a date value.
+    /// * `number_style` requires a number value.
+    /// * `currency_code` requires a currency value, and a currency value requires a valid `currency_code`.
+    pub fn validate(&self) -> Result<(), ContentError> {
+        let options = &self.options;
+        let is_date = matches!(self.value, FieldValue::Date(_));
+        let is_number = matches!(self.value, FieldValue::Number(_));
+        let is_currency = matches!(self.value, FieldValue::Currency(_));
+
+        let date_options = [
+            ("dateStyle", options.date_style.is_some()),
+            ("timeStyle", options.time_style.is_some()),
+            ("ignoresTimeZone", options.ignores_time_zone.is_some()),
+            ("isRelative", options.is_relative.is_some()),
+        ];
+        if !is_date && let Some((option, _)) = date_options.iter().find(|(_, set)| *set) {
+            return Err(ContentError::OptionRequiresDate {
+                key: self.key.clone(),
+                option,
+            });
+        }
+
+        if options.number_style.is_some() && !is_number {
+            return Err(ContentError::OptionRequiresNumber {
+                key: self.key.clone(),
+                option: "numberStyle",
+            });
+        }
+
+        match (&options.currency_code, is_currency) {
+            (Some(_), false) => Err(ContentError::OptionRequiresCurrency {
+                key: self.key.clone(),
+                option: "currencyCode",
+            }),
+            (None, true) => Err(ContentError::MissingCurrencyCode {
+                key: self.key.clone(),
+            }),
+            (Some(code), true) if !is_currency_code(code) => {
+                Err(ContentError::InvalidCurrencyCode {
+                    key: self.key.clone(),
+                    code: code.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// ISO 4217 alphabetic code: three uppercase ASCII letters
+fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// Deserialization form of `Content`
+///
+/// pass.json stores dates as strings and currency amounts as numbers,
+/// so the value type is resolved from the formatting options.
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "Content")]
+struct ContentRepr {
+    /// (Required) A unique key that identifies a field in the pass; for example, “departure-gate”.
+    key: String,
+    /// (Required) The value to use for the field; for example, 42. A date or time value must include a time zone.
+    value: FieldValue,
+    #[serde(flatten)]
+    options: ContentOptions,
+}
+
+impl From<ContentRepr> for Content {
+    fn from(repr: ContentRepr) -> Self {
+        let options = repr.options;
+        let value = match repr.value {
+            FieldValue::Text(text)
+                if options.date_style.is_some() || options.time_style.is_some() =>
+            {
+                match DateTime::parse_from_rfc3339(&text) {
+                    Ok(date) => FieldValue::Date(date),
+                    Err(_) => FieldValue::Text(text),
+                }
+            }
+            FieldValue::Number(amount) if options.currency_code.is_some() => {
+                FieldValue::Currency(amount)
+            }
+            value => value,
+        };
+        Self {
+            key: repr.key,
+            value,
+            options,
+        }
+    }
+}
+
+/// The value of a field.
+///
+/// Serialized as a JSON string for text and dates (ISO 8601 with time zone),
+/// and as a JSON number for numbers and currency amounts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    /// Plain text
+    Text(String),
+
+    /// Number, formatted with [number_style](ContentOptions::number_style)
+    Number(f64),
+
+    /// Date and time with time zone offset, formatted with [date_style](ContentOptions::date_style) and [time_style](ContentOptions::time_style)
+    Date(DateTime<FixedOffset>),
+
+    /// Amount of money in [currency_code](ContentOptions::currency_code)
+    Currency(f64),
+}
+
+impl Default for FieldValue {
+    /// Creates an empty text `FieldValue`.
+    fn default() -> Self {
+        FieldValue::Text(String::new())
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Text(text) => f.write_str(text),
+            FieldValue::Number(number) | FieldValue::Currency(number) => write!(f, "{number}"),
+            FieldValue::Date(date) => f.write_str(&date.to_rfc3339()),
+        }
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::Text(String::from(value))
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::Text(value)
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        FieldValue::Number(value)
+    }
+}
+
+impl From<i32> for FieldValue {
+    fn from(value: i32) -> Self {
+        FieldValue::Number(value.into())
+    }
+}
+
+impl From<u32> for FieldValue {
+    fn from(value: u32) -> Self {
+        FieldValue::Number(value.into())
+    }
+}
+
+impl From<f32> for FieldValue {
+    fn from(value: f32) -> Self {
+        FieldValue::Number(value.into())
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        FieldValue::Number(value as f64)
+    }
+}
+
+impl From<u64> for FieldValue {
+    fn from(value: u64) -> Self {
+        FieldValue::Number(value as f64)
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for FieldValue {
+    fn from(value: DateTime<Tz>) -> Self {
+        FieldValue::Date(value.fixed_offset())
+    }
+}
+
+impl JsonSchema for FieldValue {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("FieldValue")
+    }
+
+    /// String for text and dates, number for numbers and currency amounts
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": ["string", "number"]
+        })
+    }
+}
+
+impl Serialize for FieldValue {
+    /// Serialize `FieldValue` to JSON string or number
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            FieldValue::Text(text) => serializer.serialize_str(text),
+            FieldValue::Date(date) => serializer.serialize_str(&date.to_rfc3339()),
+            FieldValue::Number(number) | FieldValue::Currency(number) => {
+                // Whole numbers are written without fraction, e.g. `42` instead of `42.0`
+                const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+                if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER {
+                    serializer.serialize_i64(*number as i64)
+                } else {
+                    serializer.serialize_f64(*number)
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldValue {
+    /// Deserialize `FieldValue` from JSON string or number
+    ///
+    /// Strings are read as [FieldValue::Text] and numbers as [FieldValue::Number],
+    /// `Content` refines them using its options.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FieldValueVisitor;
+
+        impl de::Visitor<'_> for FieldValueVisitor {
+            type Value = FieldValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string or a number")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<FieldValue, E> {
+                Ok(FieldValue::Text(String::from(v)))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<FieldValue, E> {
+                Ok(FieldValue::Text(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<FieldValue, E> {
+                Ok(FieldValue::Number(v as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<FieldValue, E> {
+                Ok(FieldValue::Number(v as f64))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<FieldValue, E> {
+                Ok(FieldValue::Number(v))
+            }
+        }
+
+        deserializer.deserialize_any(FieldValueVisitor)
+    }
+}
+
+/// Mismatch between the value type of `Content` and its formatting options
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ContentError {
+    #[error("field `{key}`: `{option}` requires a date value")]
+    OptionRequiresDate { key: String, option: &'static str },
+    #[error("field `{key}`: `{option}` requires a number value")]
+    OptionRequiresNumber { key: String, option: &'static str },
+    #[error("field `{key}`: `{option}` requires a currency value")]
+    OptionRequiresCurrency { key: String, option: &'static str },
+    #[error("field `{key}`: currency value requires `currencyCode`")]
+    MissingCurrencyCode { key: String },
+    #[error("field `{key}`: `{code}` is not an ISO 4217 currency code")]
+    InvalidCurrencyCode { key: String, code: String },
 }
 
 /// Represents options for `FieldContent`
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ContentOptions {
     /// The value of the field, including HTML markup for links.
@@ -179,7 +601,7 @@ impl Default for ContentOptions {
 }
 
 /// The data detectors to apply to the value of a field on the back of the pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum DetectorType {
     #[serde(rename = "PKDataDetectorTypePhoneNumber")]
     PhoneNumber,
@@ -192,7 +614,7 @@ pub enum DetectorType {
 }
 
 /// The style of the date to display in the field.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum DateStyle {
     #[serde(rename = "PKDateStyleNone")]
     None,
@@ -207,7 +629,7 @@ pub enum DateStyle {
 }
 
 /// The style of the number to display in the field.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum NumberStyle {
     #[serde(rename = "PKNumberStyleDecimal")]
     Decimal,
@@ -220,7 +642,7 @@ pub enum NumberStyle {
 }
 
 /// The alignment for the content of a field.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum TextAlignment {
     #[serde(rename = "PKTextAlignmentLeft")]
     Left,
@@ -233,7 +655,7 @@ pub enum TextAlignment {
 }
 
 /// Groups of fields that display information on the front and back of a pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum Type {
     /// Represents the groups of fields that display the information for a boarding pass.
@@ -264,10 +686,16 @@ pub enum Type {
         #[serde(flatten)]
         pass_fields: Fields,
     },
+    /// Represents the groups of fields that display the information for a store card.
+    StoreCard {
+        /// Groups of fields that display information on the front and back of a pass.
+        #[serde(flatten)]
+        pass_fields: Fields,
+    },
 }
 
 /// The type of transit for a boarding pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransitType {
     #[serde(rename = "PKTransitTypeAir")]
     Air,
@@ -284,107 +712,237 @@ pub enum TransitType {
 impl Type {
     /// Add field that display additional information on the front of a pass.
     pub fn add_auxiliary_field(mut self, field: Content) -> Self {
-        match self {
-            Self::BoardingPass {
-                ref mut pass_fields,
-                transit_type: _,
-            } => pass_fields.auxiliary_fields.push(field),
-            Self::Coupon {
-                ref mut pass_fields,
-            }
-            | Self::EventTicket {
-                ref mut pass_fields,
-            }
-            | Self::Generic {
-                ref mut pass_fields,
-            } => pass_fields.auxiliary_fields.push(field),
-        }
+        self.fields_mut().auxiliary_fields.push(field);
         self
     }
 
     /// Add field that display information on the back of a pass.
     pub fn add_back_field(mut self, field: Content) -> Self {
-        match self {
-            Self::BoardingPass {
-                ref mut pass_fields,
-                transit_type: _,
-            } => pass_fields.back_fields.push(field),
-            Self::Coupon {
-                ref mut pass_fields,
-            }
-            | Self::EventTicket {
-                ref mut pass_fields,
-            }
-            | Self::Generic {
-                ref mut pass_fields,
-            } => pass_fields.back_fields.push(field),
-        }
+        self.fields_mut().back_fields.push(field);
         self
     }
 
     /// Add field that display information at the top of a pass.
     pub fn add_header_field(mut self, field: Content) -> Self {
-        match self {
-            Self::BoardingPass {
-                ref mut pass_fields,
-                transit_type: _,
-            } => pass_fields.header_fields.push(field),
-            Self::Coupon {
-                ref mut pass_fields,
-            }
-            | Self::EventTicket {
-                ref mut pass_fields,
-            }
-            | Self::Generic {
-                ref mut pass_fields,
-            } => pass_fields.header_fields.push(field),
-        }
+        self.fields_mut().header_fields.push(field);
         self
     }
 
     /// Add field that display the most important information on a pass.
     pub fn add_primary_field(mut self, field: Content) -> Self {
-        match self {
-            Self::BoardingPass {
-                ref mut pass_fields,
-                transit_type: _,
-            } => pass_fields.primary_fields.push(field),
-            Self::Coupon {
-                ref mut pass_fields,
-            }
-            | Self::EventTicket {
-                ref mut pass_fields,
-            }
-            | Self::Generic {
-                ref mut pass_fields,
-            } => pass_fields.primary_fields.push(field),
-        }
+        self.fields_mut().primary_fields.push(field);
         self
     }
 
     /// Add field that display supporting information on the front of a pass.
     pub fn add_secondary_field(mut self, field: Content) -> Self {
+        self.fields_mut().secondary_fields.push(field);
+        self
+    }
+
+    /// Groups of fields of the pass, regardless of pass style.
+    pub fn fields(&self) -> &Fields {
         match self {
-            Self::BoardingPass {
-                ref mut pass_fields,
-                transit_type: _,
-            } => pass_fields.secondary_fields.push(field),
-            Self::Coupon {
-                ref mut pass_fields,
-            }
-            | Self::EventTicket {
-                ref mut pass_fields,
+            Self::BoardingPass { pass_fields, .. }
+            | Self::Coupon { pass_fields }
+            | Self::EventTicket { pass_fields }
+            | Self::Generic { pass_fields }
+            | Self::StoreCard { pass_fields } => pass_fields,
+        }
+    }
+
+    /// Mutable groups of fields of the pass, regardless of pass style.
+    pub fn fields_mut(&mut self) -> &mut Fields {
+        match self {
+            Self::BoardingPass { pass_fields, .. }
+            | Self::Coupon { pass_fields }
+            | Self::EventTicket { pass_fields }
+            | Self::Generic { pass_fields }
+            | Self::StoreCard { pass_fields } => pass_fields,
+        }
+    }
+
+    /// Iterate over all fields with their sections, front of the pass first.
+    ///
+    /// ```
+    /// use neopasses::fields::{Content, Fields, Section, Type};
+    ///
+    /// let fields = Type::Generic {
+    ///     pass_fields: Fields::default(),
+    /// }
+    /// .add_back_field(Content::new("terms", "No refunds", Default::default()))
+    /// .add_header_field(Content::new("gate", "21", Default::default()));
+    ///
+    /// let keys: Vec<_> = fields.iter().map(|(section, f)| (section, f.key.as_str())).collect();
+    /// assert_eq!(vec![(Section::Header, "gate"), (Section::Back, "terms")], keys);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Section, &Content)> {
+        let fields = self.fields();
+        Section::ALL
+            .into_iter()
+            .flat_map(move |section| fields.section(section).iter().map(move |f| (section, f)))
+    }
+
+    /// Find field by key in any section.
+    pub fn find(&self, key: &str) -> Option<&Content> {
+        self.iter().map(|(_, f)| f).find(|f| f.key == key)
+    }
+
+    /// Find field by key in any section, for in-place changes.
+    pub fn find_mut(&mut self, key: &str) -> Option<&mut Content> {
+        let fields = self.fields_mut();
+        Section::ALL
+            .into_iter()
+            .find_map(|section| fields.position(section, key))
+            .map(|(section, index)| &mut fields.section_mut(section)[index])
+    }
+
+    /// Section that contains field with key.
+    pub fn section_of(&self, key: &str) -> Option<Section> {
+        self.iter().find(|(_, f)| f.key == key).map(|(s, _)| s)
+    }
+
+    /// Replace value of field with key, returns previous value.
+    ///
+    /// Field options (label, change message, etc.) are kept.
+    ///
+    /// ```
+    /// use neopasses::fields::{Content, FieldValue, Fields, Type};
+    ///
+    /// let mut fields = Type::Generic {
+    ///     pass_fields: Fields::default(),
+    /// }
+    /// .add_header_field(Content::new("gate", "21", Default::default()));
+    ///
+    /// let old = fields.update_value("gate", "B7").unwrap();
+    /// assert_eq!(FieldValue::from("21"), old);
+    /// assert_eq!(FieldValue::from("B7"), fields.find("gate").unwrap().value);
+    /// ```
+    pub fn update_value(
+        &mut self,
+        key: &str,
+        value: impl Into<FieldValue>,
+    ) -> Result<FieldValue, FieldsError> {
+        let field = self
+            .find_mut(key)
+            .ok_or_else(|| FieldsError::NotFound(String::from(key)))?;
+        Ok(std::mem::replace(&mut field.value, value.into()))
+    }
+
+    /// Remove field with key from any section.
+    pub fn remove(&mut self, key: &str) -> Option<Content> {
+        let fields = self.fields_mut();
+        let (section, index) = Section::ALL
+            .into_iter()
+            .find_map(|section| fields.position(section, key))?;
+        Some(fields.section_mut(section).remove(index))
+    }
+
+    /// Move field with key to the end of another section.
+    pub fn move_field(&mut self, key: &str, section: Section) -> Result<(), FieldsError> {
+        let field = self
+            .remove(key)
+            .ok_or_else(|| FieldsError::NotFound(String::from(key)))?;
+        self.fields_mut().section_mut(section).push(field);
+        Ok(())
+    }
+
+    /// Add field to section, keeping keys unique across all sections.
+    pub fn insert(&mut self, section: Section, field: Content) -> Result<(), FieldsError> {
+        if self.find(&field.key).is_some() {
+            return Err(FieldsError::DuplicateKey(field.key));
+        }
+        self.fields_mut().section_mut(section).push(field);
+        Ok(())
+    }
+
+    /// Keys used by more than one field, in order of first occurrence.
+    ///
+    /// Keys must be unique across all sections of a pass.
+    pub fn duplicate_keys(&self) -> Vec<&str> {
+        let mut seen = std::collections::HashSet::new();
+        let mut duplicates = Vec::new();
+        for (_, field) in self.iter() {
+            let key = field.key.as_str();
+            if !seen.insert(key) && !duplicates.contains(&key) {
+                duplicates.push(key);
             }
-            | Self::Generic {
-                ref mut pass_fields,
-            } => pass_fields.secondary_fields.push(field),
         }
-        self
+        duplicates
+    }
+}
+
+impl Fields {
+    /// Fields of section.
+    pub fn section(&self, section: Section) -> &Vec<Content> {
+        match section {
+            Section::Header => &self.header_fields,
+            Section::Primary => &self.primary_fields,
+            Section::Secondary => &self.secondary_fields,
+            Section::Auxiliary => &self.auxiliary_fields,
+            Section::Back => &self.back_fields,
+        }
     }
+
+    /// Mutable fields of section.
+    pub fn section_mut(&mut self, section: Section) -> &mut Vec<Content> {
+        match section {
+            Section::Header => &mut self.header_fields,
+            Section::Primary => &mut self.primary_fields,
+            Section::Secondary => &mut self.secondary_fields,
+            Section::Auxiliary => &mut self.auxiliary_fields,
+            Section::Back => &mut self.back_fields,
+        }
+    }
+
+    /// Position of field with key in section
+    fn position(&self, section: Section, key: &str) -> Option<(Section, usize)> {
+        self.section(section)
+            .iter()
+            .position(|f| f.key == key)
+            .map(|index| (section, index))
+    }
+}
+
+/// Group of fields on a pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    /// Fields at the top of a pass, see [header_fields](Fields::header_fields)
+    Header,
+    /// The most important fields, see [primary_fields](Fields::primary_fields)
+    Primary,
+    /// Supporting fields on the front, see [secondary_fields](Fields::secondary_fields)
+    Secondary,
+    /// Additional fields on the front, see [auxiliary_fields](Fields::auxiliary_fields)
+    Auxiliary,
+    /// Fields on the back of a pass, see [back_fields](Fields::back_fields)
+    Back,
+}
+
+impl Section {
+    /// All sections, in display order.
+    pub const ALL: [Section; 5] = [
+        Section::Header,
+        Section::Primary,
+        Section::Secondary,
+        Section::Auxiliary,
+        Section::Back,
+    ];
+}
+
+/// Errors of field lookups and changes in [Type]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FieldsError {
+    #[error("field `{0}` not found")]
+    NotFound(String),
+    #[error("field key `{0}` is already used")]
+    DuplicateKey(String),
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::Utc;
+
     use crate::pass::semantic_tags::SemanticTagSeat;
 
     use super::*;
@@ -582,4 +1140,221 @@ mod tests {
         let json = serde_json::to_string_pretty(&event_ticket).unwrap();
         assert_eq!(json_expected, json);
     }
+
+    #[test]
+    fn typed_values() {
+        // Serialization test
+        let date = FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 2, 7, 19, 0, 0)
+            .unwrap();
+        let event_ticket = Type::EventTicket {
+            pass_fields: Fields {
+                ..Default::default()
+            },
+        }
+        .add_primary_field(Content::date("doors", date, DateStyle::Short))
+        .add_secondary_field(Content::number("row", 12.0, NumberStyle::Decimal))
+        .add_secondary_field(Content::number("discount", 0.15, NumberStyle::Percent))
+        .add_auxiliary_field(Content::currency("price", 49.99, "JPY"));
+
+        let json = serde_json::to_string_pretty(&event_ticket).unwrap();
+
+        println!("{}", json);
+
+        let json_expected = r#"{
+  "eventTicket": {
+    "auxiliaryFields": [
+      {
+        "key": "price",
+        "value": 49.99,
+        "currencyCode": "JPY"
+      }
+    ],
+    "backFields": [],
+    "headerFields": [],
+    "primaryFields": [
+      {
+        "key": "doors",
+        "value": "2024-02-07T19:00:00+09:00",
+        "dateStyle": "PKDateStyleShort"
+      }
+    ],
+    "secondaryFields": [
+      {
+        "key": "row",
+        "value": 12,
+        "numberStyle": "PKNumberStyleDecimal"
+      },
+      {
+        "key": "discount",
+        "value": 0.15,
+        "numberStyle": "PKNumberStylePercent"
+      }
+    ]
+  }
+}"#;
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let event_ticket: Type = serde_json::from_str(json_expected).unwrap();
+        let Type::EventTicket { pass_fields } = &event_ticket else {
+            panic!("expected event ticket");
+        };
+        assert_eq!(FieldValue::Date(date), pass_fields.primary_fields[0].value);
+        assert_eq!(
+            FieldValue::Number(12.0),
+            pass_fields.secondary_fields[0].value
+        );
+        assert_eq!(
+            FieldValue::Currency(49.99),
+            pass_fields.auxiliary_fields[0].value
+        );
+        let json = serde_json::to_string_pretty(&event_ticket).unwrap();
+        assert_eq!(json_expected, json);
+    }
+
+    #[test]
+    fn date_string_without_style_is_text() {
+        let json = r#"{"key":"note","value":"2024-02-07T19:00:00+09:00"}"#;
+        let content: Content = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            FieldValue::Text(String::from("2024-02-07T19:00:00+09:00")),
+            content.value
+        );
+    }
+
+    #[test]
+    fn validate_options() {
+        let date = Utc.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap();
+        assert!(
+            Content::date("date", date, DateStyle::Long)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            Content::number("n", 1.0, NumberStyle::SpellOut)
+                .validate()
+                .is_ok()
+        );
+        assert!(Content::currency("c", 1.0, "USD").validate().is_ok());
+        assert!(
+            Content::new("t", "text", Default::default())
+                .validate()
+                .is_ok()
+        );
+
+        let content = Content::new(
+            "t",
+            "text",
+            ContentOptions {
+                date_style: DateStyle::Short.into(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            Err(ContentError::OptionRequiresDate {
+                key: String::from("t"),
+                option: "dateStyle",
+            }),
+            content.validate()
+        );
+
+        let mut content = Content::date("d", date, DateStyle::Short);
+        content.options.number_style = NumberStyle::Decimal.into();
+        assert_eq!(
+            Err(ContentError::OptionRequiresNumber {
+                key: String::from("d"),
+                option: "numberStyle",
+            }),
+            content.validate()
+        );
+
+        let mut content = Content::number("n", 1.0, NumberStyle::Decimal);
+        content.options.currency_code = String::from("USD").into();
+        assert!(matches!(
+            content.validate(),
+            Err(ContentError::OptionRequiresCurrency { .. })
+        ));
+
+        let content = Content::new("c", FieldValue::Currency(1.0), Default::default());
+        assert!(matches!(
+            content.validate(),
+            Err(ContentError::MissingCurrencyCode { .. })
+        ));
+
+        let content = Content::currency("c", 1.0, "dollars");
+        assert!(matches!(
+            content.validate(),
+            Err(ContentError::InvalidCurrencyCode { .. })
+        ));
+    }
+
+    #[test]
+    fn query_and_update_fields() {
+        let mut boarding_pass = Type::BoardingPass {
+            pass_fields: Fields {
+                ..Default::default()
+            },
+            transit_type: TransitType::Air,
+        }
+        .add_header_field(Content::new(
+            "gate",
+            "21",
+            ContentOptions {
+                label: String::from("GATE").into(),
+                change_message: String::from("Gate changed to %@").into(),
+                ..Default::default()
+            },
+        ))
+        .add_primary_field(Content::new("from", "OAK", Default::default()))
+        .add_primary_field(Content::new("to", "MVK", Default::default()))
+        .add_back_field(Content::new("about", "Dodo Airlines", Default::default()));
+
+        // Lookups
+        assert_eq!(Some(Section::Primary), boarding_pass.section_of("to"));
+        assert!(boarding_pass.find("seat").is_none());
+        assert_eq!(4, boarding_pass.iter().count());
+
+        // Update value, options are kept
+        let old = boarding_pass.update_value("gate", "B7").unwrap();
+        assert_eq!(FieldValue::from("21"), old);
+        let gate = boarding_pass.find("gate").unwrap();
+        assert_eq!(FieldValue::from("B7"), gate.value);
+        assert_eq!(Some(String::from("GATE")), gate.options.label);
+        assert_eq!(
+            Err(FieldsError::NotFound(String::from("seat"))),
+            boarding_pass.update_value("seat", "1A")
+        );
+
+        // Move & remove
+        boarding_pass
+            .move_field("gate", Section::Auxiliary)
+            .unwrap();
+        assert_eq!(Some(Section::Auxiliary), boarding_pass.section_of("gate"));
+        assert!(boarding_pass.fields().header_fields.is_empty());
+        let about = boarding_pass.remove("about").unwrap();
+        assert_eq!("about", about.key);
+        assert!(boarding_pass.remove("about").is_none());
+
+        // Key uniqueness
+        assert_eq!(
+            Err(FieldsError::DuplicateKey(String::from("from"))),
+            boarding_pass.insert(
+                Section::Back,
+                Content::new("from", "OAK", Default::default())
+            )
+        );
+        boarding_pass
+            .insert(
+                Section::Back,
+                Content::new("seat", "1A", Default::default()),
+            )
+            .unwrap();
+        assert!(boarding_pass.duplicate_keys().is_empty());
+
+        let boarding_pass =
+            boarding_pass.add_secondary_field(Content::new("to", "OAK", Default::default()));
+        assert_eq!(vec!["to"], boarding_pass.duplicate_keys());
+    }
 }
@@ -0,0 +1,505 @@
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+use super::{Content, ContentOptions, FieldValue};
+
+/// URL schemes kept as links, other links are converted to text
+const ALLOWED_SCHEMES: [&str; 4] = ["http:", "https:", "mailto:", "tel:"];
+
+/// HTML tags and comments
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<!--.*?-->|<(?P<close>/?)(?P<name>[a-zA-Z][a-zA-Z0-9]*)(?P<attrs>[^>]*)>")
+        .unwrap()
+});
+
+/// `href` attribute of a tag, quoted or not
+static HREF_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\bhref\s*=\s*(?:"(?P<dq>[^"]*)"|'(?P<sq>[^']*)'|(?P<uq>[^\s"'>]+))"#).unwrap()
+});
+
+/// HTML character references
+static HTML_ENTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&(?:#(?P<dec>[0-9]+)|#[xX](?P<hex>[0-9a-fA-F]+)|(?P<name>[a-zA-Z]+));").unwrap()
+});
+
+/// Markdown inline link `[text](url)` or autolink `<url>`
+static MARKDOWN_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\[(?P<text>[^\]]*)\]\((?P<href>[^)\s]+)\)|<(?P<auto>[a-zA-Z][a-zA-Z0-9+.-]*:[^>\s]+)>",
+    )
+    .unwrap()
+});
+
+/// Text of a field with links, serialized to the HTML subset supported by Wallet.
+///
+/// The only supported tag is `<a href>`, the rest of the text is escaped.
+///
+/// ```
+/// use neopasses::fields::{AttributedText, Content};
+///
+/// let text = AttributedText::new()
+///     .text("Questions? Call ")
+///     .link("support", "tel:+15555550100");
+///
+/// assert_eq!(
+///     r#"Questions? Call <a href="tel:+15555550100">support</a>"#,
+///     text.to_html()
+/// );
+///
+/// // Plain value is filled for devices that don't show links
+/// let field = Content::attributed("help", text, Default::default());
+/// assert_eq!("Questions? Call support (+15555550100)", field.value.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AttributedText {
+    spans: Vec<Span>,
+}
+
+/// Part of [AttributedText]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Span {
+    /// Plain text
+    Text(String),
+    /// Link with text
+    Link { text: String, href: String },
+}
+
+impl AttributedText {
+    /// Creates an empty `AttributedText`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adding plain text.
+    pub fn text(mut self, text: &str) -> Self {
+        self.push_text(text);
+        self
+    }
+
+    /// Adding link with text.
+    ///
+    /// Empty text is replaced with `href`.
+    pub fn link(mut self, text: &str, href: &str) -> Self {
+        self.push_link(text, href);
+        self
+    }
+
+    /// Parts of the text.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Checks that there is no text and no links.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// HTML for [attributed_value](ContentOptions::attributed_value).
+    pub fn to_html(&self) -> String {
+        self.spans
+            .iter()
+            .map(|span| match span {
+                Span::Text(text) => escape(text),
+                Span::Link { text, href } => {
+                    format!(r#"<a href="{}">{}</a>"#, escape(href), escape(text))
+                }
+            })
+            .collect()
+    }
+
+    /// Plain text for [value](Content::value), links are followed by their address.
+    pub fn to_plain(&self) -> String {
+        self.spans
+            .iter()
+            .map(|span| match span {
+                Span::Text(text) => text.clone(),
+                Span::Link { text, href } if text == href => text.clone(),
+                Span::Link { text, href } => {
+                    let address = href
+                        .strip_prefix("mailto:")
+                        .or_else(|| href.strip_prefix("tel:"))
+                        .unwrap_or(href);
+                    format!("{text} ({address})")
+                }
+            })
+            .collect()
+    }
+
+    /// Converts arbitrary HTML to supported subset.
+    ///
+    /// * `<a href>` links with http, https, mailto and tel schemes are kept
+    /// * `<br>` and ends of blocks (paragraphs, list items, headings) become line breaks
+    /// * `<script>` and `<style>` are removed with their content
+    /// * Other tags are removed, their text is kept
+    ///
+    /// ```
+    /// use neopasses::fields::AttributedText;
+    ///
+    /// let html = r#"<p>Read <b>our</b> <a href="https://example.com/terms" target="_blank">terms</a></p>
+    /// <p onclick="alert()">5 &lt; 6</p>"#;
+    /// let text = AttributedText::from_html(html);
+    ///
+    /// assert_eq!(
+    ///     "Read our <a href=\"https://example.com/terms\">terms</a>\n5 &lt; 6",
+    ///     text.to_html()
+    /// );
+    /// ```
+    pub fn from_html(html: &str) -> Self {
+        let mut result = Self::new();
+        // Open link: href and text
+        let mut link: Option<(String, String)> = None;
+        // Name of tag whose content is skipped
+        let mut skip_until: Option<String> = None;
+        let mut last = 0;
+
+        for tag in HTML_TAG.captures_iter(html) {
+            let whole = tag.get(0).unwrap();
+            if skip_until.is_none() {
+                let text = collapse_whitespace(&decode_entities(&html[last..whole.start()]));
+                match &mut link {
+                    Some((_, link_text)) => link_text.push_str(&text),
+                    None => result.push_text(&text),
+                }
+            }
+            last = whole.end();
+
+            let Some(name) = tag.name("name") else {
+                // Comment
+                continue;
+            };
+            let name = name.as_str().to_ascii_lowercase();
+            let close = !tag["close"].is_empty();
+
+            if let Some(skipped) = &skip_until {
+                if close && *skipped == name {
+                    skip_until = None;
+                }
+                continue;
+            }
+
+            match (name.as_str(), close) {
+                ("script" | "style", false) => skip_until = Some(name),
+                ("a", false) => {
+                    if let Some((href, text)) = link.take() {
+                        result.push_link(&text, &href);
+                    }
+                    link = HREF_ATTR
+                        .captures(&tag["attrs"])
+                        .and_then(|attr| {
+                            attr.name("dq")
+                                .or_else(|| attr.name("sq"))
+                                .or_else(|| attr.name("uq"))
+                        })
+                        .map(|href| decode_entities(href.as_str().trim()))
+                        .filter(|href| is_allowed_href(href))
+                        .map(|href| (href, String::new()));
+                }
+                ("a", true) => {
+                    if let Some((href, text)) = link.take() {
+                        result.push_link(text.trim(), &href);
+                    }
+                }
+                ("br", _) => match &mut link {
+                    Some((_, link_text)) => link_text.push(' '),
+                    None => result.push_text("\n"),
+                },
+                ("p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => {
+                    result.push_text("\n")
+                }
+                _ => {}
+            }
+        }
+
+        if skip_until.is_none() {
+            let text = collapse_whitespace(&decode_entities(&html[last..]));
+            match &mut link {
+                Some((_, link_text)) => link_text.push_str(&text),
+                None => result.push_text(&text),
+            }
+        }
+        if let Some((href, text)) = link {
+            result.push_link(text.trim(), &href);
+        }
+        result.trim_lines();
+        result
+    }
+
+    /// Converts Markdown to supported subset.
+    ///
+    /// Inline links `[text](url)` and autolinks `<url>` become links,
+    /// emphasis, code and heading markers are removed.
+    ///
+    /// ```
+    /// use neopasses::fields::AttributedText;
+    ///
+    /// let text = AttributedText::from_markdown("## Help\n**Email** [us](mailto:help@example.com)");
+    ///
+    /// assert_eq!(
+    ///     "Help\nEmail <a href=\"mailto:help@example.com\">us</a>",
+    ///     text.to_html()
+    /// );
+    /// ```
+    pub fn from_markdown(markdown: &str) -> Self {
+        let mut result = Self::new();
+        let mut last = 0;
+        for link in MARKDOWN_LINK.captures_iter(markdown) {
+            let whole = link.get(0).unwrap();
+            result.push_text(&strip_markdown(&markdown[last..whole.start()]));
+            last = whole.end();
+
+            let (text, href) = match link.name("auto") {
+                Some(href) => (href.as_str().to_string(), href.as_str()),
+                None => (
+                    strip_markdown(&link["text"]),
+                    link.name("href").unwrap().as_str(),
+                ),
+            };
+            if is_allowed_href(href) {
+                result.push_link(&text, href);
+            } else {
+                result.push_text(&text);
+            }
+        }
+        result.push_text(&strip_markdown(&markdown[last..]));
+        result.trim_lines();
+        result
+    }
+
+    /// Adding text, merged with the previous text span
+    fn push_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(Span::Text(previous)) => previous.push_str(text),
+            _ => self.spans.push(Span::Text(String::from(text))),
+        }
+    }
+
+    fn push_link(&mut self, text: &str, href: &str) {
+        let text = if text.is_empty() { href } else { text };
+        self.spans.push(Span::Link {
+            text: String::from(text),
+            href: String::from(href),
+        });
+    }
+
+    /// Removes spaces around line breaks, blank lines and leading and trailing whitespace
+    fn trim_lines(&mut self) {
+        static LINE_BREAK: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"[ \t]*\n[ \t\n]*").unwrap());
+
+        for span in &mut self.spans {
+            if let Span::Text(text) = span {
+                *text = LINE_BREAK.replace_all(text, "\n").into_owned();
+            }
+        }
+        if let Some(Span::Text(text)) = self.spans.first_mut() {
+            *text = String::from(text.trim_start());
+        }
+        if let Some(Span::Text(text)) = self.spans.last_mut() {
+            *text = String::from(text.trim_end());
+        }
+        self.spans
+            .retain(|span| !matches!(span, Span::Text(text) if text.is_empty()));
+    }
+}
+
+impl Content {
+    /// Creates `FieldContent` with [attributed_value](ContentOptions::attributed_value) and
+    /// plain text [value](Content::value) fallback.
+    pub fn attributed(key: &str, text: AttributedText, options: ContentOptions) -> Self {
+        Self::new(
+            key,
+            FieldValue::Text(text.to_plain()),
+            ContentOptions {
+                attributed_value: Some(text.to_html()),
+                ..options
+            },
+        )
+    }
+
+    /// Converts [attributed_value](ContentOptions::attributed_value) to HTML supported by Wallet,
+    /// see [AttributedText::from_html].
+    ///
+    /// Text value is replaced with plain text of attributed value, other values are kept.
+    pub fn sanitize_attributed_value(&mut self) {
+        let Some(html) = &self.options.attributed_value else {
+            return;
+        };
+        let text = AttributedText::from_html(html);
+        if let FieldValue::Text(_) = self.value {
+            self.value = FieldValue::Text(text.to_plain());
+        }
+        self.options.attributed_value = Some(text.to_html());
+    }
+}
+
+/// Checks that link can be opened from a pass
+fn is_allowed_href(href: &str) -> bool {
+    let href = href.to_ascii_lowercase();
+    ALLOWED_SCHEMES
+        .iter()
+        .any(|scheme| href.starts_with(scheme) && href.len() > scheme.len())
+}
+
+/// Escapes HTML special characters
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Decodes character references, unknown references are kept as is
+fn decode_entities(text: &str) -> String {
+    HTML_ENTITY
+        .replace_all(text, |entity: &Captures| {
+            let c = if let Some(dec) = entity.name("dec") {
+                dec.as_str().parse().ok().and_then(char::from_u32)
+            } else if let Some(hex) = entity.name("hex") {
+                u32::from_str_radix(hex.as_str(), 16)
+                    .ok()
+                    .and_then(char::from_u32)
+            } else {
+                match entity["name"].to_ascii_lowercase().as_str() {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some('\u{a0}'),
+                    _ => None,
+                }
+            };
+            c.map(String::from)
+                .unwrap_or_else(|| String::from(&entity[0]))
+        })
+        .into_owned()
+}
+
+/// Replaces runs of whitespace with a single space, as HTML does
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            space = true;
+            continue;
+        }
+        if space {
+            collapsed.push(' ');
+            space = false;
+        }
+        collapsed.push(c);
+    }
+    if space {
+        collapsed.push(' ');
+    }
+    collapsed
+}
+
+/// Removes Markdown emphasis, code and heading markers
+fn strip_markdown(text: &str) -> String {
+    static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^#{1,6}[ \t]+").unwrap());
+    static STRONG: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\*\*(?P<a>[^*]+)\*\*|__(?P<b>[^_]+)__").unwrap());
+    static EMPHASIS: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\*(?P<a>[^*\n]+)\*|`(?P<b>[^`\n]*)`").unwrap());
+
+    let text = HEADING.replace_all(text, "");
+    let text = STRONG.replace_all(&text, "$a$b");
+    EMPHASIS.replace_all(&text, "$a$b").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributed_serialization() {
+        let text = AttributedText::new()
+            .text("Terms & conditions: ")
+            .link("", "https://example.com/terms?a=1&b=2");
+        let field = Content::attributed("terms", text, Default::default());
+
+        let json = serde_json::to_string_pretty(&field).unwrap();
+        println!("{}", json);
+        let json_expected = r#"{
+  "key": "terms",
+  "value": "Terms & conditions: https://example.com/terms?a=1&b=2",
+  "attributedValue": "Terms &amp; conditions: <a href=\"https://example.com/terms?a=1&amp;b=2\">https://example.com/terms?a=1&amp;b=2</a>"
+}"#;
+        assert_eq!(json_expected, json);
+    }
+
+    #[test]
+    fn html_sanitizing() {
+        let html = r#"
+<h1>Rules</h1>
+<ul>
+  <li>No <em>outside</em> food</li>
+  <li>Lost &amp; found: <A HREF='mailto:lost@example.com'>email&nbsp;us</A></li>
+</ul>
+<script>alert("x")</script><!-- comment -->
+<p>Tickets are <a href="javascript:alert(1)">non-refundable</a><br>See <a href=/faq>FAQ</a></p>"#;
+        let text = AttributedText::from_html(html);
+
+        assert_eq!(
+            "Rules\nNo outside food\nLost &amp; found: <a href=\"mailto:lost@example.com\">email\u{a0}us</a>\nTickets are non-refundable\nSee FAQ",
+            text.to_html()
+        );
+        assert_eq!(
+            "Rules\nNo outside food\nLost & found: email\u{a0}us (lost@example.com)\nTickets are non-refundable\nSee FAQ",
+            text.to_plain()
+        );
+
+        // Unclosed link and stray markup
+        let text = AttributedText::from_html("a < b <a href=\"tel:911\">call");
+        assert_eq!(
+            vec![
+                Span::Text(String::from("a < b ")),
+                Span::Link {
+                    text: String::from("call"),
+                    href: String::from("tel:911")
+                }
+            ],
+            text.spans()
+        );
+    }
+
+    #[test]
+    fn markdown_sanitizing() {
+        let markdown = "# Parking\nUse *lot B*, see <https://example.com/map> or [call](tel:5551234).\n[local](/parking) `code`";
+        let text = AttributedText::from_markdown(markdown);
+
+        assert_eq!(
+            "Parking\nUse lot B, see <a href=\"https://example.com/map\">https://example.com/map</a> or <a href=\"tel:5551234\">call</a>.\nlocal code",
+            text.to_html()
+        );
+        assert!(AttributedText::from_markdown("  ").is_empty());
+    }
+
+    #[test]
+    fn sanitize_content() {
+        let json = r#"{"key":"info","value":"","attributedValue":"<b>Open</b> <a href=\"https://example.com\">site</a>"}"#;
+        let mut field: Content = serde_json::from_str(json).unwrap();
+        field.sanitize_attributed_value();
+
+        assert_eq!(
+            Some(String::from(
+                "Open <a href=\"https://example.com\">site</a>"
+            )),
+            field.options.attributed_value
+        );
+        assert_eq!("Open site (https://example.com)", field.value.to_string());
+    }
+}
@@ -0,0 +1,441 @@
+use std::fmt;
+
+use super::{Content, DateStyle, FieldValue, NumberStyle, Section, Style, Type};
+
+/// Advance widths of printable ASCII characters (`' '..='~'`), in 1/1000 of font size.
+///
+/// Helvetica-compatible metrics, close to the system font Wallet uses.
+const ASCII_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, // ' '..='\''
+    333, 333, 389, 584, 278, 333, 278, 278, // '('..='/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0'..='9'
+    278, 278, 584, 584, 584, 556, 1015, // ':'..='@'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A'..='M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N'..='Z'
+    278, 278, 278, 469, 556, 333, // '['..='`'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a'..='m'
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n'..='z'
+    334, 260, 334, 584, // '{'..='~'
+];
+
+/// Width of characters outside of ASCII, except wide (CJK) characters
+const DEFAULT_WIDTH: u16 = 556;
+
+/// Width of wide (CJK, emoji) characters
+const WIDE_WIDTH: u16 = 1000;
+
+/// Space between fields in a row, in points
+pub(crate) const FIELD_GAP: f64 = 8.0;
+
+/// Space taken by the logo and logo text next to header fields, in points
+const LOGO_WIDTH: f64 = 100.0;
+
+/// Space taken by the transit icon between primary fields of boarding passes, in points
+const TRANSIT_ICON_WIDTH: f64 = 48.0;
+
+/// Estimated width of `text` in points, rendered in one line with `font_size`.
+///
+/// ```
+/// use neopasses::fields::text_width;
+///
+/// assert!(text_width("WWW", 17.0) > text_width("iii", 17.0));
+/// assert_eq!(0.0, text_width("", 17.0));
+/// ```
+pub fn text_width(text: &str, font_size: f64) -> f64 {
+    let units: u32 = text.chars().map(|c| u32::from(char_width(c))).sum();
+    f64::from(units) * font_size / 1000.0
+}
+
+/// Advance width of character, in 1/1000 of font size
+fn char_width(c: char) -> u16 {
+    match c {
+        ' '..='~' => ASCII_WIDTHS[c as usize - ' ' as usize],
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1FAFF}'
+        | '\u{20000}'..='\u{3FFFD}' => WIDE_WIDTH,
+        _ => DEFAULT_WIDTH,
+    }
+}
+
+/// Class of device by width of the pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceClass {
+    /// iPhone SE and other 320 points wide screens
+    Compact,
+    /// iPhone with 375-393 points wide screen
+    Regular,
+    /// iPhone Plus and Pro Max
+    Large,
+    /// Apple Watch
+    Watch,
+}
+
+impl DeviceClass {
+    /// All device classes, from the narrowest phone.
+    pub const ALL: [DeviceClass; 4] = [
+        DeviceClass::Compact,
+        DeviceClass::Regular,
+        DeviceClass::Large,
+        DeviceClass::Watch,
+    ];
+
+    /// Width of a row of fields on the front of the pass, in points.
+    pub fn row_width(&self) -> f64 {
+        match self {
+            DeviceClass::Compact => 268.0,
+            DeviceClass::Regular => 323.0,
+            DeviceClass::Large => 378.0,
+            DeviceClass::Watch => 170.0,
+        }
+    }
+
+    /// Scale of font sizes relative to phones.
+    pub fn font_scale(&self) -> f64 {
+        match self {
+            DeviceClass::Watch => 0.7,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Part of a field that doesn't fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPart {
+    /// Label, displayed in uppercase
+    Label,
+    /// Value, with date and number formatting applied
+    Value,
+}
+
+/// How Wallet displays text that doesn't fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Text continues on a second line (primary values of most styles)
+    Wraps,
+    /// Text ends with `…`
+    Truncates,
+}
+
+/// Field that likely doesn't fit its slot, see [Type::estimate_layout]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutIssue {
+    /// Key of the field
+    pub key: String,
+    /// Section of the field
+    pub section: Section,
+    /// Device class the issue appears on
+    pub device: DeviceClass,
+    /// Part of the field that doesn't fit
+    pub part: FieldPart,
+    /// Estimated width of the text, in points
+    pub width: f64,
+    /// Width of the slot of the field, in points
+    pub slot: f64,
+    /// How Wallet displays the text
+    pub overflow: Overflow,
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} field `{}`: {} {} on {:?} devices ({:.0} of {:.0} points)",
+            self.section,
+            self.key,
+            match self.part {
+                FieldPart::Label => "label",
+                FieldPart::Value => "value",
+            },
+            match self.overflow {
+                Overflow::Wraps => "wraps",
+                Overflow::Truncates => "is truncated",
+            },
+            self.device,
+            self.width,
+            self.slot
+        )
+    }
+}
+
+impl Style {
+    /// Font sizes of label and value in section on phones, in points.
+    ///
+    /// `None` for [Section::Back], back fields are not limited in width.
+    pub fn font_sizes(&self, section: Section) -> Option<(f64, f64)> {
+        match (self, section) {
+            (_, Section::Back) => None,
+            (_, Section::Header) => Some((10.0, 17.0)),
+            (Style::BoardingPass(_), Section::Primary) => Some((11.0, 36.0)),
+            (_, Section::Primary) => Some((11.0, 30.0)),
+            (_, Section::Secondary | Section::Auxiliary) => Some((10.0, 17.0)),
+        }
+    }
+
+    /// Width of each of `count` fields in section on device, in points.
+    ///
+    /// Fields share the row evenly. `None` for [Section::Back].
+    pub fn slot_width(&self, section: Section, count: usize, device: DeviceClass) -> Option<f64> {
+        self.slot_width_in(section, count, device.row_width())
+    }
+
+    /// Width of each of `count` fields in section, in a row `row` points wide.
+    pub(crate) fn slot_width_in(&self, section: Section, count: usize, row: f64) -> Option<f64> {
+        let (row, count) = match (self, section) {
+            (_, Section::Back) => return None,
+            (_, Section::Header) => (row - LOGO_WIDTH, count),
+            // Origin and destination on the sides of the transit icon
+            (Style::BoardingPass(_), Section::Primary) => (row - TRANSIT_ICON_WIDTH, 2),
+            _ => (row, count),
+        };
+        let count = count.max(1) as f64;
+        Some((row - FIELD_GAP * (count - 1.0)) / count)
+    }
+}
+
+impl Type {
+    /// Estimate which fields on the front of the pass don't fit their slots.
+    ///
+    /// Widths are estimated with bundled font metrics (see [text_width]), so the
+    /// estimate works offline. Dates and numbers are measured as formatted for
+    /// the `en_US` locale.
+    ///
+    /// ```
+    /// use neopasses::fields::{Content, DeviceClass, Fields, FieldPart, TransitType, Type};
+    ///
+    /// let fields = Type::BoardingPass {
+    ///     pass_fields: Fields::default(),
+    ///     transit_type: TransitType::Air,
+    /// }
+    /// .add_primary_field(Content::new("from", "San Francisco", Default::default()))
+    /// .add_primary_field(Content::new("to", "JFK", Default::default()));
+    ///
+    /// let issues = fields.estimate_layout(&[DeviceClass::Regular]);
+    /// assert_eq!(1, issues.len());
+    /// assert_eq!("from", issues[0].key);
+    /// assert_eq!(FieldPart::Value, issues[0].part);
+    /// ```
+    pub fn estimate_layout(&self, devices: &[DeviceClass]) -> Vec<LayoutIssue> {
+        let style = self.style();
+        let mut issues = Vec::new();
+        for &device in devices {
+            let scale = device.font_scale();
+            for section in [
+                Section::Header,
+                Section::Primary,
+                Section::Secondary,
+                Section::Auxiliary,
+            ] {
+                let list = self.fields().section(section);
+                let (Some((label_size, value_size)), Some(slot)) = (
+                    style.font_sizes(section),
+                    style.slot_width(section, list.len(), device),
+                ) else {
+                    continue;
+                };
+                let value_wraps =
+                    section == Section::Primary && !matches!(style, Style::BoardingPass(_));
+
+                for field in list {
+                    let label = field
+                        .options
+                        .label
+                        .as_deref()
+                        .map(|label| text_width(&label.to_uppercase(), label_size * scale));
+                    let value = text_width(&display_value(field), value_size * scale);
+
+                    let parts = [
+                        (FieldPart::Label, label, Overflow::Truncates),
+                        (
+                            FieldPart::Value,
+                            Some(value),
+                            if value_wraps {
+                                Overflow::Wraps
+                            } else {
+                                Overflow::Truncates
+                            },
+                        ),
+                    ];
+                    for (part, width, overflow) in parts {
+                        let Some(width) = width.filter(|width| *width > slot) else {
+                            continue;
+                        };
+                        // Wrapped text is truncated after the second line
+                        let overflow = if overflow == Overflow::Wraps && width > 2.0 * slot {
+                            Overflow::Truncates
+                        } else {
+                            overflow
+                        };
+                        issues.push(LayoutIssue {
+                            key: field.key.clone(),
+                            section,
+                            device,
+                            part,
+                            width,
+                            slot,
+                            overflow,
+                        });
+                    }
+                }
+            }
+        }
+        issues
+    }
+}
+
+/// Value of field as displayed by Wallet
+pub(crate) fn display_value(field: &Content) -> String {
+    let options = &field.options;
+    match &field.value {
+        FieldValue::Text(text) => text.clone(),
+        FieldValue::Number(number) => match options.number_style {
+            Some(NumberStyle::Percent) => format!("{}%", number * 100.0),
+            Some(NumberStyle::Scientific) => format!("{:E}", number),
+            // Spelled out numbers are about 6 characters per digit
+            Some(NumberStyle::SpellOut) => "x".repeat(6 * number.to_string().len()),
+            Some(NumberStyle::Decimal) | None => number.to_string(),
+        },
+        FieldValue::Currency(amount) => format!(
+            "{} {:.2}",
+            options.currency_code.as_deref().unwrap_or_default(),
+            amount
+        ),
+        FieldValue::Date(date) => {
+            if options.date_style.is_none() && options.time_style.is_none() {
+                return date.to_rfc3339();
+            }
+            let date_format = match options.date_style {
+                Some(DateStyle::Short) => Some("%-m/%-d/%y"),
+                Some(DateStyle::Medium) => Some("%b %-d, %Y"),
+                Some(DateStyle::Long) => Some("%B %-d, %Y"),
+                Some(DateStyle::Full) => Some("%A, %B %-d, %Y"),
+                Some(DateStyle::None) | None => None,
+            };
+            let time_format = match options.time_style {
+                Some(DateStyle::Short) => Some("%-I:%M %p"),
+                Some(DateStyle::Medium) => Some("%-I:%M:%S %p"),
+                Some(DateStyle::Long | DateStyle::Full) => Some("%-I:%M:%S %p GMT%:z"),
+                Some(DateStyle::None) | None => None,
+            };
+            let format = [date_format, time_format]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", ");
+            date.format(&format).to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+    use crate::fields::{ContentOptions, Fields, TransitType};
+
+    #[test]
+    fn widths() {
+        assert!((text_width("123", 17.0) - 17.0 * 0.556 * 3.0).abs() < 1e-9);
+        assert_eq!(20.0, text_width("東京", 10.0));
+        assert!(text_width("MMMM", 10.0) > text_width("1111", 10.0));
+    }
+
+    #[test]
+    fn slots() {
+        let style = Style::Generic;
+        assert_eq!(
+            Some(DeviceClass::Regular.row_width()),
+            style.slot_width(Section::Primary, 1, DeviceClass::Regular)
+        );
+        assert_eq!(
+            Some((323.0 - 3.0 * FIELD_GAP) / 4.0),
+            style.slot_width(Section::Auxiliary, 4, DeviceClass::Regular)
+        );
+        assert_eq!(
+            None,
+            style.slot_width(Section::Back, 1, DeviceClass::Regular)
+        );
+    }
+
+    #[test]
+    fn date_value() {
+        let date = FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 2, 7, 19, 0, 0)
+            .unwrap();
+        let mut field = Content::date("doors", date, DateStyle::Medium);
+        field.options.time_style = Some(DateStyle::Short);
+        assert_eq!("Feb 7, 2024, 7:00 PM", display_value(&field));
+    }
+
+    #[test]
+    fn estimate_per_device() {
+        let fields = Type::Generic {
+            pass_fields: Fields::default(),
+        }
+        .add_primary_field(Content::new(
+            "name",
+            "Johnathan Appleseed-Montgomery",
+            Default::default(),
+        ))
+        .add_auxiliary_field(Content::new(
+            "level",
+            "Gold",
+            ContentOptions {
+                label: String::from("Membership level").into(),
+                ..Default::default()
+            },
+        ))
+        .add_auxiliary_field(Content::new("points", 1200, Default::default()))
+        .add_auxiliary_field(Content::new("since", "2019", Default::default()))
+        .add_back_field(Content::new(
+            "terms",
+            "Very long terms and conditions ".repeat(10),
+            Default::default(),
+        ));
+
+        let issues = fields.estimate_layout(&DeviceClass::ALL);
+        for issue in &issues {
+            println!("{}", issue);
+        }
+
+        // Primary value wraps to the second line
+        let name: Vec<_> = issues.iter().filter(|i| i.key == "name").collect();
+        assert_eq!(4, name.len());
+        assert!(name.iter().all(|i| i.overflow == Overflow::Wraps));
+
+        // Long label in a shared row
+        assert!(issues.iter().any(|i| i.key == "level"
+            && i.part == FieldPart::Label
+            && i.device == DeviceClass::Compact));
+        assert!(issues.iter().all(|i| i.section != Section::Back));
+
+        // Wrapped text longer than two lines is truncated
+        let fields = fields.add_primary_field(Content::new(
+            "title",
+            "Platinum member since the opening of the very first store",
+            Default::default(),
+        ));
+        let issues = fields.estimate_layout(&[DeviceClass::Regular]);
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.key == "title" && i.overflow == Overflow::Truncates)
+        );
+
+        // Transit icon splits primary row of boarding passes
+        let boarding_pass = Type::BoardingPass {
+            pass_fields: Fields::default(),
+            transit_type: TransitType::Train,
+        }
+        .add_primary_field(Content::new("from", "SFO", Default::default()))
+        .add_primary_field(Content::new("to", "JFK", Default::default()));
+        assert!(boarding_pass.estimate_layout(&DeviceClass::ALL).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use super::{FieldsError, Type};
+
+#[cfg(feature = "derive")]
+pub use neopasses_derive::PassFields;
+
+/// Struct whose members are displayed as pass fields.
+///
+/// Usually implemented with `#[derive(PassFields)]` (feature `derive`, enabled by default):
+///
+/// ```
+/// use chrono::{FixedOffset, TimeZone};
+/// use neopasses::fields::{Fields, FieldValue, PassFields, TransitType, Type};
+///
+/// #[derive(PassFields)]
+/// #[pass(section = "auxiliary")]
+/// struct Flight {
+///     #[pass(section = "header", label = "Gate", change_message = "Gate changed to %@")]
+///     gate: String,
+///     #[pass(key = "boarding", label = "Boarding", date_style = "none", time_style = "short")]
+///     boarding_time: chrono::DateTime<FixedOffset>,
+///     #[pass(label = "Seat")]
+///     seat: Option<String>,
+///     #[pass(section = "back", label = "Fare", currency = "USD")]
+///     fare: f64,
+///     #[pass(skip)]
+///     internal_id: u32,
+/// }
+///
+/// let flight = Flight {
+///     gate: String::from("B7"),
+///     boarding_time: FixedOffset::east_opt(3600).unwrap().with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap(),
+///     seat: None,
+///     fare: 120.5,
+///     internal_id: 1,
+/// };
+///
+/// let fields = Type::BoardingPass {
+///     pass_fields: Fields::default(),
+///     transit_type: TransitType::Air,
+/// }
+/// .with(&flight)
+/// .unwrap();
+///
+/// assert_eq!(Some("Gate changed to %@"), fields.find("gate").unwrap().options.change_message.as_deref());
+/// assert!(fields.find("seat").is_none());
+/// assert_eq!(FieldValue::Currency(120.5), fields.find("fare").unwrap().value);
+/// ```
+pub trait PassFields {
+    /// Add fields to `fields`, keeping keys unique across all sections.
+    ///
+    /// On error `fields` is not changed.
+    fn append_to(&self, fields: &mut Type) -> Result<(), FieldsError>;
+}
+
+impl Type {
+    /// Add fields of [PassFields] struct.
+    pub fn with(mut self, value: &impl PassFields) -> Result<Self, FieldsError> {
+        value.append_to(&mut self)?;
+        Ok(self)
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+    use crate::fields::{
+        Content, DateStyle, FieldValue, Fields, NumberStyle, Section, TextAlignment,
+    };
+
+    #[derive(PassFields)]
+    #[pass(section = "secondary")]
+    struct Ticket<'a> {
+        #[pass(section = "primary", label = "Event")]
+        event: &'a str,
+        #[pass(
+            label = "Doors",
+            date_style = "short",
+            time_style = "short",
+            is_relative
+        )]
+        doors: chrono::DateTime<FixedOffset>,
+        #[pass(key = "row", label = "Row", text_alignment = "right")]
+        row_number: u32,
+        #[pass(section = "back", label = "Discount", number_style = "percent")]
+        discount: Option<f64>,
+        #[pass(skip)]
+        #[allow(dead_code)]
+        order_id: u64,
+    }
+
+    fn make_ticket(discount: Option<f64>) -> Ticket<'static> {
+        Ticket {
+            event: "Concert",
+            doors: FixedOffset::east_opt(9 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2024, 2, 7, 19, 0, 0)
+                .unwrap(),
+            row_number: 12,
+            discount,
+            order_id: 1,
+        }
+    }
+
+    #[test]
+    fn derive_pass_fields() {
+        let fields = Type::EventTicket {
+            pass_fields: Fields::default(),
+        }
+        .with(&make_ticket(Some(0.1)))
+        .unwrap();
+
+        let json = serde_json::to_string_pretty(&fields).unwrap();
+        println!("{}", json);
+
+        assert_eq!(Some(Section::Primary), fields.section_of("event"));
+        assert_eq!(Some(Section::Secondary), fields.section_of("doors"));
+        assert_eq!(Some(Section::Back), fields.section_of("discount"));
+        assert!(fields.find("order_id").is_none());
+
+        let doors = fields.find("doors").unwrap();
+        assert_eq!(Some(DateStyle::Short), doors.options.time_style);
+        assert_eq!(Some(true), doors.options.is_relative);
+        assert!(doors.validate().is_ok());
+
+        let row = fields.find("row").unwrap();
+        assert_eq!(FieldValue::Number(12.0), row.value);
+        assert_eq!(Some(TextAlignment::Right), row.options.text_alignment);
+
+        let discount = fields.find("discount").unwrap();
+        assert_eq!(Some(NumberStyle::Percent), discount.options.number_style);
+    }
+
+    #[test]
+    fn derive_pass_fields_optional_and_duplicate() {
+        let mut fields = Type::EventTicket {
+            pass_fields: Fields::default(),
+        };
+        make_ticket(None).append_to(&mut fields).unwrap();
+        assert!(fields.find("discount").is_none());
+        assert_eq!(3, fields.iter().count());
+
+        let err = make_ticket(None).append_to(&mut fields).unwrap_err();
+        assert_eq!(FieldsError::DuplicateKey(String::from("event")), err);
+    }
+
+    #[test]
+    fn derive_pass_fields_error_keeps_fields() {
+        // Key of the third field is taken, the first two are not added
+        let mut fields = Type::EventTicket {
+            pass_fields: Fields::default(),
+        }
+        .add_back_field(Content::new("row", "12", Default::default()));
+        let err = make_ticket(None).append_to(&mut fields).unwrap_err();
+        assert_eq!(FieldsError::DuplicateKey(String::from("row")), err);
+        assert_eq!(1, fields.iter().count());
+    }
+
+    #[test]
+    fn derive_pass_fields_numbers() {
+        #[derive(PassFields)]
+        #[pass(section = "auxiliary")]
+        struct Counters {
+            points: u64,
+            balance: i64,
+            rating: f32,
+        }
+
+        let fields = Type::StoreCard {
+            pass_fields: Fields::default(),
+        }
+        .with(&Counters {
+            points: 1200,
+            balance: -5,
+            rating: 4.5,
+        })
+        .unwrap();
+        assert_eq!(
+            FieldValue::Number(1200.0),
+            fields.find("points").unwrap().value
+        );
+        assert_eq!(
+            FieldValue::Number(-5.0),
+            fields.find("balance").unwrap().value
+        );
+        assert_eq!(
+            FieldValue::Number(4.5),
+            fields.find("rating").unwrap().value
+        );
+    }
+}
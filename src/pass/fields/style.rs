@@ -0,0 +1,358 @@
+use std::mem;
+
+use crate::package::resource;
+
+use super::{DeviceClass, Overflow, Section, TransitType, Type};
+
+/// Pass style, defines layout of fields and allowed images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Boarding pass with the type of transit
+    BoardingPass(TransitType),
+    /// Coupon
+    Coupon,
+    /// Event ticket
+    EventTicket,
+    /// Generic pass
+    Generic,
+    /// Store card
+    StoreCard,
+}
+
+impl Style {
+    /// Maximum number of fields the style shows in a section on the front of a pass.
+    ///
+    /// Back fields are not limited, `None` is returned for [Section::Back]. Coupons and
+    /// store cards also limit secondary and auxiliary fields together, see
+    /// [combined_capacity](Style::combined_capacity).
+    pub fn capacity(&self, section: Section) -> Option<usize> {
+        match (self, section) {
+            (_, Section::Back) => None,
+            (_, Section::Header) => Some(3),
+            (Style::BoardingPass(_), Section::Primary) => Some(2),
+            (_, Section::Primary) => Some(1),
+            (Style::BoardingPass(_), Section::Secondary | Section::Auxiliary) => Some(5),
+            (_, Section::Secondary | Section::Auxiliary) => Some(4),
+        }
+    }
+
+    /// Maximum number of secondary and auxiliary fields combined, for styles that limit
+    /// them together.
+    ///
+    /// Coupons and store cards show up to 4 secondary and auxiliary fields in total.
+    pub fn combined_capacity(&self) -> Option<usize> {
+        match self {
+            Style::Coupon | Style::StoreCard => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Checks that the style displays the image.
+    ///
+    /// * Boarding passes: icon, logo, footer
+    /// * Coupons and store cards: icon, logo, strip
+    /// * Event tickets: icon, logo, strip, background, thumbnail (strip is not allowed together with background or thumbnail)
+    /// * Generic passes: icon, logo, thumbnail
+    pub fn allows_resource(&self, image: &resource::Type) -> bool {
+        use resource::Type::*;
+        matches!(
+            (self, image),
+            (_, Icon(_) | Logo(_))
+                | (Style::BoardingPass(_), Footer(_))
+                | (Style::Coupon | Style::StoreCard, Strip(_))
+                | (Style::EventTicket, Strip(_) | Background(_) | Thumbnail(_))
+                | (Style::Generic, Thumbnail(_))
+        )
+    }
+}
+
+/// Field that changed section during [conversion](Type::convert_to)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovedField {
+    /// Key of the field
+    pub key: String,
+    /// Section before conversion
+    pub from: Section,
+    /// Section after conversion
+    pub to: Section,
+}
+
+/// Changes made by [Type::convert_to]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConversionReport {
+    /// Fields moved to another section, because the section of the new style is full.
+    pub moved: Vec<MovedField>,
+
+    /// Keys of fields that no longer fit on the front of the pass.
+    ///
+    /// These fields are moved to back fields.
+    pub dropped: Vec<String>,
+
+    /// Keys of front fields that are likely to be truncated in the new layout
+    /// on [Regular](DeviceClass::Regular) devices, see [Type::estimate_layout].
+    pub truncated: Vec<String>,
+
+    /// Images that were allowed by the previous style, but are not displayed by the new style.
+    ///
+    /// Each image is reported once in [Standard](resource::Version::Standard) version, other sizes are affected too.
+    pub invalid_resources: Vec<resource::Type>,
+}
+
+impl ConversionReport {
+    /// Checks that conversion didn't change the layout.
+    pub fn is_lossless(&self) -> bool {
+        self.moved.is_empty() && self.truncated.is_empty() && self.invalid_resources.is_empty()
+    }
+}
+
+impl Type {
+    /// Style of the pass.
+    pub fn style(&self) -> Style {
+        match self {
+            Type::BoardingPass { transit_type, .. } => Style::BoardingPass(*transit_type),
+            Type::Coupon { .. } => Style::Coupon,
+            Type::EventTicket { .. } => Style::EventTicket,
+            Type::Generic { .. } => Style::Generic,
+            Type::StoreCard { .. } => Style::StoreCard,
+        }
+    }
+
+    /// Convert fields to another pass style.
+    ///
+    /// Fields that exceed [capacity](Style::capacity) of a section move down to the next one
+    /// (primary to secondary, secondary to auxiliary); fields that don't fit on the front
+    /// move to back fields. Nothing is removed.
+    ///
+    /// ```
+    /// use neopasses::fields::{Content, Fields, Section, Style, TransitType, Type};
+    /// use neopasses::resource;
+    ///
+    /// let generic = Type::Generic {
+    ///     pass_fields: Fields::default(),
+    /// }
+    /// .add_primary_field(Content::new("from", "SFO", Default::default()))
+    /// .add_secondary_field(Content::new("to", "JFK", Default::default()));
+    ///
+    /// let (boarding_pass, report) = generic.convert_to(Style::BoardingPass(TransitType::Air));
+    ///
+    /// assert_eq!(Style::BoardingPass(TransitType::Air), boarding_pass.style());
+    /// assert_eq!(Some(Section::Secondary), boarding_pass.section_of("to"));
+    /// assert!(report.moved.is_empty());
+    ///
+    /// // Boarding passes don't display thumbnail images
+    /// assert_eq!(
+    ///     vec![resource::Type::Thumbnail(resource::Version::Standard)],
+    ///     report.invalid_resources
+    /// );
+    /// ```
+    pub fn convert_to(mut self, style: Style) -> (Type, ConversionReport) {
+        let previous_style = self.style();
+        let mut fields = mem::take(self.fields_mut());
+        let mut report = ConversionReport::default();
+
+        // Overflow cascades down the front of the pass, then to the back
+        let front = [
+            (Section::Header, Section::Back),
+            (Section::Primary, Section::Secondary),
+            (Section::Secondary, Section::Auxiliary),
+            (Section::Auxiliary, Section::Back),
+        ];
+        for (section, overflow_to) in front {
+            let mut capacity = style.capacity(section).unwrap_or(usize::MAX);
+            if let (Section::Auxiliary, Some(combined)) = (section, style.combined_capacity()) {
+                let secondary = fields.section(Section::Secondary).len();
+                capacity = capacity.min(combined.saturating_sub(secondary));
+            }
+            let list = fields.section_mut(section);
+            if list.len() <= capacity {
+                continue;
+            }
+            let overflow = list.split_off(capacity);
+            for mut field in overflow {
+                match report.moved.iter_mut().find(|m| m.key == field.key) {
+                    Some(moved) => moved.to = overflow_to,
+                    None => report.moved.push(MovedField {
+                        key: field.key.clone(),
+                        from: section,
+                        to: overflow_to,
+                    }),
+                }
+                if overflow_to == Section::Back {
+                    report.dropped.push(field.key.clone());
+                    // Back fields can't be aligned
+                    field.options.text_alignment = None;
+                }
+                fields.section_mut(overflow_to).push(field);
+            }
+        }
+
+        use resource::{Type::*, Version::Standard};
+        for image in [
+            Background(Standard),
+            Footer(Standard),
+            Strip(Standard),
+            Thumbnail(Standard),
+        ] {
+            if previous_style.allows_resource(&image) && !style.allows_resource(&image) {
+                report.invalid_resources.push(image);
+            }
+        }
+
+        let converted = match style {
+            Style::BoardingPass(transit_type) => Type::BoardingPass {
+                pass_fields: fields,
+                transit_type,
+            },
+            Style::Coupon => Type::Coupon {
+                pass_fields: fields,
+            },
+            Style::EventTicket => Type::EventTicket {
+                pass_fields: fields,
+            },
+            Style::Generic => Type::Generic {
+                pass_fields: fields,
+            },
+            Style::StoreCard => Type::StoreCard {
+                pass_fields: fields,
+            },
+        };
+
+        for issue in converted.estimate_layout(&[DeviceClass::Regular]) {
+            if issue.overflow == Overflow::Truncates && !report.truncated.contains(&issue.key) {
+                report.truncated.push(issue.key);
+            }
+        }
+        (converted, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::{Content, ContentOptions, Fields, TextAlignment};
+
+    #[test]
+    fn convert_generic_to_event_ticket() {
+        let generic = Type::Generic {
+            pass_fields: Fields::default(),
+        }
+        .add_primary_field(Content::new("name", "Concert", Default::default()))
+        .add_secondary_field(Content::new("row", "12", Default::default()));
+
+        let (event_ticket, report) = generic.convert_to(Style::EventTicket);
+
+        assert!(matches!(event_ticket, Type::EventTicket { .. }));
+        assert_eq!(Some(Section::Primary), event_ticket.section_of("name"));
+        assert!(report.moved.is_empty());
+        assert!(report.dropped.is_empty());
+        assert!(report.truncated.is_empty());
+        // Thumbnail is allowed for both styles
+        assert!(report.invalid_resources.is_empty());
+    }
+
+    #[test]
+    fn convert_boarding_pass_to_coupon() {
+        let boarding_pass = Type::BoardingPass {
+            pass_fields: Fields::default(),
+            transit_type: TransitType::Train,
+        }
+        .add_primary_field(Content::new("from", "Paris", Default::default()))
+        .add_primary_field(Content::new("to", "Lyon", Default::default()))
+        .add_auxiliary_field(Content::new("car", "7", Default::default()))
+        .add_auxiliary_field(Content::new("seat", "42", Default::default()))
+        .add_auxiliary_field(Content::new("class", "1", Default::default()))
+        .add_auxiliary_field(Content::new("coach", "A", Default::default()))
+        .add_auxiliary_field(Content::new(
+            "deck",
+            "Upper",
+            ContentOptions {
+                text_alignment: Some(TextAlignment::Right),
+                ..Default::default()
+            },
+        ));
+
+        let (coupon, report) = boarding_pass.convert_to(Style::Coupon);
+
+        assert_eq!(Style::Coupon, coupon.style());
+        assert_eq!(
+            vec![
+                MovedField {
+                    key: String::from("to"),
+                    from: Section::Primary,
+                    to: Section::Secondary,
+                },
+                MovedField {
+                    key: String::from("coach"),
+                    from: Section::Auxiliary,
+                    to: Section::Back,
+                },
+                MovedField {
+                    key: String::from("deck"),
+                    from: Section::Auxiliary,
+                    to: Section::Back,
+                },
+            ],
+            report.moved
+        );
+        // Coupons show 4 secondary and auxiliary fields combined
+        assert_eq!(
+            vec![String::from("coach"), String::from("deck")],
+            report.dropped
+        );
+        assert_eq!(Some(Section::Back), coupon.section_of("deck"));
+        assert_eq!(None, coupon.find("deck").unwrap().options.text_alignment);
+        assert_eq!(7, coupon.iter().count());
+        assert_eq!(
+            vec![resource::Type::Footer(resource::Version::Standard)],
+            report.invalid_resources
+        );
+        assert!(!report.is_lossless());
+    }
+
+    #[test]
+    fn convert_to_boarding_pass_reports_truncation() {
+        let event_ticket = Type::EventTicket {
+            pass_fields: Fields::default(),
+        }
+        .add_primary_field(Content::new(
+            "from",
+            "San Francisco International",
+            Default::default(),
+        ));
+
+        let (boarding_pass, report) =
+            event_ticket.convert_to(Style::BoardingPass(TransitType::Air));
+
+        assert!(matches!(
+            boarding_pass,
+            Type::BoardingPass {
+                transit_type: TransitType::Air,
+                ..
+            }
+        ));
+        assert_eq!(vec![String::from("from")], report.truncated);
+        assert_eq!(
+            vec![
+                resource::Type::Background(resource::Version::Standard),
+                resource::Type::Strip(resource::Version::Standard),
+                resource::Type::Thumbnail(resource::Version::Standard),
+            ],
+            report.invalid_resources
+        );
+    }
+
+    #[test]
+    fn store_card_serialization() {
+        let store_card = Type::StoreCard {
+            pass_fields: Fields::default(),
+        }
+        .add_primary_field(Content::currency("balance", 25.0, "USD"));
+
+        let json = serde_json::to_string(&store_card).unwrap();
+        let json_expected = r#"{"storeCard":{"auxiliaryFields":[],"backFields":[],"headerFields":[],"primaryFields":[{"key":"balance","value":25,"currencyCode":"USD"}],"secondaryFields":[]}}"#;
+        assert_eq!(json_expected, json);
+
+        let store_card: Type = serde_json::from_str(json_expected).unwrap();
+        assert_eq!(Style::StoreCard, store_card.style());
+    }
+}
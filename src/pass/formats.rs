@@ -0,0 +1,508 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+use crate::error::FormatError;
+
+use super::Pass;
+use super::fields::with_default_groups;
+
+/// Key of a mapping replaced by the content of another file
+const INCLUDE_KEY: &str = "$include";
+
+/// Key of YAML merge mapping
+const MERGE_KEY: &str = "<<";
+
+/// Directory included files must be in, and canonical paths of files being included
+///
+/// Files being included are kept to detect cycles.
+struct Includes {
+    root: PathBuf,
+    stack: Vec<PathBuf>,
+}
+
+impl Pass {
+    /// Build pass from YAML data.
+    ///
+    /// Keys are the same as in pass.json, but empty field groups can be left out.
+    /// Anchors, aliases and merge keys (`<<`) are supported. `$include` is rejected,
+    /// it is only resolved by [Pass::from_yaml_file].
+    ///
+    /// ```
+    /// use neopasses::Pass;
+    ///
+    /// let yaml = r#"
+    /// formatVersion: 1
+    /// organizationName: Apple inc.
+    /// description: Example pass
+    /// passTypeIdentifier: pass.com.example
+    /// teamIdentifier: AA00AA0A0A
+    /// serialNumber: ABCDEFG1234567890
+    /// generic:
+    ///   primaryFields:
+    ///     - &name { key: name, value: John Appleseed }
+    ///   backFields:
+    ///     - *name
+    /// "#;
+    /// let pass = Pass::from_yaml(yaml).unwrap();
+    /// assert_eq!("Example pass", pass.config.description);
+    /// ```
+    pub fn from_yaml(data: &str) -> Result<Self, FormatError> {
+        Self::from_yaml_in(data, None)
+    }
+
+    /// Build pass from YAML file.
+    ///
+    /// Mappings with `$include` key are replaced by the content of the included YAML, TOML or JSON file,
+    /// resolved relative to the including file. Other keys of the mapping override included keys.
+    /// Included files must be in the directory of `path` or below it: absolute paths and `..`
+    /// leaving the directory are rejected. Invalid values of documents with includes or merge keys
+    /// are reported without position, as they may come from several files.
+    /// An included list inside a list is spliced into it, so field blocks can be shared:
+    ///
+    /// ```yaml
+    /// backFields:
+    ///   - $include: common/terms.yaml
+    ///   - key: support
+    ///     value: support@example.com
+    /// ```
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self, FormatError> {
+        let path = path.as_ref();
+        let data = read(path)?;
+        let includes = Includes::of(path)?;
+        Self::from_yaml_in(&data, Some(includes)).map_err(|err| err.in_file(path))
+    }
+
+    /// Serialize pass to YAML.
+    pub fn to_yaml(&self) -> Result<String, FormatError> {
+        serde_yaml_ng::to_string(self).map_err(|err| FormatError::Serialize(err.to_string()))
+    }
+
+    /// Build pass from TOML data.
+    ///
+    /// Keys are the same as in pass.json, dates are strings and empty field groups can be left out.
+    /// `$include` is rejected, it is only resolved by [Pass::from_toml_file].
+    ///
+    /// ```
+    /// use neopasses::Pass;
+    ///
+    /// let toml = r#"
+    /// formatVersion = 1
+    /// organizationName = "Apple inc."
+    /// description = "Example pass"
+    /// passTypeIdentifier = "pass.com.example"
+    /// teamIdentifier = "AA00AA0A0A"
+    /// serialNumber = "ABCDEFG1234567890"
+    ///
+    /// [[generic.primaryFields]]
+    /// key = "name"
+    /// value = "John Appleseed"
+    /// "#;
+    /// let pass = Pass::from_toml(toml).unwrap();
+    /// assert_eq!("Example pass", pass.config.description);
+    /// ```
+    pub fn from_toml(data: &str) -> Result<Self, FormatError> {
+        Self::from_toml_in(data, None)
+    }
+
+    /// Build pass from TOML file, with `$include` resolved relative to the file
+    /// (see [Pass::from_yaml_file]).
+    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self, FormatError> {
+        let path = path.as_ref();
+        let data = read(path)?;
+        let includes = Includes::of(path)?;
+        Self::from_toml_in(&data, Some(includes)).map_err(|err| err.in_file(path))
+    }
+
+    /// Serialize pass to TOML.
+    pub fn to_toml(&self) -> Result<String, FormatError> {
+        toml::to_string_pretty(self).map_err(|err| FormatError::Serialize(err.to_string()))
+    }
+
+    fn from_yaml_in(data: &str, includes: Option<Includes>) -> Result<Self, FormatError> {
+        let (value, merged) = parse_yaml(data)?;
+        if !merged && !has_include(&value) {
+            // Typed deserialization keeps positions of errors
+            return with_default_groups(|| serde_yaml_ng::from_str(data))
+                .map_err(FormatError::from);
+        }
+        Self::from_resolved(value, includes)
+    }
+
+    fn from_toml_in(data: &str, includes: Option<Includes>) -> Result<Self, FormatError> {
+        let value = parse_toml(data)?;
+        if !has_include(&value) {
+            return with_default_groups(|| toml::from_str(data))
+                .map_err(|err| toml_error(data, err));
+        }
+        Self::from_resolved(value, includes)
+    }
+
+    fn from_resolved(value: Value, includes: Option<Includes>) -> Result<Self, FormatError> {
+        let value = match includes {
+            Some(mut includes) => {
+                let dir = includes.root.clone();
+                resolve(value, &dir, &mut includes)?
+            }
+            None if has_include(&value) => return Err(FormatError::IncludeNotAllowed),
+            None => value,
+        };
+        with_default_groups(|| serde_json::from_value(value))
+            .map_err(|err| FormatError::Invalid(err.to_string()))
+    }
+}
+
+impl Includes {
+    /// Includes of pass file at `path`, resolved in its directory
+    fn of(path: &Path) -> Result<Self, FormatError> {
+        let file = canonicalize(path)?;
+        Ok(Self {
+            root: parent(&file).to_path_buf(),
+            stack: vec![file],
+        })
+    }
+}
+
+/// Parse YAML as JSON value, merge keys are applied
+///
+/// Returns `true` along with the value if the document had merge keys.
+fn parse_yaml(data: &str) -> Result<(Value, bool), FormatError> {
+    let mut value: serde_yaml_ng::Value = serde_yaml_ng::from_str(data)?;
+    let merged = has_merge(&value);
+    value.apply_merge()?;
+    let value = serde_json::to_value(value).map_err(|err| FormatError::Invalid(err.to_string()))?;
+    Ok((value, merged))
+}
+
+/// Parse TOML as JSON value
+fn parse_toml(data: &str) -> Result<Value, FormatError> {
+    let table: toml::Table = toml::from_str(data).map_err(|err| toml_error(data, err))?;
+    serde_json::to_value(table).map_err(|err| FormatError::Invalid(err.to_string()))
+}
+
+/// Converts TOML error with byte span to line and column
+fn toml_error(data: &str, err: toml::de::Error) -> FormatError {
+    let message = String::from(err.message());
+    let Some(span) = err.span() else {
+        return FormatError::Invalid(message);
+    };
+    let before = &data[..span.start.min(data.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    FormatError::Located {
+        message,
+        line,
+        column,
+    }
+}
+
+/// Checks that value contains `$include` keys
+fn has_include(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.contains_key(INCLUDE_KEY) || map.values().any(has_include),
+        Value::Array(list) => list.iter().any(has_include),
+        _ => false,
+    }
+}
+
+/// Checks that YAML value contains merge keys
+fn has_merge(value: &serde_yaml_ng::Value) -> bool {
+    match value {
+        serde_yaml_ng::Value::Mapping(map) => {
+            map.contains_key(MERGE_KEY) || map.values().any(has_merge)
+        }
+        serde_yaml_ng::Value::Sequence(list) => list.iter().any(has_merge),
+        serde_yaml_ng::Value::Tagged(tagged) => has_merge(&tagged.value),
+        _ => false,
+    }
+}
+
+/// Replaces `$include` mappings with content of files, relative to `dir`
+fn resolve(value: Value, dir: &Path, includes: &mut Includes) -> Result<Value, FormatError> {
+    match value {
+        Value::Object(mut map) => {
+            let included = match map.remove(INCLUDE_KEY) {
+                Some(Value::String(path)) => Some(include(dir, Path::new(&path), includes)?),
+                Some(other) => {
+                    return Err(FormatError::Invalid(format!(
+                        "`{INCLUDE_KEY}` must be a path, got `{other}`"
+                    )));
+                }
+                None => None,
+            };
+            let mut resolved = Map::new();
+            for (key, value) in map {
+                resolved.insert(key, resolve(value, dir, includes)?);
+            }
+            match included {
+                Some(Value::Object(mut included)) => {
+                    // Keys next to `$include` override included keys
+                    included.extend(resolved);
+                    Ok(Value::Object(included))
+                }
+                Some(included) if resolved.is_empty() => Ok(included),
+                Some(_) => Err(FormatError::Invalid(format!(
+                    "`{INCLUDE_KEY}` of a list or a value can't have other keys"
+                ))),
+                None => Ok(Value::Object(resolved)),
+            }
+        }
+        Value::Array(list) => {
+            let mut resolved = Vec::with_capacity(list.len());
+            for item in list {
+                let splice = matches!(&item, Value::Object(map) if map.len() == 1 && map.contains_key(INCLUDE_KEY));
+                match resolve(item, dir, includes)? {
+                    Value::Array(items) if splice => resolved.extend(items),
+                    item => resolved.push(item),
+                }
+            }
+            Ok(Value::Array(resolved))
+        }
+        value => Ok(value),
+    }
+}
+
+/// Reads file included as `path` from `dir`, format is chosen by extension
+fn include(dir: &Path, path: &Path, includes: &mut Includes) -> Result<Value, FormatError> {
+    if path.has_root() {
+        return Err(FormatError::IncludeOutside(path.to_path_buf()));
+    }
+    let full_path = dir.join(path);
+    let canonical = canonicalize(&full_path)?;
+    if !canonical.starts_with(&includes.root) {
+        return Err(FormatError::IncludeOutside(path.to_path_buf()));
+    }
+    if includes.stack.contains(&canonical) {
+        return Err(FormatError::IncludeCycle(path.to_path_buf()));
+    }
+    let data = read(&canonical)?;
+    let value = match canonical.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => parse_toml(&data),
+        Some("json") => serde_json::from_str(&data).map_err(FormatError::from),
+        _ => parse_yaml(&data).map(|(value, _)| value),
+    }
+    .map_err(|err| err.in_file(path))?;
+
+    let dir = parent(&canonical).to_path_buf();
+    includes.stack.push(canonical);
+    let resolved = resolve(value, &dir, includes).map_err(|err| err.in_file(path));
+    includes.stack.pop();
+    resolved
+}
+
+fn read(path: &Path) -> Result<String, FormatError> {
+    fs::read_to_string(path).map_err(|source| FormatError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Absolute path without `..` and symbolic links, so the same file has the same path
+fn canonicalize(path: &Path) -> Result<PathBuf, FormatError> {
+    fs::canonicalize(path).map_err(|source| FormatError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parent(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pass::{PassBuilder, fields, make_config};
+
+    fn make_pass() -> Pass {
+        PassBuilder::new(make_config())
+            .fields(
+                fields::Type::Generic {
+                    pass_fields: fields::Fields::default(),
+                }
+                .add_primary_field(fields::Content::new("name", "John", Default::default()))
+                .add_back_field(fields::Content::currency("balance", 25.5, "USD")),
+            )
+            .build()
+    }
+
+    #[test]
+    fn yaml_roundtrip() {
+        let pass = make_pass();
+        let yaml = pass.to_yaml().unwrap();
+        println!("{}", yaml);
+        let pass_read = Pass::from_yaml(&yaml).unwrap();
+        assert_eq!(pass.make_json().unwrap(), pass_read.make_json().unwrap());
+    }
+
+    #[test]
+    fn toml_roundtrip() {
+        let pass = make_pass();
+        let toml = pass.to_toml().unwrap();
+        println!("{}", toml);
+        let pass_read = Pass::from_toml(&toml).unwrap();
+        assert_eq!(pass.make_json().unwrap(), pass_read.make_json().unwrap());
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        let yaml = "formatVersion: 1\norganizationName: [1, 2\n";
+        let err = Pass::from_yaml(yaml).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::Located { line: 3, .. }));
+
+        // Type error, not syntax error
+        let yaml = "formatVersion: 1\nteamIdentifier: AA00\n";
+        let err = Pass::from_yaml(yaml).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::Located { .. }));
+        assert!(err.to_string().contains("team identifier"));
+
+        // Field groups are left out
+        let yaml = r#"
+formatVersion: 1
+organizationName: Apple inc.
+description: Example pass
+passTypeIdentifier: pass.com.example
+teamIdentifier: AA00
+serialNumber: "123"
+generic:
+  primaryFields:
+    - { key: name, value: John }
+"#;
+        let err = Pass::from_yaml(yaml).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::Located { .. }));
+        assert!(err.to_string().contains("team identifier"));
+
+        let toml = "formatVersion = 1\norganizationName = [1,\n";
+        let err = Pass::from_toml(toml).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::Located { line: 2, .. }));
+    }
+
+    #[test]
+    fn yaml_merge_keys() {
+        let yaml = r#"
+base: &base
+  organizationName: Apple inc.
+  passTypeIdentifier: pass.com.example
+  teamIdentifier: AA00AA0A0A
+formatVersion: 1
+<<: *base
+description: Example pass
+serialNumber: "123"
+generic: {}
+"#;
+        let pass = Pass::from_yaml(yaml).unwrap();
+        assert_eq!("Apple inc.", pass.config.organization_name);
+
+        // pass.json still requires all field groups
+        let json = serde_json::to_string(&parse_yaml(yaml).unwrap().0).unwrap();
+        let err = Pass::from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("missing field"));
+    }
+
+    #[test]
+    fn yaml_includes() {
+        let dir = std::env::temp_dir().join(format!("neopasses-include-{}", std::process::id()));
+        fs::create_dir_all(dir.join("common")).unwrap();
+        fs::write(
+            dir.join("common/config.toml"),
+            r#"
+formatVersion = 1
+organizationName = "Apple inc."
+passTypeIdentifier = "pass.com.example"
+teamIdentifier = "AA00AA0A0A"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("common/terms.yaml"),
+            "- { key: terms, value: No refunds }\n- { key: support, value: help@example.com }\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("pass.yaml"),
+            r#"
+$include: common/config.toml
+description: Example pass
+serialNumber: "123"
+generic:
+  backFields:
+    - $include: common/terms.yaml
+    - { key: website, value: example.com }
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("cycle.yaml"), "$include: cycle.yaml\n").unwrap();
+        let name = dir.file_name().unwrap().to_str().unwrap();
+        fs::write(
+            dir.join("self.yaml"),
+            format!("generic:\n  $include: ../{name}/self.yaml\n"),
+        )
+        .unwrap();
+
+        let pass = Pass::from_yaml_file(dir.join("pass.yaml")).unwrap();
+        assert_eq!("Apple inc.", pass.config.organization_name);
+        let keys: Vec<&str> = pass
+            .fields
+            .fields()
+            .back_fields
+            .iter()
+            .map(|field| field.key.as_str())
+            .collect();
+        assert_eq!(vec!["terms", "support", "website"], keys);
+
+        let err = Pass::from_yaml_file(dir.join("cycle.yaml")).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::IncludeCycle(_)));
+        // Same file by another path
+        let err = Pass::from_yaml_file(dir.join("self.yaml")).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::IncludeCycle(_)));
+
+        let err = Pass::from_yaml_file(dir.join("missing.yaml")).unwrap_err();
+        assert!(matches!(err, FormatError::Read { .. }));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn includes_stay_in_directory() {
+        let dir = std::env::temp_dir().join(format!("neopasses-outside-{}", std::process::id()));
+        fs::create_dir_all(dir.join("pass")).unwrap();
+        fs::write(dir.join("secret.yaml"), "organizationName: Secret\n").unwrap();
+        let secret = dir.join("secret.yaml");
+
+        // String input has no directory to resolve includes in
+        let yaml = format!("$include: {}\n", secret.display());
+        let err = Pass::from_yaml(&yaml).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, FormatError::IncludeNotAllowed));
+        let toml = format!("\"$include\" = '{}'\n", secret.display());
+        let err = Pass::from_toml(&toml).unwrap_err();
+        assert!(matches!(err, FormatError::IncludeNotAllowed));
+
+        fs::write(dir.join("pass/absolute.yaml"), &yaml).unwrap();
+        let err = Pass::from_yaml_file(dir.join("pass/absolute.yaml")).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(
+            err,
+            FormatError::InFile { source, .. } if matches!(*source, FormatError::IncludeOutside(_))
+        ));
+
+        fs::write(dir.join("pass/parent.yaml"), "$include: ../secret.yaml\n").unwrap();
+        let err = Pass::from_yaml_file(dir.join("pass/parent.yaml")).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(
+            err,
+            FormatError::InFile { source, .. } if matches!(*source, FormatError::IncludeOutside(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValueError;
+
+/// The Team ID for the Apple Developer Program account, 10 alphanumeric characters.
+///
+/// ```
+/// use neopasses::identifiers::TeamId;
+///
+/// let team: TeamId = "AA00AA0A0A".parse().unwrap();
+/// assert_eq!("AA00AA0A0A", team.as_str());
+///
+/// assert!("AA00".parse::<TeamId>().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct TeamId(String);
+
+impl TeamId {
+    /// Team ID as string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for TeamId {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 10 && s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            Ok(Self(String::from(s)))
+        } else {
+            Err(ValueError::TeamId(String::from(s)))
+        }
+    }
+}
+
+/// The pass type identifier registered with Apple, a reverse-DNS string starting with `pass.`
+///
+/// ```
+/// use neopasses::identifiers::PassTypeId;
+///
+/// let id: PassTypeId = "pass.com.example.ticket".parse().unwrap();
+/// assert_eq!("pass.com.example.ticket", id.as_str());
+///
+/// assert!("com.example.ticket".parse::<PassTypeId>().is_err());
+/// assert!("pass..example".parse::<PassTypeId>().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct PassTypeId(String);
+
+impl PassTypeId {
+    /// Pass type identifier as string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for PassTypeId {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid_label = |label: &str| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        };
+        match s.strip_prefix("pass.") {
+            Some(rest) if rest.split('.').all(valid_label) => Ok(Self(String::from(s))),
+            _ => Err(ValueError::PassTypeId(String::from(s))),
+        }
+    }
+}
+
+/// An App Store identifier of an app associated with the pass.
+///
+/// ```
+/// use neopasses::identifiers::AppStoreId;
+///
+/// let id: AppStoreId = "6448311069".parse().unwrap();
+/// assert_eq!(6448311069, id.get());
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+#[schemars(description = "An App Store identifier of an app associated with the pass.")]
+pub struct AppStoreId(u64);
+
+impl AppStoreId {
+    /// App Store identifier as number
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for AppStoreId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl FromStr for AppStoreId {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u64>()
+            .map(Self)
+            .map_err(|_| ValueError::AppStoreId(String::from(s)))
+    }
+}
+
+/// The unique identifier of a Bluetooth Low Energy location beacon, such as `E2C56DB5-DFFB-48D2-B060-D0F5A71096E0`.
+///
+/// Serialized in lowercase hyphenated form.
+///
+/// ```
+/// use neopasses::identifiers::ProximityUuid;
+///
+/// let uuid: ProximityUuid = "E286373B-15B5-4F4E-BF91-E9E64787724A".parse().unwrap();
+/// assert_eq!("e286373b-15b5-4f4e-bf91-e9e64787724a", uuid.to_string());
+///
+/// assert!("e286373b15b54f4ebf91e9e64787724a".parse::<ProximityUuid>().is_err());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(try_from = "String", into = "String")]
+pub struct ProximityUuid([u8; 16]);
+
+impl ProximityUuid {
+    /// UUID as bytes
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl From<[u8; 16]> for ProximityUuid {
+    fn from(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl FromStr for ProximityUuid {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ValueError::ProximityUuid(String::from(s));
+
+        // Groups of 8-4-4-4-12 hex digits
+        let groups: Vec<&str> = s.split('-').collect();
+        let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+        if lengths != [8, 4, 4, 4, 12] {
+            return Err(err());
+        }
+        let mut bytes = [0u8; 16];
+        hex::decode_to_slice(groups.concat(), &mut bytes).map_err(|_| err())?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for ProximityUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = hex::encode(self.0);
+        write!(
+            f,
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+}
+
+// String conversions for serde and display
+
+macro_rules! string_id {
+    ($type:ty) => {
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $type {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<$type> for String {
+            fn from(id: $type) -> Self {
+                id.0
+            }
+        }
+    };
+}
+
+string_id!(TeamId);
+string_id!(PassTypeId);
+
+impl From<ProximityUuid> for String {
+    fn from(uuid: ProximityUuid) -> Self {
+        uuid.to_string()
+    }
+}
+
+impl fmt::Display for AppStoreId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+macro_rules! try_from_string {
+    ($($type:ty),*) => {
+        $(
+            impl TryFrom<String> for $type {
+                type Error = ValueError;
+
+                fn try_from(s: String) -> Result<Self, Self::Error> {
+                    s.parse()
+                }
+            }
+
+            impl TryFrom<&str> for $type {
+                type Error = ValueError;
+
+                fn try_from(s: &str) -> Result<Self, Self::Error> {
+                    s.parse()
+                }
+            }
+        )*
+    };
+}
+
+try_from_string!(TeamId, PassTypeId, ProximityUuid);
+
+// JSON Schema of string identifiers, with the same format checks as `FromStr`
+
+macro_rules! string_schema {
+    ($type:ident, $pattern:literal) => {
+        impl JsonSchema for $type {
+            fn schema_name() -> Cow<'static, str> {
+                Cow::Borrowed(stringify!($type))
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "pattern": $pattern
+                })
+            }
+        }
+    };
+}
+
+string_schema!(TeamId, "^[A-Za-z0-9]{10}$");
+string_schema!(PassTypeId, r"^pass(\.[A-Za-z0-9_-]+)+$");
+string_schema!(
+    ProximityUuid,
+    "^[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}$"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers_serialization() {
+        let team: TeamId = serde_json::from_str(r#""AA00AA0A0A""#).unwrap();
+        assert_eq!(r#""AA00AA0A0A""#, serde_json::to_string(&team).unwrap());
+
+        let id: PassTypeId = serde_json::from_str(r#""pass.com.example""#).unwrap();
+        assert_eq!(r#""pass.com.example""#, serde_json::to_string(&id).unwrap());
+
+        let id: AppStoreId = serde_json::from_str("6448311069").unwrap();
+        assert_eq!("6448311069", serde_json::to_string(&id).unwrap());
+
+        let uuid: ProximityUuid =
+            serde_json::from_str(r#""e286373b-15b5-4f4e-bf91-e9e64787724a""#).unwrap();
+        assert_eq!(
+            r#""e286373b-15b5-4f4e-bf91-e9e64787724a""#,
+            serde_json::to_string(&uuid).unwrap()
+        );
+    }
+
+    #[test]
+    fn identifiers_errors() {
+        let err = serde_json::from_str::<TeamId>(r#""AA00AA0A0""#).unwrap_err();
+        assert!(err.to_string().contains("10 alphanumeric characters"));
+
+        let err = "com.example".parse::<PassTypeId>().unwrap_err();
+        assert_eq!(ValueError::PassTypeId(String::from("com.example")), err);
+        assert!("pass.".parse::<PassTypeId>().is_err());
+        assert!("pass.com.exa mple".parse::<PassTypeId>().is_err());
+
+        let err = "-1".parse::<AppStoreId>().unwrap_err();
+        assert_eq!(
+            "App Store identifier must be a non-negative integer, got `-1`",
+            err.to_string()
+        );
+        assert_eq!(0, "0".parse::<AppStoreId>().unwrap().get());
+
+        assert!(
+            "g286373b-15b5-4f4e-bf91-e9e64787724a"
+                .parse::<ProximityUuid>()
+                .is_err()
+        );
+        assert!(
+            "e286373b-15b5-4f4e-bf91-e9e64787724"
+                .parse::<ProximityUuid>()
+                .is_err()
+        );
+    }
+}
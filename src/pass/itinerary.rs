@@ -0,0 +1,505 @@
+//! Boarding passes of a flight itinerary, one pass per leg.
+//!
+//! Passes of an [Itinerary] share [grouping_identifier](Pass::grouping_identifier), so Wallet
+//! displays connecting flights together, and have the same field layout and semantic tags.
+//!
+//! ```
+//! use chrono::prelude::*;
+//! use neopasses::PassConfig;
+//! use neopasses::barcode::BarcodeFormat;
+//! use neopasses::itinerary::{Airport, FlightLeg, Itinerary};
+//! use neopasses::travel::Passenger;
+//!
+//! let montreal = FixedOffset::west_opt(5 * 3600).unwrap();
+//! let frankfurt = FixedOffset::east_opt(3600).unwrap();
+//! let leg = FlightLeg::new(
+//!     "AC",
+//!     834,
+//!     Airport::new("YUL"),
+//!     Airport::new("FRA"),
+//!     montreal.with_ymd_and_hms(2024, 11, 21, 18, 30, 0).unwrap(),
+//!     frankfurt.with_ymd_and_hms(2024, 11, 22, 7, 45, 0).unwrap(),
+//! );
+//!
+//! let passes = Itinerary::new("ABC123", Passenger::new("Luc", "Desmarais"))
+//!     .add_leg(leg)
+//!     .bcbp_barcode(BarcodeFormat::PDF417)
+//!     .passes(&PassConfig {
+//!         organization_name: String::from("Air Canada"),
+//!         description: String::from("Boarding pass"),
+//!         pass_type_identifier: "pass.com.example".parse().unwrap(),
+//!         team_identifier: "AA00AA0A0A".parse().unwrap(),
+//!         serial_number: String::from("ABC123"),
+//!     })
+//!     .unwrap();
+//!
+//! assert_eq!("ABC123-1", passes[0].config.serial_number);
+//! assert_eq!(Some("ABC123"), passes[0].grouping_identifier.as_deref());
+//! assert_eq!(Some(834), passes[0].semantics.flight_number);
+//! ```
+
+use chrono::{DateTime, Duration, FixedOffset};
+
+use crate::BcbpError;
+
+use super::barcode::BarcodeFormat;
+use super::barcode::bcbp::BoardingPass;
+use super::fields::{self, Content, ContentOptions, DateStyle, TransitType};
+use super::relevant_date::RelevantDate;
+use super::semantic_tags::SemanticTags;
+use super::travel::{Passenger, labeled, seat_semantics};
+use super::{Pass, PassBuilder, PassConfig};
+
+/// Departure or destination airport of [FlightLeg]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Airport {
+    /// IATA airport code, such as `YUL`
+    pub code: String,
+
+    /// Name displayed as label of the airport code, such as `Montréal`
+    pub name: Option<String>,
+
+    pub terminal: Option<String>,
+}
+
+/// Flight of [Itinerary]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightLeg {
+    /// IATA airline designator, such as `AC`
+    pub airline_code: String,
+    pub flight_number: u32,
+    pub departure: Airport,
+    pub destination: Airport,
+    pub boarding_time: DateTime<FixedOffset>,
+    pub departure_time: DateTime<FixedOffset>,
+    pub arrival_time: DateTime<FixedOffset>,
+    pub gate: Option<String>,
+
+    /// Seat, such as `12C`
+    pub seat: Option<String>,
+    pub boarding_group: Option<String>,
+    pub boarding_sequence_number: Option<String>,
+
+    /// IATA compartment code written to BCBP barcodes, such as `Y` for economy or `J` for business
+    pub compartment: char,
+}
+
+/// Flights of a passenger under one booking
+#[derive(Debug, Clone, PartialEq)]
+pub struct Itinerary {
+    /// Booking reference (PNR)
+    pub confirmation_number: String,
+    pub passenger: Passenger,
+    pub legs: Vec<FlightLeg>,
+
+    /// Frequent flyer number of the passenger, written to BCBP barcodes
+    pub frequent_flyer_number: Option<String>,
+
+    /// Priority status of the passenger, such as `Gold`
+    pub priority_status: Option<String>,
+
+    /// [grouping_identifier](Pass::grouping_identifier) of the passes, the confirmation number if `None`
+    pub grouping_identifier: Option<String>,
+
+    /// Passes are relevant from this long before boarding
+    pub relevant_before_boarding: Duration,
+
+    /// Passes are relevant until this long after boarding
+    pub relevant_after_boarding: Duration,
+
+    /// Format of BCBP barcodes of the passes, no barcode if `None`
+    pub bcbp_format: Option<BarcodeFormat>,
+}
+
+impl Airport {
+    /// Creates `Airport` with IATA code.
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: String::from(code),
+            ..Default::default()
+        }
+    }
+}
+
+impl FlightLeg {
+    /// Creates `FlightLeg` in economy, boarding 30 minutes before departure.
+    pub fn new(
+        airline_code: &str,
+        flight_number: u32,
+        departure: Airport,
+        destination: Airport,
+        departure_time: DateTime<FixedOffset>,
+        arrival_time: DateTime<FixedOffset>,
+    ) -> Self {
+        Self {
+            airline_code: String::from(airline_code),
+            flight_number,
+            departure,
+            destination,
+            boarding_time: departure_time - Duration::minutes(30),
+            departure_time,
+            arrival_time,
+            gate: None,
+            seat: None,
+            boarding_group: None,
+            boarding_sequence_number: None,
+            compartment: 'Y',
+        }
+    }
+
+    /// Flight code, such as `AC834`
+    pub fn flight_code(&self) -> String {
+        format!("{}{}", self.airline_code, self.flight_number)
+    }
+}
+
+impl Itinerary {
+    /// Creates `Itinerary` without legs, relevant from 3 hours before boarding until an hour after.
+    pub fn new(confirmation_number: &str, passenger: Passenger) -> Self {
+        Self {
+            confirmation_number: String::from(confirmation_number),
+            passenger,
+            legs: Vec::new(),
+            frequent_flyer_number: None,
+            priority_status: None,
+            grouping_identifier: None,
+            relevant_before_boarding: Duration::hours(3),
+            relevant_after_boarding: Duration::hours(1),
+            bcbp_format: None,
+        }
+    }
+
+    /// Adding [FlightLeg] to [legs](Itinerary::legs)
+    pub fn add_leg(mut self, leg: FlightLeg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Adding [frequent_flyer_number](Itinerary::frequent_flyer_number)
+    pub fn frequent_flyer_number(mut self, field: String) -> Self {
+        self.frequent_flyer_number = Some(field);
+        self
+    }
+
+    /// Adding [priority_status](Itinerary::priority_status)
+    pub fn priority_status(mut self, field: String) -> Self {
+        self.priority_status = Some(field);
+        self
+    }
+
+    /// Adding [grouping_identifier](Itinerary::grouping_identifier)
+    pub fn grouping_identifier(mut self, field: String) -> Self {
+        self.grouping_identifier = Some(field);
+        self
+    }
+
+    /// Set relevance window of the passes around boarding time
+    pub fn relevance(mut self, before_boarding: Duration, after_boarding: Duration) -> Self {
+        self.relevant_before_boarding = before_boarding;
+        self.relevant_after_boarding = after_boarding;
+        self
+    }
+
+    /// Add BCBP barcode of the leg in `format` to each pass
+    pub fn bcbp_barcode(mut self, format: BarcodeFormat) -> Self {
+        self.bcbp_format = Some(format);
+        self
+    }
+
+    /// Boarding pass of each leg.
+    ///
+    /// Passes take `config` with serial number suffixed by the number of the leg, such as `ABC123-2`.
+    /// Fails if BCBP barcode can't be encoded, such as for non-ASCII passenger name.
+    pub fn passes(&self, config: &PassConfig) -> Result<Vec<Pass>, BcbpError> {
+        let grouping_identifier = self
+            .grouping_identifier
+            .clone()
+            .unwrap_or_else(|| self.confirmation_number.clone());
+
+        self.legs
+            .iter()
+            .enumerate()
+            .map(|(index, leg)| {
+                let config = PassConfig {
+                    serial_number: format!("{}-{}", config.serial_number, index + 1),
+                    ..config.clone()
+                };
+                let semantics = self.semantics(leg);
+                let mut builder = PassBuilder::new(config)
+                    .grouping_identifier(grouping_identifier.clone())
+                    .fields(self.fields(leg))
+                    .add_relevant_date(RelevantDate::interval(
+                        leg.boarding_time - self.relevant_before_boarding,
+                        leg.boarding_time + self.relevant_after_boarding,
+                    ));
+
+                let Some(format) = &self.bcbp_format else {
+                    return Ok(builder.semantics(semantics).build());
+                };
+                let mut bcbp = BoardingPass::from_semantics(&semantics)?;
+                bcbp.legs[0].compartment = leg.compartment;
+                builder = builder
+                    .semantics(semantics)
+                    .bcbp_barcode(&bcbp, 0, format.clone())?;
+                let mut pass = builder.build();
+                if let Some(barcode) = pass.barcodes.last_mut() {
+                    barcode.alt_text = Some(format!(
+                        "{} {}",
+                        leg.flight_code(),
+                        self.confirmation_number
+                    ));
+                }
+                Ok(pass)
+            })
+            .collect()
+    }
+
+    /// Semantic tags of the pass of the leg
+    fn semantics(&self, leg: &FlightLeg) -> SemanticTags {
+        SemanticTags {
+            airline_code: Some(leg.airline_code.clone()),
+            flight_code: Some(leg.flight_code()),
+            flight_number: Some(leg.flight_number),
+            departure_airport_code: Some(leg.departure.code.clone()),
+            departure_airport_name: leg.departure.name.clone(),
+            departure_terminal: leg.departure.terminal.clone(),
+            departure_gate: leg.gate.clone(),
+            destination_airport_code: Some(leg.destination.code.clone()),
+            destination_airport_name: leg.destination.name.clone(),
+            destination_terminal: leg.destination.terminal.clone(),
+            original_boarding_date: Some(leg.boarding_time),
+            original_departure_date: Some(leg.departure_time),
+            original_arrival_date: Some(leg.arrival_time),
+            duration: u32::try_from((leg.arrival_time - leg.departure_time).num_seconds()).ok(),
+            passenger_name: Some(self.passenger.name_semantics()),
+            seats: leg.seat.iter().map(|seat| seat_semantics(seat)).collect(),
+            boarding_group: leg.boarding_group.clone(),
+            boarding_sequence_number: leg.boarding_sequence_number.clone(),
+            confirmation_number: Some(self.confirmation_number.clone()),
+            membership_program_number: self.frequent_flyer_number.clone(),
+            priority_status: self.priority_status.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Airline boarding pass layout: gate in header, airports as primary fields,
+    /// passenger, flight and departure as secondary, boarding time, group and seat as auxiliary.
+    fn fields(&self, leg: &FlightLeg) -> fields::Type {
+        let mut fields = fields::Type::BoardingPass {
+            pass_fields: Default::default(),
+            transit_type: TransitType::Air,
+        };
+        if let Some(gate) = &leg.gate {
+            fields = fields.add_header_field(Content::new(
+                "gate",
+                gate.as_str(),
+                ContentOptions {
+                    label: Some(String::from("GATE")),
+                    change_message: Some(String::from("Gate changed to %@")),
+                    ..Default::default()
+                },
+            ));
+        }
+        let airport = |key: &str, airport: &Airport| {
+            let label = airport.name.clone().unwrap_or_else(|| airport.code.clone());
+            labeled(key, &label, &airport.code)
+        };
+        fields = fields
+            .add_primary_field(airport("origin", &leg.departure))
+            .add_primary_field(airport("destination", &leg.destination))
+            .add_secondary_field(labeled(
+                "passenger",
+                "PASSENGER",
+                &self.passenger.display_name(),
+            ))
+            .add_secondary_field(labeled("flight", "FLIGHT", &leg.flight_code()))
+            .add_secondary_field(time("departs", "DEPARTS", leg.departure_time))
+            .add_auxiliary_field(time("boarding", "BOARDING", leg.boarding_time));
+        if let Some(group) = &leg.boarding_group {
+            fields = fields.add_auxiliary_field(labeled("group", "GROUP", group));
+        }
+        if let Some(seat) = &leg.seat {
+            fields = fields.add_auxiliary_field(labeled("seat", "SEAT", seat));
+        }
+
+        fields = fields.add_back_field(labeled(
+            "confirmation",
+            "Confirmation number",
+            &self.confirmation_number,
+        ));
+        if let Some(number) = &self.frequent_flyer_number {
+            fields =
+                fields.add_back_field(labeled("frequent-flyer", "Frequent flyer number", number));
+        }
+        fields
+    }
+}
+
+/// Time of day in the time zone of the airport
+fn time(key: &str, label: &str, date: DateTime<FixedOffset>) -> Content {
+    let mut field = Content::date(key, date, DateStyle::None);
+    field.options.label = Some(String::from(label));
+    field.options.time_style = Some(DateStyle::Short);
+    field.options.ignores_time_zone = Some(true);
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::barcode::bcbp;
+    use crate::fields::{FieldValue, Section};
+    use crate::pass::make_config;
+
+    fn make_itinerary() -> Itinerary {
+        let montreal = FixedOffset::west_opt(5 * 3600).unwrap();
+        let frankfurt = FixedOffset::east_opt(3600).unwrap();
+        let first = FlightLeg {
+            gate: Some(String::from("52")),
+            seat: Some(String::from("1A")),
+            boarding_group: Some(String::from("1")),
+            boarding_sequence_number: Some(String::from("25")),
+            ..FlightLeg::new(
+                "AC",
+                834,
+                Airport {
+                    code: String::from("YUL"),
+                    name: Some(String::from("Montréal")),
+                    terminal: None,
+                },
+                Airport::new("FRA"),
+                montreal.with_ymd_and_hms(2024, 11, 21, 18, 30, 0).unwrap(),
+                frankfurt.with_ymd_and_hms(2024, 11, 22, 7, 45, 0).unwrap(),
+            )
+        };
+        let second = FlightLeg {
+            seat: Some(String::from("12C")),
+            compartment: 'J',
+            ..FlightLeg::new(
+                "LH",
+                3664,
+                Airport::new("FRA"),
+                Airport::new("GVA"),
+                frankfurt.with_ymd_and_hms(2024, 11, 22, 9, 50, 0).unwrap(),
+                frankfurt.with_ymd_and_hms(2024, 11, 22, 11, 0, 0).unwrap(),
+            )
+        };
+
+        Itinerary::new("ABC123", Passenger::new("Luc", "Desmarais"))
+            .frequent_flyer_number(String::from("1234567890"))
+            .add_leg(first)
+            .add_leg(second)
+    }
+
+    #[test]
+    fn pass_per_leg() {
+        let itinerary = make_itinerary();
+        let passes = itinerary.passes(&make_config()).unwrap();
+        println!("{}", passes[0].make_json().unwrap());
+
+        assert_eq!(2, passes.len());
+        for (pass, leg) in passes.iter().zip(&itinerary.legs) {
+            assert!(pass.validate().is_ok());
+            assert_eq!(Some("ABC123"), pass.grouping_identifier.as_deref());
+            assert!(matches!(
+                pass.fields,
+                fields::Type::BoardingPass {
+                    transit_type: TransitType::Air,
+                    ..
+                }
+            ));
+            assert_eq!(
+                vec![RelevantDate::interval(
+                    leg.boarding_time - Duration::hours(3),
+                    leg.boarding_time + Duration::hours(1)
+                )],
+                pass.relevant_dates
+            );
+            assert!(pass.barcodes.is_empty());
+        }
+        assert_eq!("ABCDEFG1234567890-2", passes[1].config.serial_number);
+
+        let first = &passes[0];
+        assert_eq!(Some(Section::Header), first.fields.section_of("gate"));
+        assert_eq!(
+            Some(&FieldValue::Text(String::from("YUL"))),
+            first.fields.find("origin").map(|field| &field.value)
+        );
+        assert_eq!(
+            Some("Montréal"),
+            first
+                .fields
+                .find("origin")
+                .unwrap()
+                .options
+                .label
+                .as_deref()
+        );
+        assert_eq!(Some(Section::Auxiliary), first.fields.section_of("seat"));
+        // Second leg has no gate and boarding group yet
+        assert_eq!(None, passes[1].fields.section_of("gate"));
+        assert_eq!(None, passes[1].fields.section_of("group"));
+
+        let semantics = &first.semantics;
+        assert_eq!(Some("AC834"), semantics.flight_code.as_deref());
+        assert_eq!(Some("52"), semantics.departure_gate.as_deref());
+        assert_eq!(Some("1"), semantics.boarding_group.as_deref());
+        assert_eq!(Some(7 * 3600 + 15 * 60), semantics.duration);
+        assert_eq!(Some("A"), semantics.seats[0].seat_number.as_deref());
+        assert_eq!(
+            Some("1234567890"),
+            semantics.membership_program_number.as_deref()
+        );
+    }
+
+    #[test]
+    fn bcbp_barcodes() {
+        let itinerary = make_itinerary()
+            .grouping_identifier(String::from("trip-42"))
+            .bcbp_barcode(BarcodeFormat::Aztec);
+        let passes = itinerary.passes(&make_config()).unwrap();
+
+        let barcode = &passes[1].barcodes[0];
+        println!("{}", barcode.message);
+        assert_eq!(BarcodeFormat::Aztec, barcode.format);
+        assert_eq!(Some("trip-42"), passes[1].grouping_identifier.as_deref());
+
+        let bcbp = bcbp::BoardingPass::decode(&barcode.message).unwrap();
+        assert_eq!("DESMARAIS/LUC", bcbp.passenger_name);
+        // Semantics agree with the barcode
+        assert_eq!(
+            Some("DESMARAIS"),
+            passes[1]
+                .semantics
+                .passenger_name
+                .as_ref()
+                .unwrap()
+                .family_name
+                .as_deref()
+        );
+        assert_eq!(Some("LH3664 ABC123"), barcode.alt_text.as_deref());
+        let leg = &bcbp.legs[0];
+        assert_eq!(("FRA", "GVA"), (&*leg.from_airport, &*leg.to_airport));
+        assert_eq!("3664", leg.flight_number);
+        assert_eq!(327, leg.date_of_flight);
+        assert_eq!("12C", leg.seat);
+        assert_eq!('J', leg.compartment);
+        assert_eq!(
+            Some("1234567890"),
+            leg.conditional
+                .as_ref()
+                .map(|conditional| &*conditional.frequent_flyer_number)
+        );
+
+        // Economy unless set
+        let first = bcbp::BoardingPass::decode(&passes[0].barcodes[0].message).unwrap();
+        assert_eq!('Y', first.legs[0].compartment);
+
+        // BCBP can't carry non-ASCII names
+        let mut itinerary = itinerary;
+        itinerary.passenger.family_name = String::from("Müller");
+        assert!(itinerary.passes(&make_config()).is_err());
+    }
+}
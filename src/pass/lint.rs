@@ -0,0 +1,546 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::error::ValueError;
+
+use super::Pass;
+use super::fields::Section;
+use super::identifiers::PassTypeId;
+use super::visual_appearance::CONTRAST_AA;
+
+/// Default maximum number of characters in [logo_text](Pass::logo_text)
+pub const MAX_LOGO_TEXT_CHARS: usize = 20;
+
+/// Quality rule checked by [Linter]
+///
+/// Rule ids are kebab-case, such as `low-contrast`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// Foreground or label color has low contrast with background color
+    LowContrast,
+    /// Logo text doesn't fit next to the logo
+    LongLogoText,
+    /// Expiration date is earlier than relevant date
+    ExpiresBeforeRelevant,
+    /// Description is empty, VoiceOver has nothing to read
+    EmptyDescription,
+    /// Description repeats organization name, VoiceOver reads it twice
+    DescriptionIsOrganization,
+    /// Back field has no label
+    UnlabeledBackField,
+    /// Barcode has no alternative text for when it can't be scanned
+    BarcodeWithoutAltText,
+    /// Code128 barcode has no fallback, Apple Watch doesn't display Code128
+    Code128WithoutWatchFallback,
+}
+
+impl Rule {
+    /// All rules.
+    pub const ALL: [Rule; 8] = [
+        Rule::LowContrast,
+        Rule::LongLogoText,
+        Rule::ExpiresBeforeRelevant,
+        Rule::EmptyDescription,
+        Rule::DescriptionIsOrganization,
+        Rule::UnlabeledBackField,
+        Rule::BarcodeWithoutAltText,
+        Rule::Code128WithoutWatchFallback,
+    ];
+
+    /// Id of the rule.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::LowContrast => "low-contrast",
+            Rule::LongLogoText => "long-logo-text",
+            Rule::ExpiresBeforeRelevant => "expires-before-relevant",
+            Rule::EmptyDescription => "empty-description",
+            Rule::DescriptionIsOrganization => "description-is-organization",
+            Rule::UnlabeledBackField => "unlabeled-back-field",
+            Rule::BarcodeWithoutAltText => "barcode-without-alt-text",
+            Rule::Code128WithoutWatchFallback => "code128-without-watch-fallback",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = ValueError;
+
+    /// Parse `Rule` from its id
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rule::ALL
+            .into_iter()
+            .find(|rule| rule.id() == s)
+            .ok_or_else(|| ValueError::LintRule(String::from(s)))
+    }
+}
+
+/// Severity of [Rule]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// Rule is not checked
+    Allow,
+    /// Findings are warnings
+    Warn,
+    /// Findings are errors
+    Deny,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Allow => "allow",
+            Level::Warn => "warning",
+            Level::Deny => "error",
+        })
+    }
+}
+
+/// Finding of [Linter]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    /// Rule that found the problem
+    pub rule: Rule,
+
+    /// Configured level of the rule
+    pub level: Level,
+
+    /// Key of the field or index of the barcode, if the problem is in one of them
+    pub location: Option<String>,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.level, self.rule, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " (at `{}`)", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lint engine checking passes against quality [rules](Rule)
+///
+/// All rules are [warnings](Level::Warn) by default. Rules can be allowed for a single pass,
+/// see [allow_for](Linter::allow_for).
+///
+/// ```
+/// use neopasses::{PassBuilder, PassConfig};
+/// use neopasses::lint::{Level, Linter, Rule};
+///
+/// let pass = PassBuilder::new(PassConfig {
+///     organization_name: String::from("Apple inc."),
+///     description: String::from("Apple inc."),
+///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+///     team_identifier: "AA00AA0A0A".parse().unwrap(),
+///     serial_number: String::from("ABCDEFG1234567890"),
+/// })
+/// .logo_text(String::from("Apple Worldwide Developers Conference"))
+/// .build();
+///
+/// let linter = Linter::new().level(Rule::LongLogoText, Level::Deny);
+/// let lints = linter.check(&pass);
+/// assert_eq!(
+///     vec![Rule::LongLogoText, Rule::DescriptionIsOrganization],
+///     lints.iter().map(|lint| lint.rule).collect::<Vec<_>>()
+/// );
+/// assert_eq!(Level::Deny, lints[0].level);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Linter {
+    levels: HashMap<Rule, Level>,
+    /// Allowed rules by pass type identifier and serial number of the pass
+    allowed: HashMap<(PassTypeId, String), Vec<Rule>>,
+    min_contrast: f64,
+    max_logo_text_chars: usize,
+}
+
+impl Default for Linter {
+    /// Creates `Linter` with all rules as warnings.
+    fn default() -> Self {
+        Self {
+            levels: HashMap::new(),
+            allowed: HashMap::new(),
+            min_contrast: CONTRAST_AA,
+            max_logo_text_chars: MAX_LOGO_TEXT_CHARS,
+        }
+    }
+}
+
+impl Linter {
+    /// Creates `Linter` with all rules as warnings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set level of rule.
+    pub fn level(mut self, rule: Rule, level: Level) -> Self {
+        self.levels.insert(rule, level);
+        self
+    }
+
+    /// Don't check rule for the pass with pass type identifier and serial number.
+    pub fn allow_for(
+        mut self,
+        pass_type_identifier: &PassTypeId,
+        serial_number: &str,
+        rule: Rule,
+    ) -> Self {
+        self.allowed
+            .entry((pass_type_identifier.clone(), String::from(serial_number)))
+            .or_default()
+            .push(rule);
+        self
+    }
+
+    /// Set minimum contrast ratio for [LowContrast](Rule::LowContrast), [CONTRAST_AA] by default.
+    pub fn min_contrast(mut self, ratio: f64) -> Self {
+        self.min_contrast = ratio;
+        self
+    }
+
+    /// Set maximum length for [LongLogoText](Rule::LongLogoText), [MAX_LOGO_TEXT_CHARS] by default.
+    pub fn max_logo_text_chars(mut self, chars: usize) -> Self {
+        self.max_logo_text_chars = chars;
+        self
+    }
+
+    /// Level of rule.
+    pub fn level_of(&self, rule: Rule) -> Level {
+        self.levels.get(&rule).copied().unwrap_or(Level::Warn)
+    }
+
+    /// Check pass, findings are in order of [Rule::ALL].
+    pub fn check(&self, pass: &Pass) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let allowed = self
+            .allowed
+            .get(&(
+                pass.config.pass_type_identifier.clone(),
+                pass.config.serial_number.clone(),
+            ))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for rule in Rule::ALL {
+            let level = self.level_of(rule);
+            if level == Level::Allow || allowed.contains(&rule) {
+                continue;
+            }
+            for (location, message) in self.check_rule(rule, pass) {
+                lints.push(Lint {
+                    rule,
+                    level,
+                    location,
+                    message,
+                });
+            }
+        }
+        lints
+    }
+
+    /// Locations and messages of findings of rule
+    fn check_rule(&self, rule: Rule, pass: &Pass) -> Vec<(Option<String>, String)> {
+        match rule {
+            Rule::LowContrast => {
+                let Some(appearance) = &pass.appearance else {
+                    return Vec::new();
+                };
+                [
+                    ("foreground", appearance.foreground_contrast()),
+                    ("label", appearance.label_contrast()),
+                ]
+                .into_iter()
+                .filter_map(|(name, ratio)| {
+                    let ratio = ratio.filter(|ratio| *ratio < self.min_contrast)?;
+                    Some((
+                        None,
+                        format!(
+                            "{} color has contrast {:.2}:1 with background color, minimum is {:.2}:1",
+                            name, ratio, self.min_contrast
+                        ),
+                    ))
+                })
+                .collect()
+            }
+            Rule::LongLogoText => pass
+                .logo_text
+                .as_ref()
+                .map(|text| text.chars().count())
+                .filter(|chars| *chars > self.max_logo_text_chars)
+                .map(|chars| {
+                    (
+                        None,
+                        format!(
+                            "logo text has {} characters, maximum is {}",
+                            chars, self.max_logo_text_chars
+                        ),
+                    )
+                })
+                .into_iter()
+                .collect(),
+            Rule::ExpiresBeforeRelevant => {
+                let Some(expiration) = pass.expiration_date else {
+                    return Vec::new();
+                };
+                let relevant = pass
+                    .relevant_date
+                    .into_iter()
+                    .chain(pass.relevant_dates.iter().filter_map(|date| date.start()))
+                    .max();
+                match relevant {
+                    Some(relevant) if expiration < relevant => vec![(
+                        None,
+                        format!(
+                            "expiration date {} is earlier than relevant date {}",
+                            expiration.to_rfc3339(),
+                            relevant.to_rfc3339()
+                        ),
+                    )],
+                    _ => Vec::new(),
+                }
+            }
+            Rule::EmptyDescription => {
+                if pass.config.description.trim().is_empty() {
+                    vec![(None, String::from("description is empty"))]
+                } else {
+                    Vec::new()
+                }
+            }
+            Rule::DescriptionIsOrganization => {
+                let description = pass.config.description.trim();
+                if !description.is_empty()
+                    && description.eq_ignore_ascii_case(pass.config.organization_name.trim())
+                {
+                    vec![(
+                        None,
+                        String::from("description is the same as organization name"),
+                    )]
+                } else {
+                    Vec::new()
+                }
+            }
+            Rule::UnlabeledBackField => pass
+                .fields
+                .fields()
+                .section(Section::Back)
+                .iter()
+                .filter(|field| {
+                    field
+                        .options
+                        .label
+                        .as_ref()
+                        .is_none_or(|label| label.trim().is_empty())
+                })
+                .map(|field| {
+                    (
+                        Some(field.key.clone()),
+                        String::from("back field has no label"),
+                    )
+                })
+                .collect(),
+            Rule::BarcodeWithoutAltText => pass
+                .barcodes
+                .iter()
+                .enumerate()
+                .filter(|(_, barcode)| {
+                    barcode
+                        .alt_text
+                        .as_ref()
+                        .is_none_or(|text| text.trim().is_empty())
+                })
+                .map(|(index, _)| {
+                    (
+                        Some(format!("barcodes[{}]", index)),
+                        String::from("barcode has no alternative text"),
+                    )
+                })
+                .collect(),
+            Rule::Code128WithoutWatchFallback => {
+                let watch_compatible = pass
+                    .barcodes
+                    .iter()
+                    .any(|barcode| barcode.format.is_watch_compatible());
+                match pass.barcodes.first() {
+                    Some(barcode) if !watch_compatible => vec![(
+                        Some(String::from("barcodes[0]")),
+                        format!(
+                            "{:?} barcode is not displayed on watchOS, add a QR, Aztec or PDF417 barcode",
+                            barcode.format
+                        ),
+                    )],
+                    _ => Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+impl Pass {
+    /// Check pass with default [Linter].
+    pub fn lint(&self) -> Vec<Lint> {
+        Linter::new().check(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::pass::barcode::{Barcode, BarcodeFormat};
+    use crate::pass::visual_appearance::{Color, VisualAppearance};
+    use crate::pass::{PassBuilder, fields, make_config};
+
+    fn make_builder() -> PassBuilder {
+        PassBuilder::new(make_config())
+    }
+
+    #[test]
+    fn clean_pass() {
+        let pass = make_builder()
+            .logo_text(String::from("Apple"))
+            .appearance(VisualAppearance {
+                label_color: Color::white(),
+                foreground_color: Color::white(),
+                background_color: Color::black(),
+            })
+            .add_barcode(Barcode {
+                message: String::from("ABCDEFG1234567890"),
+                format: BarcodeFormat::QR,
+                alt_text: Some(String::from("ABCDEFG1234567890")),
+                ..Default::default()
+            })
+            .build();
+        assert!(pass.lint().is_empty());
+    }
+
+    #[test]
+    fn all_rules() {
+        let mut pass = make_builder()
+            .logo_text(String::from("Apple Worldwide Developers Conference"))
+            .appearance(VisualAppearance {
+                label_color: Color::from_hex("#ffcc00"),
+                foreground_color: Color::white(),
+                background_color: Color::from_hex("#008fd4"),
+            })
+            .relevant_date(Utc.with_ymd_and_hms(2024, 2, 8, 0, 0, 0).unwrap())
+            .expiration_date(Utc.with_ymd_and_hms(2024, 2, 7, 0, 0, 0).unwrap())
+            .fields(
+                fields::Type::Generic {
+                    pass_fields: fields::Fields::default(),
+                }
+                .add_back_field(fields::Content::new(
+                    "terms",
+                    "No refunds",
+                    Default::default(),
+                )),
+            )
+            .add_barcode(Barcode {
+                message: String::from("ABCDEFG1234567890"),
+                format: BarcodeFormat::QR,
+                ..Default::default()
+            })
+            .build();
+        pass.config.description = String::from(" ");
+
+        let lints = Linter::new()
+            .level(Rule::BarcodeWithoutAltText, Level::Deny)
+            .check(&pass);
+        for lint in &lints {
+            println!("{}", lint);
+        }
+        assert_eq!(
+            vec![
+                Rule::LowContrast,
+                Rule::LowContrast,
+                Rule::LongLogoText,
+                Rule::ExpiresBeforeRelevant,
+                Rule::EmptyDescription,
+                Rule::UnlabeledBackField,
+                Rule::BarcodeWithoutAltText,
+            ],
+            lints.iter().map(|lint| lint.rule).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(String::from("terms")), lints[5].location);
+        assert_eq!(
+            "error[barcode-without-alt-text]: barcode has no alternative text (at `barcodes[0]`)",
+            lints[6].to_string()
+        );
+    }
+
+    #[test]
+    fn suppressed_rules() {
+        let pass = make_builder()
+            .add_barcode(Barcode::default())
+            .logo_text(String::from("Apple Worldwide Developers Conference"))
+            .build();
+
+        let pass_type = &pass.config.pass_type_identifier;
+        let linter =
+            Linter::new().allow_for(pass_type, "ABCDEFG1234567890", Rule::BarcodeWithoutAltText);
+        let lints = linter.check(&pass);
+        assert_eq!(1, lints.len());
+        assert_eq!(Rule::LongLogoText, lints[0].rule);
+
+        let linter = linter.level(Rule::LongLogoText, Level::Allow);
+        assert!(linter.check(&pass).is_empty());
+        assert!(
+            Linter::new()
+                .allow_for(pass_type, "ABCDEFG1234567890", Rule::BarcodeWithoutAltText)
+                .max_logo_text_chars(40)
+                .check(&pass)
+                .is_empty()
+        );
+
+        // Other passes are still checked
+        let mut other = pass.clone();
+        other.config.serial_number = String::from("HIJKLMN1234567890");
+        assert_eq!(Rule::BarcodeWithoutAltText, linter.check(&other)[0].rule);
+        // Serial numbers are unique only within a pass type
+        let mut other = pass.clone();
+        other.config.pass_type_identifier = "pass.com.example.other".parse().unwrap();
+        assert_eq!(Rule::BarcodeWithoutAltText, linter.check(&other)[0].rule);
+    }
+
+    #[test]
+    fn code128_watch_fallback() {
+        let code128 = Barcode {
+            message: String::from("1234567890"),
+            format: BarcodeFormat::Code128,
+            alt_text: Some(String::from("1234567890")),
+            ..Default::default()
+        };
+        let pass = make_builder().add_barcode(code128.clone()).build();
+        let lints = pass.lint();
+        println!("{}", lints[0]);
+        assert_eq!(
+            vec![Rule::Code128WithoutWatchFallback],
+            lints.iter().map(|lint| lint.rule).collect::<Vec<_>>()
+        );
+
+        let pass = make_builder()
+            .add_barcode(code128)
+            .watch_fallback_barcode(BarcodeFormat::QR)
+            .build();
+        assert_eq!(BarcodeFormat::QR, pass.barcodes[1].format);
+        assert!(pass.lint().is_empty());
+    }
+
+    #[test]
+    fn rule_ids() {
+        for rule in Rule::ALL {
+            assert_eq!(Ok(rule), rule.id().parse());
+        }
+        assert_eq!(
+            Err(ValueError::LintRule(String::from("unknown"))),
+            "unknown".parse::<Rule>()
+        );
+    }
+}
@@ -1,14 +1,17 @@
+use std::str::FromStr;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ValueError;
+
 /// Represents a location that the system uses to show a relevant pass.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Location {
-    /// (Required) The latitude, in degrees, of the location.
-    pub latitude: f64,
-
-    /// (Required) The longitude, in degrees, of the location.
-    pub longitude: f64,
+    /// (Required) The latitude and longitude, in degrees, of the location.
+    #[serde(flatten)]
+    pub coordinates: LatLon,
 
     /// The altitude, in meters, of the location.
     #[serde(default)]
@@ -27,14 +30,92 @@ impl Default for Location {
     /// Creates an empty `Location`.
     fn default() -> Self {
         Self {
-            latitude: 0.0,
-            longitude: 0.0,
+            coordinates: LatLon::default(),
             altitude: None,
             relevant_text: None,
         }
     }
 }
 
+/// Geographic coordinates, in degrees.
+///
+/// Latitude is in range -90..=90, longitude is in range -180..=180.
+///
+/// ```
+/// use neopasses::location::LatLon;
+///
+/// let apple_park = LatLon::new(37.334606, -122.009102).unwrap();
+/// assert_eq!(37.334606, apple_park.latitude());
+///
+/// assert!(LatLon::new(91.0, 0.0).is_err());
+/// assert_eq!(apple_park, "37.334606,-122.009102".parse().unwrap());
+/// ```
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(try_from = "RawLatLon")]
+pub struct LatLon {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl LatLon {
+    /// Creates `LatLon`, checking ranges of coordinates.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, ValueError> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(ValueError::Latitude(latitude));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(ValueError::Longitude(longitude));
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+
+    /// The latitude, in degrees.
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    /// The longitude, in degrees.
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+}
+
+impl FromStr for LatLon {
+    type Err = ValueError;
+
+    /// Parse `LatLon` from `latitude,longitude` pair
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ValueError::Coordinates(String::from(s));
+        let (latitude, longitude) = s.split_once(',').ok_or_else(err)?;
+        let latitude = latitude.trim().parse::<f64>().map_err(|_| err())?;
+        let longitude = longitude.trim().parse::<f64>().map_err(|_| err())?;
+        Self::new(latitude, longitude)
+    }
+}
+
+/// Unchecked coordinates for deserialization
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "LatLon")]
+struct RawLatLon {
+    /// (Required) The latitude, in degrees, of the location.
+    #[schemars(range(min = -90.0, max = 90.0))]
+    latitude: f64,
+    /// (Required) The longitude, in degrees, of the location.
+    #[schemars(range(min = -180.0, max = 180.0))]
+    longitude: f64,
+}
+
+impl TryFrom<RawLatLon> for LatLon {
+    type Error = ValueError;
+
+    fn try_from(raw: RawLatLon) -> Result<Self, Self::Error> {
+        Self::new(raw.latitude, raw.longitude)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,8 +124,7 @@ mod tests {
     fn make_location() {
         // Serialization test
         let location = Location {
-            latitude: 37.334606,
-            longitude: -122.009102,
+            coordinates: LatLon::new(37.334606, -122.009102).unwrap(),
             relevant_text: Some(String::from("Apple Park, Cupertino, CA, USA")),
             ..Default::default()
         };
@@ -66,4 +146,17 @@ mod tests {
         let json = serde_json::to_string_pretty(&location).unwrap();
         assert_eq!(json_expected, json);
     }
+
+    #[test]
+    fn location_out_of_range() {
+        assert_eq!(Err(ValueError::Latitude(-90.5)), LatLon::new(-90.5, 0.0));
+        assert_eq!(Err(ValueError::Longitude(180.5)), LatLon::new(0.0, 180.5));
+
+        let json = r#"{
+  "latitude": 37.334606,
+  "longitude": -222.009102
+}"#;
+        let err = serde_json::from_str::<Location>(json).unwrap_err();
+        assert!(err.to_string().contains("longitude must be in range"));
+    }
 }
@@ -0,0 +1,293 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::Pass;
+use super::barcode::{Barcode, BarcodeFormat};
+
+/// Options of [Pass::make_json_with]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonOptions {
+    /// Write deprecated `barcode` and `relevantDate` keys alongside `barcodes` and
+    /// `relevantDates`, for iOS versions that don't read the new keys.
+    pub legacy_keys: bool,
+}
+
+/// Upgrade of deprecated pass.json structure, see [Pass::from_json_migrated]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Migration {
+    /// Deprecated `barcode` was moved to `barcodes`
+    Barcode,
+
+    /// Deprecated `relevantDate` was moved to `relevantDates`
+    RelevantDate,
+
+    /// Deprecated key was removed, because its replacement is present
+    LegacyKeyDropped(&'static str),
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Migration::Barcode => write!(f, "moved `barcode` to `barcodes`"),
+            Migration::RelevantDate => write!(f, "moved `relevantDate` to `relevantDates`"),
+            Migration::LegacyKeyDropped(key) => {
+                write!(f, "removed `{}`, its replacement is present", key)
+            }
+        }
+    }
+}
+
+impl Pass {
+    /// Build pass (pass.json) from json data, upgrading deprecated keys.
+    ///
+    /// Returns the pass and every migration performed, in order.
+    /// Set [legacy_keys](JsonOptions::legacy_keys) to write the deprecated keys back for older iOS versions.
+    ///
+    /// ```
+    /// use neopasses::Pass;
+    /// use neopasses::migration::Migration;
+    ///
+    /// let json = r#"{
+    ///   "formatVersion": 1,
+    ///   "organizationName": "Apple inc.",
+    ///   "description": "Example pass",
+    ///   "passTypeIdentifier": "pass.com.example",
+    ///   "teamIdentifier": "AA00AA0A0A",
+    ///   "serialNumber": "ABCDEFG1234567890",
+    ///   "relevantDate": "2024-02-07T19:00:00+09:00",
+    ///   "barcode": {
+    ///     "message": "ABCDEFG1234567890",
+    ///     "format": "PKBarcodeFormatQR",
+    ///     "messageEncoding": "iso-8859-1"
+    ///   },
+    ///   "generic": {
+    ///     "auxiliaryFields": [],
+    ///     "backFields": [],
+    ///     "headerFields": [],
+    ///     "primaryFields": [],
+    ///     "secondaryFields": []
+    ///   }
+    /// }"#;
+    ///
+    /// let (pass, migrations) = Pass::from_json_migrated(json).unwrap();
+    /// assert_eq!(vec![Migration::Barcode, Migration::RelevantDate], migrations);
+    /// assert_eq!(1, pass.barcodes.len());
+    /// assert_eq!(None, pass.relevant_date);
+    /// assert_eq!(1, pass.relevant_dates.len());
+    /// ```
+    pub fn from_json_migrated(data: &str) -> Result<(Self, Vec<Migration>), serde_json::Error> {
+        let mut json: Value = serde_json::from_str(data)?;
+        let migrations = migrate(&mut json);
+        // Typed errors keep their position when the data is unchanged
+        let pass = if migrations.is_empty() {
+            serde_json::from_str(data)?
+        } else {
+            serde_json::from_value(json)?
+        };
+        Ok((pass, migrations))
+    }
+}
+
+/// Upgrades deprecated keys of pass.json
+fn migrate(json: &mut Value) -> Vec<Migration> {
+    let mut migrations = Vec::new();
+    migrations.extend(migrate_barcode(json));
+    let Value::Object(object) = json else {
+        return migrations;
+    };
+
+    if let Some(date) = object.remove("relevantDate") {
+        if object.contains_key("relevantDates") {
+            migrations.push(Migration::LegacyKeyDropped("relevantDate"));
+        } else {
+            let entry = Map::from_iter([(String::from("date"), date)]);
+            object.insert(
+                String::from("relevantDates"),
+                Value::Array(vec![Value::Object(entry)]),
+            );
+            migrations.push(Migration::RelevantDate);
+        }
+    }
+
+    migrations
+}
+
+/// Moves deprecated `barcode` to `barcodes`, or drops it if `barcodes` is present
+///
+/// [Pass] has no field for `barcode`, so it would be lost otherwise.
+pub(crate) fn migrate_barcode(json: &mut Value) -> Option<Migration> {
+    let object = json.as_object_mut()?;
+    let barcode = object.remove("barcode")?;
+    if object.contains_key("barcodes") {
+        return Some(Migration::LegacyKeyDropped("barcode"));
+    }
+    object.insert(String::from("barcodes"), Value::Array(vec![barcode]));
+    Some(Migration::Barcode)
+}
+
+/// Pass with deprecated keys derived from their replacements
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WithLegacyKeys<'a> {
+    #[serde(flatten)]
+    pass: &'a Pass,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    barcode: Option<&'a Barcode>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relevant_date: Option<String>,
+}
+
+impl<'a> WithLegacyKeys<'a> {
+    pub(crate) fn new(pass: &'a Pass) -> Self {
+        // Older iOS versions don't support Code128 barcodes
+        let barcode = pass
+            .barcodes
+            .iter()
+            .find(|barcode| barcode.format != BarcodeFormat::Code128);
+        let relevant_date = match pass.relevant_date {
+            Some(_) => None,
+            None => pass
+                .relevant_dates
+                .first()
+                .and_then(|date| date.start())
+                .map(|date| date.to_rfc3339()),
+        };
+        Self {
+            pass,
+            barcode,
+            relevant_date,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+
+    use super::*;
+    use crate::pass::relevant_date::RelevantDate;
+    use crate::pass::{PassBuilder, make_config};
+
+    fn make_pass() -> Pass {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        PassBuilder::new(make_config())
+            .add_barcode(Barcode {
+                message: String::from("ABCDEFG1234567890"),
+                format: BarcodeFormat::Code128,
+                ..Default::default()
+            })
+            .add_barcode(Barcode {
+                message: String::from("ABCDEFG1234567890"),
+                format: BarcodeFormat::QR,
+                ..Default::default()
+            })
+            .add_relevant_date(RelevantDate::interval(
+                tz.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap(),
+                tz.with_ymd_and_hms(2024, 2, 7, 22, 0, 0).unwrap(),
+            ))
+            .build()
+    }
+
+    #[test]
+    fn no_migrations() {
+        let pass = make_pass();
+        let json = pass.make_json().unwrap();
+        assert!(!json.contains("\"barcode\""));
+        assert!(!json.contains("\"relevantDate\""));
+
+        let (pass_read, migrations) = Pass::from_json_migrated(&json).unwrap();
+        assert!(migrations.is_empty());
+        assert_eq!(json, pass_read.make_json().unwrap());
+    }
+
+    #[test]
+    fn from_json_keeps_legacy_keys() {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let pass = PassBuilder::new(make_pass().config)
+            .relevant_date(tz.with_ymd_and_hms(2024, 2, 7, 19, 0, 0).unwrap())
+            .build();
+        let json = pass.make_json().unwrap();
+
+        // Plain read keeps `relevantDate`, it round-trips
+        let pass_read = Pass::from_json(&json).unwrap();
+        assert_eq!(pass.relevant_date, pass_read.relevant_date);
+        assert!(pass_read.relevant_dates.is_empty());
+        assert_eq!(json, pass_read.make_json().unwrap());
+
+        let (pass_read, migrations) = Pass::from_json_migrated(&json).unwrap();
+        assert_eq!(vec![Migration::RelevantDate], migrations);
+        assert_eq!(None, pass_read.relevant_date);
+        assert_eq!(pass.relevant_date, pass_read.relevant_dates[0].date);
+    }
+
+    #[test]
+    fn from_json_upgrades_barcode() {
+        let json = r#"{
+  "formatVersion": 1,
+  "organizationName": "Apple inc.",
+  "description": "Example pass",
+  "passTypeIdentifier": "pass.com.example",
+  "teamIdentifier": "AA00AA0A0A",
+  "serialNumber": "ABCDEFG1234567890",
+  "barcode": {
+    "message": "ABCDEFG1234567890",
+    "format": "PKBarcodeFormatPDF417",
+    "messageEncoding": "iso-8859-1"
+  },
+  "generic": {
+    "auxiliaryFields": [],
+    "backFields": [],
+    "headerFields": [],
+    "primaryFields": [],
+    "secondaryFields": []
+  }
+}"#;
+        // Pass has no `barcode` field, it is moved instead of being dropped
+        let pass = Pass::from_json(json).unwrap();
+        assert_eq!(1, pass.barcodes.len());
+        assert_eq!(BarcodeFormat::PDF417, pass.barcodes[0].format);
+        assert_eq!("ABCDEFG1234567890", pass.barcodes[0].message);
+    }
+
+    #[test]
+    fn emit_legacy_keys() {
+        let pass = make_pass();
+        let options = JsonOptions { legacy_keys: true };
+        let json = pass.make_json_with(&options).unwrap();
+        println!("{}", json);
+
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!("PKBarcodeFormatQR", value["barcode"]["format"]);
+        assert_eq!("2024-02-07T19:00:00+09:00", value["relevantDate"]);
+        assert_eq!(2, value["barcodes"].as_array().unwrap().len());
+
+        // Legacy keys next to their replacements are dropped on read
+        let (pass_read, migrations) = Pass::from_json_migrated(&json).unwrap();
+        assert_eq!(
+            vec![
+                Migration::LegacyKeyDropped("barcode"),
+                Migration::LegacyKeyDropped("relevantDate"),
+            ],
+            migrations
+        );
+        assert_eq!(pass.barcodes, pass_read.barcodes);
+        assert_eq!(pass.relevant_dates, pass_read.relevant_dates);
+        assert_eq!(None, pass_read.relevant_date);
+    }
+
+    #[test]
+    fn invalid_relevant_date() {
+        let mut pass = make_pass();
+        pass.relevant_dates.push(RelevantDate::default());
+        let err = pass.validate().unwrap_err();
+        println!("{}", err);
+        assert_eq!(
+            vec![crate::Violation::InvalidRelevantDate { index: 1 }],
+            err.violations
+        );
+    }
+}
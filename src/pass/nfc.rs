@@ -1,9 +1,10 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Represents the near-field communication (NFC) payload the device passes to an Apple Pay terminal.
 ///
 /// Adding NFC to a Pass requires a special entitlement issued by Apple. For more information, see Near Field Communication in [Getting Started](https://developer.apple.com/wallet/get-started/) with Apple Wallet.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct NFC {
     /// (Required) The public encryption key the Value Added Services protocol uses.
@@ -0,0 +1,342 @@
+use serde_json::Value;
+
+use crate::error::PatchError;
+
+use super::Pass;
+use super::fields::{Content, FieldValue, Section};
+
+/// Field whose value changed after a patch
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Key of the field
+    pub key: String,
+
+    /// Section of the field after the patch, or before it if the field was removed
+    pub section: Section,
+
+    /// Value before the patch, `None` if the field was added
+    pub old: Option<FieldValue>,
+
+    /// Value after the patch, `None` if the field was removed
+    pub new: Option<FieldValue>,
+
+    /// [change_message](crate::fields::ContentOptions::change_message) of the field after the patch
+    pub change_message: Option<String>,
+}
+
+impl FieldChange {
+    /// Text of the notification shown by Wallet: `change_message` with `%@` replaced by the new value.
+    ///
+    /// `None` if the field has no change message or was removed.
+    pub fn notification(&self) -> Option<String> {
+        let message = self.change_message.as_ref()?;
+        let value = self.new.as_ref()?;
+        Some(message.replace("%@", &value.to_string()))
+    }
+}
+
+/// Changes made by [Pass::apply_patch] and [Pass::apply_merge_patch]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PatchReport {
+    /// Fields that were added, removed, or whose value changed
+    pub fields: Vec<FieldChange>,
+}
+
+impl PatchReport {
+    /// Keys of changed fields.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|change| change.key.as_str())
+    }
+
+    /// Checks that no field changed.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl Pass {
+    /// Apply JSON Patch (RFC 6902) to pass.json.
+    ///
+    /// The patched pass.json must be a pass without new violations (see [Pass::validate]),
+    /// otherwise the pass is not changed. Violations the pass already has don't prevent patching.
+    ///
+    /// ```
+    /// use neopasses::{Pass, PassBuilder, PassConfig};
+    /// use neopasses::fields::{Content, ContentOptions, Fields, Type};
+    ///
+    /// let mut pass = PassBuilder::new(PassConfig {
+    ///     organization_name: "Apple inc.".into(),
+    ///     description: "Boarding pass".into(),
+    ///     pass_type_identifier: "pass.com.example".parse().unwrap(),
+    ///     team_identifier: "AA00AA0A0A".parse().unwrap(),
+    ///     serial_number: "ABCDEFG1234567890".into(),
+    /// })
+    /// .fields(Type::Generic { pass_fields: Fields::default() }.add_header_field(Content::new(
+    ///     "gate",
+    ///     "A1",
+    ///     ContentOptions {
+    ///         change_message: String::from("Gate changed to %@").into(),
+    ///         ..Default::default()
+    ///     },
+    /// )))
+    /// .build();
+    ///
+    /// let patch = serde_json::json!([
+    ///     { "op": "replace", "path": "/generic/headerFields/0/value", "value": "B7" }
+    /// ]);
+    /// let report = pass.apply_patch(&patch).unwrap();
+    ///
+    /// assert_eq!(vec!["gate"], report.keys().collect::<Vec<_>>());
+    /// assert_eq!(Some(String::from("Gate changed to B7")), report.fields[0].notification());
+    /// ```
+    pub fn apply_patch(&mut self, patch: &Value) -> Result<PatchReport, PatchError> {
+        let patch: json_patch::Patch =
+            serde_json::from_value(patch.clone()).map_err(PatchError::Format)?;
+        self.apply_to_json(|json| json_patch::patch(json, &patch).map_err(PatchError::from))
+    }
+
+    /// Apply JSON Merge Patch (RFC 7396) to pass.json.
+    ///
+    /// Lists, such as fields of a section, are replaced as a whole.
+    /// The patched pass.json must be a pass without new violations, see [Pass::apply_patch].
+    pub fn apply_merge_patch(&mut self, patch: &Value) -> Result<PatchReport, PatchError> {
+        self.apply_to_json(|json| {
+            json_patch::merge(json, patch);
+            Ok(())
+        })
+    }
+
+    /// Applies change to serialized pass, and replaces pass with the checked result
+    fn apply_to_json(
+        &mut self,
+        change: impl FnOnce(&mut Value) -> Result<(), PatchError>,
+    ) -> Result<PatchReport, PatchError> {
+        let mut json = serde_json::to_value(&*self).map_err(PatchError::Serialize)?;
+        change(&mut json)?;
+
+        let patched: Pass = serde_json::from_value(json).map_err(PatchError::Invalid)?;
+        if let Err(mut err) = patched.validate() {
+            // Only violations introduced by the patch are rejected
+            let existing = self
+                .validate()
+                .err()
+                .map(|err| err.violations)
+                .unwrap_or_default();
+            err.violations
+                .retain(|violation| !existing.contains(violation));
+            if !err.violations.is_empty() {
+                return Err(err.into());
+            }
+        }
+
+        let report = PatchReport {
+            fields: changed_fields(self, &patched),
+        };
+        *self = patched;
+        Ok(report)
+    }
+}
+
+/// Compares values of fields by key
+fn changed_fields(old: &Pass, new: &Pass) -> Vec<FieldChange> {
+    let mut changes: Vec<FieldChange> = new
+        .fields
+        .iter()
+        .filter_map(|(section, field)| {
+            let previous = old.fields.find(&field.key);
+            if previous.is_some_and(|previous| same_value(previous, field)) {
+                return None;
+            }
+            Some(FieldChange {
+                key: field.key.clone(),
+                section,
+                old: previous.map(|previous| previous.value.clone()),
+                new: Some(field.value.clone()),
+                change_message: field.options.change_message.clone(),
+            })
+        })
+        .collect();
+
+    let removed = old
+        .fields
+        .iter()
+        .filter(|(_, field)| new.fields.find(&field.key).is_none())
+        .map(|(section, field)| FieldChange {
+            key: field.key.clone(),
+            section,
+            old: Some(field.value.clone()),
+            new: None,
+            change_message: None,
+        });
+    changes.extend(removed);
+    changes
+}
+
+/// Checks that field displays the same value
+fn same_value(a: &Content, b: &Content) -> bool {
+    a.value == b.value && a.options.attributed_value == b.options.attributed_value
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::pass::{PassBuilder, fields, make_config};
+
+    fn make_pass() -> Pass {
+        PassBuilder::new(make_config())
+            .fields(
+                fields::Type::BoardingPass {
+                    pass_fields: fields::Fields::default(),
+                    transit_type: fields::TransitType::Air,
+                }
+                .add_header_field(fields::Content::new(
+                    "gate",
+                    "A1",
+                    fields::ContentOptions {
+                        change_message: String::from("Gate changed to %@").into(),
+                        ..Default::default()
+                    },
+                ))
+                .add_auxiliary_field(fields::Content::new("seat", "32C", Default::default()))
+                .add_auxiliary_field(fields::Content::new(
+                    "group",
+                    "4",
+                    Default::default(),
+                )),
+            )
+            .build()
+    }
+
+    #[test]
+    fn json_patch() {
+        let mut pass = make_pass();
+        let patch = json!([
+            { "op": "test", "path": "/boardingPass/headerFields/0/key", "value": "gate" },
+            { "op": "replace", "path": "/boardingPass/headerFields/0/value", "value": "B7" },
+            { "op": "replace", "path": "/boardingPass/auxiliaryFields/0/value", "value": "1A" },
+            { "op": "remove", "path": "/boardingPass/auxiliaryFields/1" },
+            { "op": "add", "path": "/boardingPass/auxiliaryFields/-", "value": { "key": "class", "value": "Business" } },
+            { "op": "replace", "path": "/description", "value": "Upgraded boarding pass" }
+        ]);
+        let report = pass.apply_patch(&patch).unwrap();
+
+        assert_eq!(
+            vec!["gate", "seat", "class", "group"],
+            report.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            FieldChange {
+                key: String::from("gate"),
+                section: Section::Header,
+                old: Some(FieldValue::from("A1")),
+                new: Some(FieldValue::from("B7")),
+                change_message: Some(String::from("Gate changed to %@")),
+            },
+            report.fields[0]
+        );
+        assert_eq!(None, report.fields[2].old);
+        assert_eq!(None, report.fields[3].new);
+        assert_eq!("Upgraded boarding pass", pass.config.description);
+        assert_eq!(
+            Some(&FieldValue::from("1A")),
+            pass.fields.find("seat").map(|f| &f.value)
+        );
+    }
+
+    #[test]
+    fn merge_patch() {
+        let mut pass = make_pass();
+        let patch = json!({
+            "description": "Delayed boarding pass",
+            "semantics": { "departureGate": "B7" },
+            "boardingPass": {
+                "headerFields": [
+                    { "key": "gate", "value": "B7", "changeMessage": "Gate changed to %@" }
+                ]
+            }
+        });
+        let report = pass.apply_merge_patch(&patch).unwrap();
+
+        // Lists are replaced, auxiliary fields are kept
+        assert_eq!(vec!["gate"], report.keys().collect::<Vec<_>>());
+        assert_eq!(
+            Some(String::from("Gate changed to B7")),
+            report.fields[0].notification()
+        );
+        assert_eq!(2, pass.fields.fields().auxiliary_fields.len());
+
+        // Unchanged value
+        let report = pass.apply_merge_patch(&patch).unwrap();
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn invalid_patch_keeps_pass() {
+        let mut pass = make_pass();
+        let expected = pass.make_json().unwrap();
+
+        // Failed test operation
+        let patch = json!([
+            { "op": "replace", "path": "/description", "value": "Changed" },
+            { "op": "test", "path": "/serialNumber", "value": "other" }
+        ]);
+        let err = pass.apply_patch(&patch).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, PatchError::Patch(_)));
+
+        // Result is not a pass
+        let err = pass
+            .apply_merge_patch(&json!({ "teamIdentifier": "AA00" }))
+            .unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, PatchError::Invalid(_)));
+
+        // Result violates constraints
+        let patch = json!([
+            { "op": "copy", "from": "/boardingPass/auxiliaryFields/0", "path": "/boardingPass/backFields/-" }
+        ]);
+        let err = pass.apply_patch(&patch).unwrap_err();
+        println!("{}", err);
+        assert!(matches!(err, PatchError::Violations(_)));
+
+        assert_eq!(expected, pass.make_json().unwrap());
+    }
+
+    #[test]
+    fn patch_pass_with_violations() {
+        let mut pass = make_pass();
+        // Text alignment is not allowed in back fields
+        pass.fields = pass.fields.add_back_field(fields::Content::new(
+            "terms",
+            "No refunds",
+            fields::ContentOptions {
+                text_alignment: Some(fields::TextAlignment::Right),
+                ..Default::default()
+            },
+        ));
+        assert!(pass.validate().is_err());
+
+        // Unrelated change
+        let patch = json!([
+            { "op": "replace", "path": "/boardingPass/headerFields/0/value", "value": "B7" }
+        ]);
+        let report = pass.apply_patch(&patch).unwrap();
+        assert_eq!(vec!["gate"], report.keys().collect::<Vec<_>>());
+
+        // New violations are still rejected
+        let patch = json!([
+            { "op": "copy", "from": "/boardingPass/auxiliaryFields/0", "path": "/boardingPass/backFields/-" }
+        ]);
+        let err = pass.apply_patch(&patch).unwrap_err();
+        println!("{}", err);
+        let PatchError::Violations(err) = err else {
+            panic!("expected violations, got {err}");
+        };
+        assert_eq!(
+            vec![crate::Violation::DuplicateFieldKey(String::from("seat"))],
+            err.violations
+        );
+    }
+}
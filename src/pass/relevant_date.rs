@@ -0,0 +1,67 @@
+use chrono::{DateTime, FixedOffset, TimeZone};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::date_format;
+
+/// Date or date interval when the pass is relevant (iOS 18 and later).
+///
+/// An entry has either [date](RelevantDate::date), or both [start_date](RelevantDate::start_date)
+/// and [end_date](RelevantDate::end_date).
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelevantDate {
+    /// The date and time when the pass becomes relevant.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "date_format")]
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub date: Option<DateTime<FixedOffset>>,
+
+    /// The date and time of the start of the interval when the pass is relevant.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "date_format")]
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub start_date: Option<DateTime<FixedOffset>>,
+
+    /// The date and time of the end of the interval when the pass is relevant.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "date_format")]
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub end_date: Option<DateTime<FixedOffset>>,
+}
+
+impl RelevantDate {
+    /// Creates `RelevantDate` for a single date.
+    pub fn at<Tz: TimeZone>(date: DateTime<Tz>) -> Self {
+        Self {
+            date: Some(date.fixed_offset()),
+            ..Default::default()
+        }
+    }
+
+    /// Creates `RelevantDate` for an interval.
+    pub fn interval<Tz: TimeZone>(start: DateTime<Tz>, end: DateTime<Tz>) -> Self {
+        Self {
+            start_date: Some(start.fixed_offset()),
+            end_date: Some(end.fixed_offset()),
+            ..Default::default()
+        }
+    }
+
+    /// Date of the entry, or start of the interval.
+    pub fn start(&self) -> Option<DateTime<FixedOffset>> {
+        self.date.or(self.start_date)
+    }
+
+    /// Checks that entry has a date, or a complete interval with start not after end.
+    pub fn is_valid(&self) -> bool {
+        match (self.date, self.start_date, self.end_date) {
+            (Some(_), None, None) => true,
+            (None, Some(start), Some(end)) => start <= end,
+            _ => false,
+        }
+    }
+}
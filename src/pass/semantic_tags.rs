@@ -1,11 +1,14 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset};
 use is_empty::IsEmpty;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::location::LatLon;
+
 /// Machine-readable metadata the system uses to offer a pass and suggest related actions.
 ///
 /// <https://developer.apple.com/documentation/walletpasses/semantictags>
-#[derive(Serialize, Deserialize, Debug, IsEmpty)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, IsEmpty)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTags {
     /// The IATA airline code, such as “EX” for flightCode “EX123”. Use this key only for airline boarding passes.
@@ -72,7 +75,8 @@ pub struct SemanticTags {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub current_arrival_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub current_arrival_date: Option<DateTime<FixedOffset>>,
 
     /// The updated date and time of boarding, if different from the originally scheduled date and time.
     ///
@@ -80,7 +84,8 @@ pub struct SemanticTags {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub current_boarding_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub current_boarding_date: Option<DateTime<FixedOffset>>,
 
     /// The updated departure date and time, if different from the originally scheduled date and time.
     ///
@@ -88,7 +93,8 @@ pub struct SemanticTags {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub current_departure_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub current_departure_date: Option<DateTime<FixedOffset>>,
 
     /// The IATA airport code for the departure airport, such as “MPM” or “LHR”.
     ///
@@ -225,7 +231,8 @@ pub struct SemanticTags {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub event_end_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub event_end_date: Option<DateTime<FixedOffset>>,
 
     /// The full name of the event, such as the title of a movie.
     ///
@@ -240,7 +247,8 @@ pub struct SemanticTags {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub event_start_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub event_start_date: Option<DateTime<FixedOffset>>,
 
     /// The type of event. Use this key for any type of event ticket.
     #[serde(default)]
@@ -303,19 +311,22 @@ pub struct SemanticTags {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub original_arrival_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub original_arrival_date: Option<DateTime<FixedOffset>>,
 
     /// The originally scheduled date and time of boarding. Use this key for any type of boarding pass.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub original_boarding_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub original_boarding_date: Option<DateTime<FixedOffset>>,
 
     /// The originally scheduled date and time of departure. Use this key for any type of boarding pass.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
-    pub original_departure_date: Option<DateTime<Utc>>,
+    #[schemars(with = "Option<DateTime<FixedOffset>>")]
+    pub original_departure_date: Option<DateTime<FixedOffset>>,
 
     /// An object that represents the name of the passenger. Use this key for any type of boarding pass.
     #[serde(default)]
@@ -456,7 +467,7 @@ pub struct SemanticTags {
 }
 
 /// Represents an amount of money and type of currency.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTagCurrencyAmount {
     /// The amount of money.
@@ -481,7 +492,7 @@ impl Default for SemanticTagCurrencyAmount {
 }
 
 /// Represents the coordinates of a location.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTagLocation {
     /// (Required) The latitude, in degrees.
@@ -491,8 +502,17 @@ pub struct SemanticTagLocation {
     pub longitude: f64,
 }
 
+impl From<LatLon> for SemanticTagLocation {
+    fn from(coordinates: LatLon) -> Self {
+        Self {
+            latitude: coordinates.latitude(),
+            longitude: coordinates.longitude(),
+        }
+    }
+}
+
 /// Represents the parts of a person’s name.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTagPersonNameComponents {
     /// The person’s family name or last name.
@@ -547,7 +567,7 @@ impl Default for SemanticTagPersonNameComponents {
 }
 
 /// Represents the identification of a seat for a transit journey or an event.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTagSeat {
     /// A description of the seat, such as “A flat bed seat”.
@@ -596,7 +616,7 @@ impl Default for SemanticTagSeat {
 }
 
 /// Contains information required to connect to a WiFi network.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTagWifiNetwork {
     /// (Required) The password for the WiFi network.
@@ -607,7 +627,7 @@ pub struct SemanticTagWifiNetwork {
 }
 
 /// The type of event.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub enum SemanticEventType {
     #[serde(rename = "PKEventTypeGeneric")]
     Generic,
@@ -730,9 +750,9 @@ mod tests {
             boarding_sequence_number: String::from("123").into(),
             car_number: String::from("01").into(),
             confirmation_number: String::from("1234").into(),
-            current_arrival_date: Utc.with_ymd_and_hms(2024, 02, 10, 0, 0, 0).unwrap().into(),
-            current_boarding_date: Utc.with_ymd_and_hms(2024, 02, 08, 0, 0, 0).unwrap().into(),
-            current_departure_date: Utc.with_ymd_and_hms(2024, 02, 09, 0, 0, 0).unwrap().into(),
+            current_arrival_date: Utc.with_ymd_and_hms(2024, 02, 10, 0, 0, 0).unwrap().fixed_offset().into(),
+            current_boarding_date: Utc.with_ymd_and_hms(2024, 02, 08, 0, 0, 0).unwrap().fixed_offset().into(),
+            current_departure_date: Utc.with_ymd_and_hms(2024, 02, 09, 0, 0, 0).unwrap().fixed_offset().into(),
             departure_airport_code: String::from("VVO").into(),
             departure_airport_name: String::from("Vladivostok International Airport").into(),
             departure_gate: String::from("8").into(),
@@ -761,9 +781,9 @@ mod tests {
             destination_station_name: String::from("2st Street Station").into(),
             destination_terminal: String::from("B").into(),
             duration: Some(12345),
-            event_end_date: Utc.with_ymd_and_hms(2024, 02, 10, 0, 0, 0).unwrap().into(),
+            event_end_date: Utc.with_ymd_and_hms(2024, 02, 10, 0, 0, 0).unwrap().fixed_offset().into(),
             event_name: String::from("Super cool movie").into(),
-            event_start_date: Utc.with_ymd_and_hms(2024, 02, 10, 8, 0, 0).unwrap().into(),
+            event_start_date: Utc.with_ymd_and_hms(2024, 02, 10, 8, 0, 0).unwrap().fixed_offset().into(),
             event_type: SemanticEventType::Generic.into(),
             flight_code: String::from("EX123").into(),
             passenger_name: SemanticTagPersonNameComponents {
@@ -0,0 +1,556 @@
+//! Rail and bus tickets.
+//!
+//! [TransitTicket] fills the boarding pass layout of a train or bus ticket, with stations
+//! as primary fields and platform, car and seat as auxiliary fields, and the transit semantic tags.
+//! A ticket is either for a fixed departure, or open and valid for any departure in a window.
+//!
+//! ```
+//! use chrono::prelude::*;
+//! use neopasses::PassConfig;
+//! use neopasses::transit::{Station, TransitTicket, Validity};
+//!
+//! let paris = FixedOffset::east_opt(3600).unwrap();
+//! let pass = TransitTicket::train(
+//!     Station::new("Paris Gare de Lyon"),
+//!     Station::new("Lyon Part-Dieu"),
+//!     Validity::Open {
+//!         from: paris.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+//!         until: paris.with_ymd_and_hms(2024, 3, 1, 23, 59, 0).unwrap(),
+//!     },
+//! )
+//! .transit_provider(String::from("SNCF"))
+//! .open_return(
+//!     paris.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap(),
+//!     paris.with_ymd_and_hms(2024, 3, 31, 23, 59, 0).unwrap(),
+//! )
+//! .pass(PassConfig {
+//!     organization_name: String::from("SNCF"),
+//!     description: String::from("Train ticket"),
+//!     pass_type_identifier: "pass.com.example".parse().unwrap(),
+//!     team_identifier: "AA00AA0A0A".parse().unwrap(),
+//!     serial_number: String::from("ABCDEFG1234567890"),
+//! })
+//! .unwrap();
+//!
+//! assert_eq!(
+//!     Some("Paris Gare de Lyon"),
+//!     pass.semantics.departure_station_name.as_deref()
+//! );
+//! // Outbound and return windows
+//! assert_eq!(2, pass.relevant_dates.len());
+//! // Expires at the end of the return window
+//! assert_eq!(
+//!     Some(paris.with_ymd_and_hms(2024, 3, 31, 23, 59, 0).unwrap()),
+//!     pass.expiration_date
+//! );
+//! ```
+
+use chrono::{DateTime, Duration, FixedOffset};
+
+use crate::BuildError;
+
+use super::fields::{self, Content, DateStyle, TransitType};
+use super::relevant_date::RelevantDate;
+use super::semantic_tags::SemanticTags;
+use super::travel::{Passenger, labeled, seat_semantics};
+use super::{Pass, PassBuilder, PassConfig};
+
+/// Departure or destination station of [TransitTicket]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Station {
+    pub name: String,
+    pub platform: Option<String>,
+}
+
+/// Departures a [TransitTicket] is valid for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validity {
+    /// Fixed departure, with arrival if known
+    Departure {
+        departure: DateTime<FixedOffset>,
+        arrival: Option<DateTime<FixedOffset>>,
+    },
+    /// Any departure in the window
+    Open {
+        from: DateTime<FixedOffset>,
+        until: DateTime<FixedOffset>,
+    },
+}
+
+/// Train or bus ticket
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitTicket {
+    /// [Train](TransitType::Train) or [Bus](TransitType::Bus)
+    pub transit_type: TransitType,
+    pub origin: Station,
+    pub destination: Station,
+    pub validity: Validity,
+
+    /// Validity of the return journey, for return tickets
+    pub return_validity: Option<Validity>,
+
+    /// Name of the operator, such as `SNCF`
+    pub transit_provider: Option<String>,
+
+    /// Train or bus number, such as `TGV 6601`
+    pub vehicle_number: Option<String>,
+
+    /// Car (coach) number of the seat
+    pub car_number: Option<String>,
+    pub seat: Option<String>,
+    pub passenger: Option<Passenger>,
+    pub confirmation_number: Option<String>,
+
+    /// Fixed departure is relevant from this long before departure
+    pub relevant_before_departure: Duration,
+}
+
+impl Station {
+    /// Creates `Station`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            platform: None,
+        }
+    }
+}
+
+impl Validity {
+    /// Start of the validity, departure or start of the window
+    pub fn start(&self) -> DateTime<FixedOffset> {
+        match self {
+            Validity::Departure { departure, .. } => *departure,
+            Validity::Open { from, .. } => *from,
+        }
+    }
+
+    /// End of the validity: arrival (or an hour after departure) for fixed departures,
+    /// end of the window for open tickets
+    pub fn end(&self) -> DateTime<FixedOffset> {
+        match self {
+            Validity::Departure { departure, arrival } => {
+                arrival.unwrap_or(*departure + Duration::hours(1))
+            }
+            Validity::Open { until, .. } => *until,
+        }
+    }
+
+    /// Relevance of the validity: window of open tickets, or from `before` departure until
+    /// its [end](Validity::end) for fixed departures
+    fn relevant_date(&self, before: Duration) -> RelevantDate {
+        match self {
+            Validity::Departure { departure, .. } => {
+                RelevantDate::interval(*departure - before, self.end())
+            }
+            Validity::Open { from, until } => RelevantDate::interval(*from, *until),
+        }
+    }
+}
+
+impl TransitTicket {
+    /// Creates train ticket, relevant from an hour before a fixed departure.
+    pub fn train(origin: Station, destination: Station, validity: Validity) -> Self {
+        Self {
+            transit_type: TransitType::Train,
+            origin,
+            destination,
+            validity,
+            return_validity: None,
+            transit_provider: None,
+            vehicle_number: None,
+            car_number: None,
+            seat: None,
+            passenger: None,
+            confirmation_number: None,
+            relevant_before_departure: Duration::hours(1),
+        }
+    }
+
+    /// Creates bus ticket, relevant from an hour before a fixed departure.
+    pub fn bus(origin: Station, destination: Station, validity: Validity) -> Self {
+        Self {
+            transit_type: TransitType::Bus,
+            ..Self::train(origin, destination, validity)
+        }
+    }
+
+    /// Adding [return_validity](TransitTicket::return_validity)
+    pub fn return_journey(mut self, validity: Validity) -> Self {
+        self.return_validity = Some(validity);
+        self
+    }
+
+    /// Adding open [return_validity](TransitTicket::return_validity), valid for any departure
+    /// from `from` until `until`
+    pub fn open_return(self, from: DateTime<FixedOffset>, until: DateTime<FixedOffset>) -> Self {
+        self.return_journey(Validity::Open { from, until })
+    }
+
+    /// Adding [transit_provider](TransitTicket::transit_provider)
+    pub fn transit_provider(mut self, field: String) -> Self {
+        self.transit_provider = Some(field);
+        self
+    }
+
+    /// Adding [vehicle_number](TransitTicket::vehicle_number)
+    pub fn vehicle_number(mut self, field: String) -> Self {
+        self.vehicle_number = Some(field);
+        self
+    }
+
+    /// Adding [car_number](TransitTicket::car_number)
+    pub fn car_number(mut self, field: String) -> Self {
+        self.car_number = Some(field);
+        self
+    }
+
+    /// Adding [seat](TransitTicket::seat)
+    pub fn seat(mut self, field: String) -> Self {
+        self.seat = Some(field);
+        self
+    }
+
+    /// Adding [passenger](TransitTicket::passenger)
+    pub fn passenger(mut self, field: Passenger) -> Self {
+        self.passenger = Some(field);
+        self
+    }
+
+    /// Adding [confirmation_number](TransitTicket::confirmation_number)
+    pub fn confirmation_number(mut self, field: String) -> Self {
+        self.confirmation_number = Some(field);
+        self
+    }
+
+    /// Pass of the ticket.
+    ///
+    /// The pass is relevant for the outbound and return journeys, and expires at the latest
+    /// [end](Validity::end) of them. Fails if the pass is invalid, such as for an open window
+    /// ending before it starts.
+    pub fn pass(&self, config: PassConfig) -> Result<Pass, BuildError> {
+        let mut builder = PassBuilder::new(config)
+            .fields(self.fields())
+            .semantics(self.semantics());
+        let validities = std::iter::once(&self.validity).chain(&self.return_validity);
+        for validity in validities.clone() {
+            builder =
+                builder.add_relevant_date(validity.relevant_date(self.relevant_before_departure));
+        }
+        if let Some(end) = validities.map(Validity::end).max() {
+            builder = builder.expiration_date(end);
+        }
+        builder.try_build()
+    }
+
+    /// Transit semantic tags of the ticket
+    fn semantics(&self) -> SemanticTags {
+        let mut semantics = SemanticTags {
+            transit_provider: self.transit_provider.clone(),
+            departure_station_name: Some(self.origin.name.clone()),
+            departure_platform: self.origin.platform.clone(),
+            destination_station_name: Some(self.destination.name.clone()),
+            destination_platform: self.destination.platform.clone(),
+            vehicle_number: self.vehicle_number.clone(),
+            car_number: self.car_number.clone(),
+            seats: self.seat.iter().map(|seat| seat_semantics(seat)).collect(),
+            passenger_name: self.passenger.as_ref().map(Passenger::name_semantics),
+            confirmation_number: self.confirmation_number.clone(),
+            ..Default::default()
+        };
+        // Open tickets have no scheduled departure
+        if let Validity::Departure { departure, arrival } = &self.validity {
+            semantics.original_departure_date = Some(*departure);
+            semantics.original_arrival_date = *arrival;
+            semantics.duration = arrival
+                .and_then(|arrival| u32::try_from((arrival - *departure).num_seconds()).ok());
+        }
+        semantics
+    }
+
+    /// Rail and bus layout: train or bus number in header, stations as primary fields,
+    /// journey dates and passenger as secondary, platform, car and seat as auxiliary.
+    fn fields(&self) -> fields::Type {
+        let mut fields = fields::Type::BoardingPass {
+            pass_fields: Default::default(),
+            transit_type: self.transit_type,
+        };
+        if let Some(number) = &self.vehicle_number {
+            let label = match self.transit_type {
+                TransitType::Bus => "BUS",
+                _ => "TRAIN",
+            };
+            fields = fields.add_header_field(labeled("vehicle", label, number));
+        }
+        fields = fields
+            .add_primary_field(labeled("origin", "FROM", &self.origin.name))
+            .add_primary_field(labeled("destination", "TO", &self.destination.name));
+
+        fields = match &self.validity {
+            Validity::Departure { departure, arrival } => {
+                let mut fields =
+                    fields.add_secondary_field(date_time("departs", "DEPARTS", *departure));
+                if let Some(arrival) = arrival {
+                    fields = fields.add_secondary_field(date_time("arrives", "ARRIVES", *arrival));
+                }
+                fields
+            }
+            Validity::Open { from, until } => fields
+                .add_secondary_field(date("valid-from", "VALID FROM", *from))
+                .add_secondary_field(date("valid-until", "VALID UNTIL", *until)),
+        };
+        if let Some(passenger) = &self.passenger {
+            fields = fields.add_secondary_field(labeled(
+                "passenger",
+                "PASSENGER",
+                &passenger.display_name(),
+            ));
+        }
+
+        if let Some(platform) = &self.origin.platform {
+            let mut field = labeled("platform", "PLATFORM", platform);
+            field.options.change_message = Some(String::from("Platform changed to %@"));
+            fields = fields.add_auxiliary_field(field);
+        }
+        if let Some(car) = &self.car_number {
+            let label = match self.transit_type {
+                TransitType::Train => "CAR",
+                _ => "COACH",
+            };
+            fields = fields.add_auxiliary_field(labeled("car", label, car));
+        }
+        if let Some(seat) = &self.seat {
+            fields = fields.add_auxiliary_field(labeled("seat", "SEAT", seat));
+        }
+
+        match &self.return_validity {
+            Some(Validity::Departure { departure, .. }) => {
+                fields = fields.add_back_field(date_time("return", "Return departure", *departure));
+            }
+            Some(Validity::Open { from, until }) => {
+                fields = fields
+                    .add_back_field(date("return-from", "Return valid from", *from))
+                    .add_back_field(date("return-until", "Return valid until", *until));
+            }
+            None => {}
+        }
+        if let Some(number) = &self.confirmation_number {
+            fields = fields.add_back_field(labeled("confirmation", "Confirmation number", number));
+        }
+        if let Some(provider) = &self.transit_provider {
+            fields = fields.add_back_field(labeled("provider", "Operator", provider));
+        }
+        fields
+    }
+}
+
+/// Date without time, such as first day of validity
+fn date(key: &str, label: &str, date: DateTime<FixedOffset>) -> Content {
+    let mut field = Content::date(key, date, DateStyle::Medium);
+    field.options.label = Some(String::from(label));
+    field.options.ignores_time_zone = Some(true);
+    field
+}
+
+/// Date and time in the time zone of the station
+fn date_time(key: &str, label: &str, date: DateTime<FixedOffset>) -> Content {
+    let mut field = Content::date(key, date, DateStyle::Short);
+    field.options.label = Some(String::from(label));
+    field.options.time_style = Some(DateStyle::Short);
+    field.options.ignores_time_zone = Some(true);
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::Violation;
+    use crate::fields::{FieldValue, Section};
+    use crate::pass::make_config;
+
+    fn zurich() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    #[test]
+    fn train_with_departure() {
+        let departure = zurich().with_ymd_and_hms(2024, 3, 1, 9, 2, 0).unwrap();
+        let arrival = zurich().with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+        let pass = TransitTicket::train(
+            Station {
+                name: String::from("Zürich HB"),
+                platform: Some(String::from("31")),
+            },
+            Station::new("Bern"),
+            Validity::Departure {
+                departure,
+                arrival: Some(arrival),
+            },
+        )
+        .transit_provider(String::from("SBB"))
+        .vehicle_number(String::from("IC 8"))
+        .car_number(String::from("7"))
+        .seat(String::from("45"))
+        .passenger(Passenger::new("Anna", "Keller"))
+        .pass(make_config())
+        .unwrap();
+        println!("{}", pass.make_json().unwrap());
+
+        assert!(pass.validate().is_ok());
+        assert!(matches!(
+            pass.fields,
+            fields::Type::BoardingPass {
+                transit_type: TransitType::Train,
+                ..
+            }
+        ));
+        assert_eq!(
+            Some(&FieldValue::Text(String::from("Zürich HB"))),
+            pass.fields.find("origin").map(|field| &field.value)
+        );
+        assert_eq!(
+            Some(Section::Primary),
+            pass.fields.section_of("destination")
+        );
+        for key in ["platform", "car", "seat"] {
+            assert_eq!(Some(Section::Auxiliary), pass.fields.section_of(key));
+        }
+        assert_eq!(Some(Section::Header), pass.fields.section_of("vehicle"));
+
+        let semantics = &pass.semantics;
+        assert_eq!(Some("SBB"), semantics.transit_provider.as_deref());
+        assert_eq!(
+            Some("Zürich HB"),
+            semantics.departure_station_name.as_deref()
+        );
+        assert_eq!(Some("Bern"), semantics.destination_station_name.as_deref());
+        assert_eq!(Some("31"), semantics.departure_platform.as_deref());
+        assert_eq!(Some("IC 8"), semantics.vehicle_number.as_deref());
+        assert_eq!(Some("7"), semantics.car_number.as_deref());
+        assert_eq!(Some("45"), semantics.seats[0].seat_identifier.as_deref());
+        assert_eq!(Some(departure), semantics.original_departure_date);
+        assert_eq!(Some(58 * 60), semantics.duration);
+
+        assert_eq!(
+            vec![RelevantDate::interval(
+                departure - Duration::hours(1),
+                arrival
+            )],
+            pass.relevant_dates
+        );
+        // Expires at arrival
+        assert_eq!(Some(arrival), pass.expiration_date);
+    }
+
+    #[test]
+    fn bus_with_open_return() {
+        let departure = zurich().with_ymd_and_hms(2024, 3, 1, 9, 2, 0).unwrap();
+        let from = zurich().with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap();
+        let until = zurich().with_ymd_and_hms(2024, 3, 31, 23, 59, 0).unwrap();
+        let pass = TransitTicket::bus(
+            Station::new("Zürich Sihlquai"),
+            Station::new("München ZOB"),
+            Validity::Departure {
+                departure,
+                arrival: None,
+            },
+        )
+        .car_number(String::from("2"))
+        .open_return(from, until)
+        .pass(make_config())
+        .unwrap();
+
+        assert!(pass.validate().is_ok());
+        assert!(matches!(
+            pass.fields,
+            fields::Type::BoardingPass {
+                transit_type: TransitType::Bus,
+                ..
+            }
+        ));
+        assert_eq!(
+            Some("COACH"),
+            pass.fields.find("car").unwrap().options.label.as_deref()
+        );
+        assert_eq!(Some(Section::Back), pass.fields.section_of("return-until"));
+        assert_eq!(
+            vec![
+                RelevantDate::interval(
+                    departure - Duration::hours(1),
+                    departure + Duration::hours(1)
+                ),
+                RelevantDate::interval(from, until),
+            ],
+            pass.relevant_dates
+        );
+        // Expires at the end of the return window
+        assert_eq!(Some(until), pass.expiration_date);
+        assert_eq!(None, pass.semantics.duration);
+    }
+
+    #[test]
+    fn open_ticket() {
+        let from = zurich().with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let until = zurich().with_ymd_and_hms(2024, 3, 1, 23, 59, 0).unwrap();
+        let pass = TransitTicket::train(
+            Station::new("Zürich HB"),
+            Station::new("Bern"),
+            Validity::Open { from, until },
+        )
+        .pass(make_config())
+        .unwrap();
+
+        assert_eq!(
+            Some(Section::Secondary),
+            pass.fields.section_of("valid-from")
+        );
+        assert_eq!(None, pass.fields.section_of("departs"));
+        assert_eq!(None, pass.semantics.original_departure_date);
+        assert_eq!(
+            vec![RelevantDate::interval(from, until)],
+            pass.relevant_dates
+        );
+        assert_eq!(Some(until), pass.expiration_date);
+    }
+
+    #[test]
+    fn expires_at_latest_validity() {
+        let from = zurich().with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let until = zurich().with_ymd_and_hms(2024, 3, 31, 23, 59, 0).unwrap();
+        let departure = zurich().with_ymd_and_hms(2024, 3, 10, 17, 2, 0).unwrap();
+        let pass = TransitTicket::train(
+            Station::new("Zürich HB"),
+            Station::new("Bern"),
+            Validity::Open { from, until },
+        )
+        .return_journey(Validity::Departure {
+            departure,
+            arrival: None,
+        })
+        .pass(make_config())
+        .unwrap();
+
+        // The return departure is within the outbound window
+        assert_eq!(Some(until), pass.expiration_date);
+    }
+
+    #[test]
+    fn open_window_ending_before_start() {
+        let from = zurich().with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap();
+        let until = zurich().with_ymd_and_hms(2024, 3, 1, 23, 59, 0).unwrap();
+        let result = TransitTicket::train(
+            Station::new("Zürich HB"),
+            Station::new("Bern"),
+            Validity::Open { from, until },
+        )
+        .pass(make_config());
+
+        let error = result.unwrap_err();
+        println!("{}", error);
+        assert_eq!(
+            vec![Violation::InvalidRelevantDate { index: 0 }],
+            error.violations
+        );
+    }
+}
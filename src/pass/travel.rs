@@ -0,0 +1,62 @@
+//! Travellers and fields shared by [itinerary](super::itinerary) and [transit](super::transit) passes.
+
+use super::barcode::bcbp;
+use super::fields::{Content, ContentOptions};
+use super::semantic_tags::{SemanticTagPersonNameComponents, SemanticTagSeat};
+
+/// Passenger of a flight [Itinerary](super::itinerary::Itinerary) or a
+/// [TransitTicket](super::transit::TransitTicket)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Passenger {
+    pub given_name: String,
+    pub family_name: String,
+}
+
+impl Passenger {
+    /// Creates `Passenger`.
+    pub fn new(given_name: &str, family_name: &str) -> Self {
+        Self {
+            given_name: String::from(given_name),
+            family_name: String::from(family_name),
+        }
+    }
+
+    /// Name as displayed on the pass, such as `Luc Desmarais`
+    pub fn display_name(&self) -> String {
+        format!("{} {}", self.given_name, self.family_name)
+            .trim()
+            .to_string()
+    }
+
+    /// Passenger name semantic tag
+    pub(super) fn name_semantics(&self) -> SemanticTagPersonNameComponents {
+        SemanticTagPersonNameComponents {
+            given_name: Some(self.given_name.clone()),
+            family_name: Some(self.family_name.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Seat with row and letter of identifier, such as `12` and `C` of `12C`
+pub(super) fn seat_semantics(seat: &str) -> SemanticTagSeat {
+    let (row, number) = bcbp::split_number(seat);
+    SemanticTagSeat {
+        seat_identifier: Some(String::from(seat)),
+        seat_row: (!row.is_empty()).then(|| String::from(row)),
+        seat_number: (!number.is_empty()).then(|| String::from(number)),
+        ..Default::default()
+    }
+}
+
+/// Text field with label
+pub(super) fn labeled(key: &str, label: &str, value: &str) -> Content {
+    Content::new(
+        key,
+        value,
+        ContentOptions {
+            label: Some(String::from(label)),
+            ..Default::default()
+        },
+    )
+}
@@ -1,8 +1,13 @@
+use std::{borrow::Cow, str::FromStr};
+
 use regex::Regex;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize, de};
 
+use crate::error::ValueError;
+
 /// Visual appearance of a pass
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct VisualAppearance {
     /// A color for the label text of the pass.
@@ -35,16 +40,31 @@ impl Default for VisualAppearance {
 }
 
 /// Represents color - specified as a CSS-style RGB triple
-#[derive(Debug)]
+///
+/// Deserialization accepts `rgb(r, g, b)` (with or without spaces, with numbers or percentages),
+/// `#RRGGBB`, `#RGB`, `hsl(h, s%, l%)` and CSS named colors.
+/// Serialization always uses `rgb(r, g, b)`.
+///
+/// ```
+/// use neopasses::visual_appearance::Color;
+///
+/// let color: Color = "#0A8FD4".parse().unwrap();
+/// assert_eq!((10, 143, 212), (color.r, color.g, color.b));
+/// assert_eq!("#0a8fd4", color.to_hex());
+///
+/// assert_eq!(color, "rgb(10,143,212)".parse().unwrap());
+/// assert_eq!(Color::from_hex("#fff"), "white".parse().ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color {
     /// Red
-    r: u8,
+    pub r: u8,
 
     /// Green
-    g: u8,
+    pub g: u8,
 
     /// Blue
-    b: u8,
+    pub b: u8,
 }
 
 impl Color {
@@ -66,6 +86,257 @@ impl Color {
             b: 255,
         })
     }
+
+    /// Creates `Color` from hex string `#RRGGBB` or `#RGB`, the leading `#` is optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        match hex.len() {
+            6 => {
+                let mut rgb = [0u8; 3];
+                hex::decode_to_slice(hex, &mut rgb).ok()?;
+                Some(Self {
+                    r: rgb[0],
+                    g: rgb[1],
+                    b: rgb[2],
+                })
+            }
+            3 => {
+                // Each digit is repeated: `#abc` is `#aabbcc`
+                let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).map(|d| d * 17);
+                Some(Self {
+                    r: digit(0).ok()?,
+                    g: digit(1).ok()?,
+                    b: digit(2).ok()?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Hex string of color, such as `#0a8fd4`.
+    pub fn to_hex(&self) -> String {
+        format!("#{}", hex::encode([self.r, self.g, self.b]))
+    }
+
+    /// Relative luminance, from 0 (black) to 1 (white).
+    ///
+    /// <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>
+    pub fn luminance(&self) -> f64 {
+        let channel = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * channel(self.r) + 0.7152 * channel(self.g) + 0.0722 * channel(self.b)
+    }
+
+    /// Contrast ratio with other color, from 1 (same luminance) to 21 (black and white).
+    ///
+    /// <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>
+    ///
+    /// ```
+    /// use neopasses::visual_appearance::Color;
+    ///
+    /// let black = Color::black().unwrap();
+    /// let white = Color::white().unwrap();
+    /// assert_eq!(21.0, black.contrast_ratio(&white));
+    /// ```
+    pub fn contrast_ratio(&self, other: &Color) -> f64 {
+        let (l1, l2) = (self.luminance(), other.luminance());
+        let (lighter, darker) = if l1 > l2 { (l1, l2) } else { (l2, l1) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    /// Creates `Color` from HSL: hue in degrees, saturation and lightness in range 0..=1.
+    fn from_hsl(h: f64, s: f64, l: f64) -> Self {
+        let h = h.rem_euclid(360.0) / 360.0;
+        let hue = |t: f64| {
+            let t = t.rem_euclid(1.0);
+            let q = if l < 0.5 {
+                l * (1.0 + s)
+            } else {
+                l + s - l * s
+            };
+            let p = 2.0 * l - q;
+            if t < 1.0 / 6.0 {
+                p + (q - p) * 6.0 * t
+            } else if t < 1.0 / 2.0 {
+                q
+            } else if t < 2.0 / 3.0 {
+                p + (q - p) * (2.0 / 3.0 - t) * 6.0
+            } else {
+                p
+            }
+        };
+        let channel = |t: f64| (hue(t) * 255.0).round() as u8;
+        Self {
+            r: channel(h + 1.0 / 3.0),
+            g: channel(h),
+            b: channel(h - 1.0 / 3.0),
+        }
+    }
+
+    /// Creates `Color` from CSS color name, such as `rebeccapurple`.
+    fn from_name(name: &str) -> Option<Self> {
+        let index = NAMED_COLORS.binary_search_by(|(n, _)| n.cmp(&name)).ok()?;
+        let [_, r, g, b] = NAMED_COLORS[index].1.to_be_bytes();
+        Some(Self { r, g, b })
+    }
+}
+
+/// Minimum contrast ratio for text (WCAG AA)
+pub const CONTRAST_AA: f64 = 4.5;
+
+/// Minimum contrast ratio for large text (WCAG AA)
+pub const CONTRAST_AA_LARGE: f64 = 3.0;
+
+/// Minimum contrast ratio for text (WCAG AAA)
+pub const CONTRAST_AAA: f64 = 7.0;
+
+impl VisualAppearance {
+    /// Contrast ratio between foreground and background colors.
+    ///
+    /// `None` if any of colors is not set.
+    pub fn foreground_contrast(&self) -> Option<f64> {
+        Some(
+            self.foreground_color
+                .as_ref()?
+                .contrast_ratio(self.background_color.as_ref()?),
+        )
+    }
+
+    /// Contrast ratio between label and background colors.
+    ///
+    /// `None` if any of colors is not set.
+    pub fn label_contrast(&self) -> Option<f64> {
+        Some(
+            self.label_color
+                .as_ref()?
+                .contrast_ratio(self.background_color.as_ref()?),
+        )
+    }
+
+    /// Contrast ratio between label and foreground colors.
+    ///
+    /// `None` if any of colors is not set.
+    pub fn label_foreground_contrast(&self) -> Option<f64> {
+        Some(
+            self.label_color
+                .as_ref()?
+                .contrast_ratio(self.foreground_color.as_ref()?),
+        )
+    }
+
+    /// Checks that foreground and label colors have at least `min_ratio` contrast with background.
+    ///
+    /// Colors that are not set are skipped.
+    ///
+    /// ```
+    /// use neopasses::visual_appearance::{CONTRAST_AA, Color, VisualAppearance};
+    ///
+    /// let appearance = VisualAppearance {
+    ///     label_color: Color::from_hex("#ffcc00"),
+    ///     foreground_color: Color::white(),
+    ///     background_color: Color::from_hex("#008fd4"),
+    /// };
+    /// assert!(!appearance.meets_contrast(CONTRAST_AA));
+    /// ```
+    pub fn meets_contrast(&self, min_ratio: f64) -> bool {
+        [self.foreground_contrast(), self.label_contrast()]
+            .into_iter()
+            .flatten()
+            .all(|ratio| ratio >= min_ratio)
+    }
+}
+
+impl FromStr for Color {
+    type Err = ValueError;
+
+    /// Parse `Color` from CSS color: `rgb()`, `hsl()`, hex or named color
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let str = s.trim().to_ascii_lowercase();
+        let format_err = || ValueError::ColorFormat(String::from(s));
+        let arguments_err = || ValueError::ColorArguments(String::from(s));
+
+        if str.starts_with('#') {
+            return Color::from_hex(&str).ok_or_else(format_err);
+        }
+
+        // Functional notation `rgb(...)` or `hsl(...)`
+        let re = Regex::new(r"^(?P<fn>rgb|hsl)\((?P<args>[^)]*)\)$").unwrap();
+        let Some(captures) = re.captures(&str) else {
+            return Color::from_name(&str).ok_or_else(format_err);
+        };
+        let args: Vec<&str> = captures["args"]
+            .split([',', ' '])
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        if args.len() != 3 {
+            return Err(format_err());
+        }
+
+        // Numeric argument, percentages are converted to fraction
+        let parse = |arg: &str| -> Result<(f64, bool), ValueError> {
+            let (number, percent) = match arg.strip_suffix('%') {
+                Some(number) => (number, true),
+                None => (arg, false),
+            };
+            let number = number.parse::<f64>().map_err(|_| format_err())?;
+            if !number.is_finite() {
+                return Err(format_err());
+            }
+            Ok((if percent { number / 100.0 } else { number }, percent))
+        };
+
+        if &captures["fn"] == "rgb" {
+            let mut rgb = [0u8; 3];
+            for (channel, arg) in rgb.iter_mut().zip(&args) {
+                let (value, percent) = parse(arg)?;
+                let value = if percent { value * 255.0 } else { value };
+                if !(0.0..=255.0).contains(&value) || (!percent && value.fract() != 0.0) {
+                    return Err(arguments_err());
+                }
+                *channel = value.round() as u8;
+            }
+            Ok(Self {
+                r: rgb[0],
+                g: rgb[1],
+                b: rgb[2],
+            })
+        } else {
+            let (h, _) = parse(args[0])?;
+            let (s, s_percent) = parse(args[1])?;
+            let (l, l_percent) = parse(args[2])?;
+            if !s_percent || !l_percent {
+                return Err(format_err());
+            }
+            if !(0.0..=1.0).contains(&s) || !(0.0..=1.0).contains(&l) {
+                return Err(arguments_err());
+            }
+            Ok(Color::from_hsl(h, s, l))
+        }
+    }
+}
+
+impl JsonSchema for Color {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("Color")
+    }
+
+    /// CSS color string, see [FromStr](Color::from_str)
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "CSS color: `rgb(r, g, b)`, `#RRGGBB`, `#RGB`, `hsl(h, s%, l%)` or color name",
+            "examples": ["rgb(0, 143, 212)"]
+        })
+    }
 }
 
 impl Serialize for Color {
@@ -80,33 +351,168 @@ impl Serialize for Color {
 }
 
 impl<'de> Deserialize<'de> for Color {
-    /// Deserialize `Color` from format `rgb(red, blue, green)`
+    /// Deserialize `Color` from CSS color, see [FromStr](Color::from_str)
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         let str = String::deserialize(deserializer)?;
-
-        // Parse triple `rgb(red, blue, green)`
-        let re = Regex::new(r"rgb\((?P<r>\d+),\s*(?P<g>\d+),\s*(?P<b>\d+)\)").unwrap();
-        let captures = re.captures(&str);
-
-        if let Some(captures) = captures {
-            if let (Ok(r), Ok(g), Ok(b)) = (
-                captures["r"].parse::<u8>(),
-                captures["g"].parse::<u8>(),
-                captures["b"].parse::<u8>(),
-            ) {
-                Ok(Self { r, g, b })
-            } else {
-                Err(de::Error::custom("Invalid color arguments"))
-            }
-        } else {
-            Err(de::Error::custom("Invalid color format"))
-        }
+        str.parse().map_err(de::Error::custom)
     }
 }
 
+/// CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +622,82 @@ mod tests {
 
         let _: Color = serde_json::from_str(json).unwrap();
     }
+
+    #[test]
+    fn color_parse_formats() {
+        let expected = Color::new(0, 143, 212).unwrap();
+        for str in [
+            "rgb(0, 143, 212)",
+            "rgb(0,143,212)",
+            "RGB( 0 143 212 )",
+            "#008FD4",
+            "hsl(199.5, 100%, 41.6%)",
+        ] {
+            let color: Color = str.parse().unwrap();
+            assert_eq!(expected, color, "{}", str);
+        }
+
+        let color: Color = "rgb(100%, 50%, 0%)".parse().unwrap();
+        assert_eq!(Color::new(255, 128, 0).unwrap(), color);
+
+        let color: Color = "#f0c".parse().unwrap();
+        assert_eq!(Color::new(255, 0, 204).unwrap(), color);
+
+        let color: Color = "RebeccaPurple".parse().unwrap();
+        assert_eq!(Color::new(102, 51, 153).unwrap(), color);
+
+        let color: Color = "hsl(120, 100%, 25%)".parse().unwrap();
+        assert_eq!(Color::new(0, 128, 0).unwrap(), color);
+
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("notacolor".parse::<Color>().is_err());
+        assert!("hsl(120, 100, 25)".parse::<Color>().is_err());
+        assert_eq!(
+            Err(ValueError::ColorArguments(String::from("rgb(0, 110%, 0)"))),
+            "rgb(0, 110%, 0)".parse::<Color>()
+        );
+    }
+
+    #[test]
+    fn color_deserialization_formats() {
+        let json = r##"{
+  "foregroundColor": "#ffffff",
+  "backgroundColor": "navy"
+}"##;
+        let appearance: VisualAppearance = serde_json::from_str(json).unwrap();
+        let json = serde_json::to_string_pretty(&appearance).unwrap();
+        let json_expected = r#"{
+  "foregroundColor": "rgb(255, 255, 255)",
+  "backgroundColor": "rgb(0, 0, 128)"
+}"#;
+        assert_eq!(json_expected, json);
+    }
+
+    #[test]
+    fn contrast_ratios() {
+        let white = Color::white().unwrap();
+        let black = Color::black().unwrap();
+        assert_eq!(1.0, white.luminance());
+        assert_eq!(0.0, black.luminance());
+        assert_eq!(1.0, white.contrast_ratio(&white));
+
+        // #777 on white is just below AA
+        let gray = Color::from_hex("#777777").unwrap();
+        let ratio = gray.contrast_ratio(&white);
+        assert!((ratio - 4.48).abs() < 0.01, "{}", ratio);
+
+        let appearance = VisualAppearance {
+            label_color: Color::from_hex("#777777"),
+            foreground_color: Color::black(),
+            background_color: Color::white(),
+        };
+        assert_eq!(Some(21.0), appearance.foreground_contrast());
+        assert!(appearance.meets_contrast(CONTRAST_AA_LARGE));
+        assert!(!appearance.meets_contrast(CONTRAST_AA));
+        assert!(appearance.label_foreground_contrast().unwrap() > CONTRAST_AA);
+
+        let appearance = VisualAppearance::default();
+        assert_eq!(None, appearance.foreground_contrast());
+        assert!(appearance.meets_contrast(CONTRAST_AAA));
+    }
 }